@@ -0,0 +1,300 @@
+//! Programmatic construction of instances from points or distance matrices.
+
+use thiserror::Error;
+
+use crate::{
+    instance::{
+        InstanceMetadata, TSPSymInstance,
+        matrix::{FromPoints, Matrix, MatrixSym},
+        node::Node,
+        point::{Point2D, Point3D},
+    },
+    metric::{Metric2D, Metric3D},
+    tsp_lib_spec::{DisplayDataType, EdgeWeightFormat, EdgeWeightType, NodeCoordType, ProblemType},
+};
+
+/// Reasons why an instance cannot be built from the given points or distances.
+#[derive(Error, Debug, Clone)]
+pub enum BuildError {
+    /// The edge weight type is not defined for points of the given number of dimensions.
+    #[error("Edge weight type {edge_weight_type:?} is not defined for {dimensions}D points")]
+    UnsupportedEdgeWeightType {
+        /// The requested edge weight type.
+        edge_weight_type: EdgeWeightType,
+        /// The number of dimensions of the points.
+        dimensions: usize,
+    },
+    /// The distance from `.0` to `.1` differs from the distance from `.1` to `.0`.
+    #[error("The distance from node {} to node {} is not symmetric", .0.0, .1.0)]
+    AsymmetricMatrix(Node, Node),
+    /// The grid size of a toroidal instance is not positive.
+    #[error("Invalid grid size {0}, it has to be positive")]
+    InvalidGridSize(f64),
+}
+
+impl<DistanceContainer: FromPoints> TSPSymInstance<DistanceContainer> {
+    /// Create a new instance from 2D node coordinates.
+    ///
+    /// The distances are computed (in parallel, for bigger instances) using the TSPLIB95 distance
+    /// function of the given edge weight type. The points are also used as display data. `TOR_2D`
+    /// uses the [`DEFAULT_GRID_SIZE`](crate::metric::DEFAULT_GRID_SIZE), see
+    /// [`TSPSymInstance::from_toroidal_points_2d`] for other grid sizes.
+    ///
+    /// Fails if the edge weight type is not defined on 2D coordinates, see [`Metric2D`].
+    pub fn from_points_2d(
+        points: Vec<Point2D>,
+        edge_weight_type: EdgeWeightType,
+    ) -> Result<Self, BuildError> {
+        let metadata = default_metadata(points.len(), edge_weight_type);
+        Self::from_points_2d_and_metadata(points, metadata)
+    }
+
+    /// Create a new `TOR_2D` instance from 2D node coordinates on a torus with the given side
+    /// length.
+    ///
+    /// Fails if the grid size is not positive.
+    pub fn from_toroidal_points_2d(
+        points: Vec<Point2D>,
+        grid_size: f64,
+    ) -> Result<Self, BuildError> {
+        let mut metadata = default_metadata(points.len(), EdgeWeightType::TOR_2D);
+        metadata.grid_size = Some(check_grid_size(grid_size)?);
+        Self::from_points_2d_and_metadata(points, metadata)
+    }
+
+    fn from_points_2d_and_metadata(
+        points: Vec<Point2D>,
+        mut metadata: InstanceMetadata,
+    ) -> Result<Self, BuildError> {
+        let metric = Metric2D::from_metadata(&metadata).ok_or_else(|| {
+            BuildError::UnsupportedEdgeWeightType {
+                edge_weight_type: metadata.edge_weight_type.clone(),
+                dimensions: 2,
+            }
+        })?;
+        let distances = DistanceContainer::from_points_2d(&points, metric);
+
+        metadata.node_coord_type = NodeCoordType::TWOD_COORDS;
+        metadata.display_data_type = Some(DisplayDataType::COORD_DISPLAY);
+
+        Ok(TSPSymInstance::new(distances, metadata).with_display_data(points))
+    }
+
+    /// Create a new instance from 3D node coordinates.
+    ///
+    /// The distances are computed (in parallel, for bigger instances) using the TSPLIB95 distance
+    /// function of the given edge weight type. `TOR_3D` uses the
+    /// [`DEFAULT_GRID_SIZE`](crate::metric::DEFAULT_GRID_SIZE), see
+    /// [`TSPSymInstance::from_toroidal_points_3d`] for other grid sizes.
+    ///
+    /// Fails if the edge weight type is not defined on 3D coordinates, see [`Metric3D`].
+    pub fn from_points_3d(
+        points: Vec<Point3D>,
+        edge_weight_type: EdgeWeightType,
+    ) -> Result<Self, BuildError> {
+        let metadata = default_metadata(points.len(), edge_weight_type);
+        Self::from_points_3d_and_metadata(points, metadata)
+    }
+
+    /// Create a new `TOR_3D` instance from 3D node coordinates on a torus with the given side
+    /// length.
+    ///
+    /// Fails if the grid size is not positive.
+    pub fn from_toroidal_points_3d(
+        points: Vec<Point3D>,
+        grid_size: f64,
+    ) -> Result<Self, BuildError> {
+        let mut metadata = default_metadata(points.len(), EdgeWeightType::TOR_3D);
+        metadata.grid_size = Some(check_grid_size(grid_size)?);
+        Self::from_points_3d_and_metadata(points, metadata)
+    }
+
+    fn from_points_3d_and_metadata(
+        points: Vec<Point3D>,
+        mut metadata: InstanceMetadata,
+    ) -> Result<Self, BuildError> {
+        let metric = Metric3D::from_metadata(&metadata).ok_or_else(|| {
+            BuildError::UnsupportedEdgeWeightType {
+                edge_weight_type: metadata.edge_weight_type.clone(),
+                dimensions: 3,
+            }
+        })?;
+        let distances = DistanceContainer::from_points_3d(&points, metric);
+
+        metadata.node_coord_type = NodeCoordType::THREED_COORDS;
+        metadata.display_data_type = Some(DisplayDataType::NO_DISPLAY);
+
+        Ok(TSPSymInstance::new(distances, metadata))
+    }
+}
+
+impl<D: Copy + PartialEq> TSPSymInstance<Matrix<D>> {
+    /// Create a new instance from an explicitly given distance matrix.
+    ///
+    /// Fails if the matrix is not symmetric.
+    pub fn from_matrix(matrix: Matrix<D>) -> Result<Self, BuildError> {
+        for from in 0..matrix.dimension() {
+            for to in 0..from {
+                if matrix.get_data(Node(from), Node(to)) != matrix.get_data(Node(to), Node(from)) {
+                    return Err(BuildError::AsymmetricMatrix(Node(from), Node(to)));
+                }
+            }
+        }
+
+        let mut metadata = default_metadata(matrix.dimension(), EdgeWeightType::EXPLICIT);
+        metadata.edge_weight_format = Some(EdgeWeightFormat::FULL_MATRIX);
+        Ok(TSPSymInstance::new(matrix, metadata))
+    }
+}
+
+impl<D> TSPSymInstance<MatrixSym<D>> {
+    /// Create a new instance from an explicitly given symmetric distance matrix.
+    pub fn from_matrix(matrix: MatrixSym<D>) -> Self {
+        let mut metadata = default_metadata(matrix.dimension(), EdgeWeightType::EXPLICIT);
+        metadata.edge_weight_format = Some(EdgeWeightFormat::LOWER_DIAG_ROW);
+        TSPSymInstance::new(matrix, metadata)
+    }
+}
+
+impl<DistanceContainer> TSPSymInstance<DistanceContainer> {
+    /// Set the name of the instance.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.metadata.name = name.into();
+        self
+    }
+}
+
+fn check_grid_size(grid_size: f64) -> Result<f64, BuildError> {
+    if grid_size > 0.0 && grid_size.is_finite() {
+        Ok(grid_size)
+    } else {
+        Err(BuildError::InvalidGridSize(grid_size))
+    }
+}
+
+/// Metadata of a symmetric TSP instance without a name, comment or display data.
+fn default_metadata(dimension: usize, edge_weight_type: EdgeWeightType) -> InstanceMetadata {
+    InstanceMetadata {
+        name: String::new(),
+        problem_type: ProblemType::TSP,
+        comment: None,
+        dimension,
+        capacity: None,
+        edge_weight_type,
+        edge_weight_format: None,
+        edge_data_format: None,
+        node_coord_type: NodeCoordType::NO_COORDS,
+        display_data_type: Some(DisplayDataType::NO_DISPLAY),
+        grid_size: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::{
+        distance::{Distance, Distance64},
+        node::Node,
+    };
+
+    fn square() -> Vec<Point2D> {
+        vec![
+            Point2D { x: 0.0, y: 0.0 },
+            Point2D { x: 3.0, y: 0.0 },
+            Point2D { x: 3.0, y: 4.0 },
+            Point2D { x: 0.0, y: 4.0 },
+        ]
+    }
+
+    #[test]
+    fn test_from_points_2d_matrix_and_matrix_sym_agree() {
+        let instance_matrix =
+            TSPSymInstance::<Matrix<Distance>>::from_points_2d(square(), EdgeWeightType::EUC_2D)
+                .unwrap();
+        let instance_sym =
+            TSPSymInstance::<MatrixSym<Distance>>::from_points_2d(square(), EdgeWeightType::EUC_2D)
+                .unwrap();
+
+        assert_eq!(instance_matrix.metadata().dimension, 4);
+        assert_eq!(instance_sym.metadata().dimension, 4);
+        assert_eq!(instance_matrix.display_data(), Some(square().as_slice()));
+        for from in 0..4 {
+            for to in 0..4 {
+                assert_eq!(
+                    instance_matrix
+                        .distance_matrix()
+                        .get_data(Node(from), Node(to)),
+                    instance_sym
+                        .distance_matrix()
+                        .get_data(Node(from), Node(to))
+                );
+            }
+        }
+        assert_eq!(
+            instance_sym.distance_matrix().get_data(Node(0), Node(2)),
+            Distance(5)
+        );
+    }
+
+    #[test]
+    fn test_from_matrix() {
+        let matrix =
+            MatrixSym::new_from_distance_function(3, |from, to| Distance((from.0 + to.0) as i32));
+        let instance = TSPSymInstance::<MatrixSym<Distance>>::from_matrix(matrix).with_name("sum");
+
+        assert_eq!(instance.metadata().name, "sum");
+        assert_eq!(instance.metadata().dimension, 3);
+        assert!(instance.display_data().is_none());
+        assert_eq!(
+            instance.distance_matrix().get_data(Node(2), Node(1)),
+            Distance(3)
+        );
+    }
+
+    #[test]
+    fn test_builders_reject_invalid_input() {
+        assert!(matches!(
+            TSPSymInstance::<MatrixSym<Distance>>::from_points_2d(square(), EdgeWeightType::EUC_3D),
+            Err(BuildError::UnsupportedEdgeWeightType { dimensions: 2, .. })
+        ));
+
+        let mut matrix = Matrix::new_from_dimension_with_value(3, Distance(1));
+        matrix.set_data(Node(2), Node(0), Distance(2));
+        assert!(matches!(
+            TSPSymInstance::<Matrix<Distance>>::from_matrix(matrix),
+            Err(BuildError::AsymmetricMatrix(Node(2), Node(0)))
+        ));
+    }
+
+    #[test]
+    fn test_from_toroidal_points_2d_uses_the_grid_size() {
+        let points = vec![Point2D { x: 1.0, y: 5.0 }, Point2D { x: 9.0, y: 5.0 }];
+        let instance =
+            TSPSymInstance::<MatrixSym<Distance>>::from_toroidal_points_2d(points.clone(), 10.0)
+                .unwrap();
+
+        assert_eq!(instance.metadata().grid_size, Some(10.0));
+        assert_eq!(
+            instance.distance_matrix().get_data(Node(0), Node(1)),
+            Distance(2)
+        );
+        assert!(matches!(
+            TSPSymInstance::<MatrixSym<Distance>>::from_toroidal_points_2d(points, 0.0),
+            Err(BuildError::InvalidGridSize(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_points_2d_with_distance_64() {
+        let instance = TSPSymInstance::<MatrixSym<Distance64>>::from_points_2d(
+            square(),
+            EdgeWeightType::EUC_2D,
+        )
+        .unwrap();
+
+        assert_eq!(
+            instance.distance_matrix().get_data(Node(1), Node(3)),
+            Distance64(5)
+        );
+    }
+}
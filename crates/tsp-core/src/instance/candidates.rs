@@ -0,0 +1,459 @@
+//! Sparse candidate neighbor lists, as used by local search heuristics to restrict the edges they
+//! consider.
+//!
+//! The candidate sets are computed directly from a distance function or from node coordinates, so
+//! no dense distance matrix is required. For 2D coordinates, the edges of the Delaunay
+//! triangulation can be used as well, which gives a sparse candidate graph with a varying number
+//! of candidates per node. For bigger instances, the candidates of different nodes
+//! are computed in parallel using scoped threads.
+
+use std::collections::BinaryHeap;
+
+use crate::{
+    instance::{
+        delaunay::DelaunayTriangulation,
+        distance::{Distance, DistanceValue},
+        kd_tree::{KdMetric, KdTree},
+        matrix::{Matrix, MatrixSym},
+        node::Node,
+        point::Point2D,
+    },
+    metric::Metric,
+};
+
+/// Number of distance evaluations from which on the computation is parallelized.
+// TODO: Add benchmarks to determine optimal parallelism bound
+const PARALLELISM_BOUND: usize = 300_000;
+
+/// Candidate neighbors for every node, sorted by increasing distance.
+///
+/// The candidates are stored in two flat vectors (neighbors and distances), the candidates of node
+/// `i` are at the indices `offsets[i]..offsets[i + 1]`. All constructors except
+/// [`CandidateSet::delaunay`] give every node the same number of candidates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateSet<D = Distance> {
+    dimension: usize,
+    offsets: Vec<usize>,
+    neighbors: Vec<Node>,
+    distances: Vec<D>,
+}
+
+impl<D: DistanceValue> CandidateSet<D> {
+    /// Computes the `k` nearest neighbors of every node with the given distance function.
+    ///
+    /// If the instance has at most `k` nodes, every other node is a candidate. Ties are broken by
+    /// the node index. Takes `O(dimension^2 * log k)` time, but only `O(dimension * k)` memory.
+    pub fn k_nearest(
+        dimension: usize,
+        k: usize,
+        distance_function: impl Fn(Node, Node) -> D + Sync,
+    ) -> Self {
+        let candidates_per_node = k.min(dimension.saturating_sub(1));
+        Self::build(dimension, candidates_per_node, |node, candidates| {
+            let mut nearest = BinaryHeap::with_capacity(candidates_per_node + 1);
+            for other in (0..dimension).map(Node).filter(|&other| other != node) {
+                push_bounded(
+                    &mut nearest,
+                    (distance_function(node, other), other),
+                    candidates_per_node,
+                );
+            }
+            candidates.extend(nearest.into_sorted_vec());
+        })
+    }
+
+    /// Computes the `k` nearest neighbors of every node from the node coordinates.
+    ///
+    /// Uses a [`KdTree`], which takes `O(dimension * log dimension)` time for well distributed
+    /// points instead of the `O(dimension^2)` time of [`CandidateSet::k_nearest`].
+    pub fn k_nearest_points<M: KdMetric + Clone + Sync>(
+        points: &[M::Point],
+        metric: &M,
+        k: usize,
+    ) -> Self
+    where
+        M::Point: Sync,
+    {
+        let dimension = points.len();
+        let tree = KdTree::new(points.to_vec(), metric.clone());
+        Self::build(
+            dimension,
+            k.min(dimension.saturating_sub(1)),
+            |node, candidates| {
+                candidates.extend(
+                    tree.k_nearest_to_node(node, k)
+                        .into_iter()
+                        .map(|(neighbor, distance)| (D::from(distance), neighbor)),
+                );
+            },
+        )
+    }
+
+    /// Computes the `k` nearest neighbors of every node of a distance matrix.
+    pub fn k_nearest_matrix(matrix: &Matrix<D>, k: usize) -> Self {
+        Self::k_nearest(matrix.dimension(), k, |from, to| matrix.get_data(from, to))
+    }
+
+    /// Computes the `k` nearest neighbors of every node of a symmetric distance matrix.
+    pub fn k_nearest_matrix_sym(matrix: &MatrixSym<D>, k: usize) -> Self {
+        Self::k_nearest(matrix.dimension(), k, |from, to| matrix.get_data(from, to))
+    }
+
+    /// Computes quadrant neighbors from 2D node coordinates.
+    ///
+    /// The plane around every node is divided into four quadrants, and the `k / 4` nearest nodes
+    /// of each quadrant become candidates. The remaining slots (e.g. if a quadrant contains too
+    /// few nodes) are filled with the nearest nodes overall, such that every node has `k`
+    /// candidates (or `dimension - 1`, if the instance is smaller). In contrast to
+    /// [`CandidateSet::k_nearest`], this also connects clustered nodes to the nodes around their
+    /// cluster.
+    pub fn quadrant_neighbors<M: Metric<Point = Point2D> + Sync>(
+        points: &[Point2D],
+        metric: &M,
+        k: usize,
+    ) -> Self {
+        let dimension = points.len();
+        let candidates_per_node = k.min(dimension.saturating_sub(1));
+        let per_quadrant = candidates_per_node / 4;
+
+        Self::build(dimension, candidates_per_node, |node, candidates| {
+            let point = &points[node.0];
+            let mut nearest_per_quadrant: [_; 4] =
+                std::array::from_fn(|_| BinaryHeap::with_capacity(per_quadrant + 1));
+            let mut nearest = BinaryHeap::with_capacity(candidates_per_node + 1);
+
+            for (other, other_point) in points.iter().enumerate() {
+                let other = Node(other);
+                if other == node {
+                    continue;
+                }
+                let candidate = (D::from(metric.distance(point, other_point)), other);
+                push_bounded(
+                    &mut nearest_per_quadrant[quadrant(point, other_point)],
+                    candidate,
+                    per_quadrant,
+                );
+                push_bounded(&mut nearest, candidate, candidates_per_node);
+            }
+
+            candidates.extend(nearest_per_quadrant.into_iter().flatten());
+            // The quadrants are disjoint, so only the fill-up may contain duplicates
+            for candidate in nearest.into_sorted_vec() {
+                if candidates.len() == candidates_per_node {
+                    break;
+                }
+                if !candidates.contains(&candidate) {
+                    candidates.push(candidate);
+                }
+            }
+            candidates.sort_unstable();
+        })
+    }
+
+    /// Uses the edges of the Delaunay triangulation of the 2D node coordinates as candidates.
+    ///
+    /// The Delaunay graph contains almost all edges of optimal tours of Euclidean instances, but
+    /// only `O(dimension)` edges, so the number of candidates differs between nodes (six on
+    /// average). The candidates are symmetric, i.e. `contains(from, to) == contains(to, from)`.
+    /// Nodes at the same position are candidates of each other and share the candidates of their
+    /// position.
+    pub fn delaunay<M: Metric<Point = Point2D>>(points: &[Point2D], metric: &M) -> Self {
+        let dimension = points.len();
+        let triangulation = DelaunayTriangulation::new(points);
+
+        // All nodes at the position of each representative
+        let mut at_position = vec![Vec::new(); dimension];
+        for node in (0..dimension).map(Node) {
+            at_position[triangulation.representative(node).0].push(node);
+        }
+        let mut adjacent_positions = vec![Vec::new(); dimension];
+        for edge in triangulation.edges() {
+            adjacent_positions[edge.from.0].push(edge.to);
+            adjacent_positions[edge.to.0].push(edge.from);
+        }
+
+        let mut offsets = Vec::with_capacity(dimension + 1);
+        let mut neighbors = Vec::with_capacity(6 * dimension);
+        let mut distances = Vec::with_capacity(6 * dimension);
+        let mut candidates = Vec::new();
+        offsets.push(0);
+        for node in (0..dimension).map(Node) {
+            let position = triangulation.representative(node);
+            let same_position = at_position[position.0].iter();
+            let adjacent = adjacent_positions[position.0]
+                .iter()
+                .flat_map(|adjacent| &at_position[adjacent.0]);
+
+            candidates.clear();
+            candidates.extend(
+                same_position
+                    .chain(adjacent)
+                    .filter(|&&other| other != node)
+                    .map(|&other| {
+                        let distance = metric.distance(&points[node.0], &points[other.0]);
+                        (D::from(distance), other)
+                    }),
+            );
+            candidates.sort_unstable();
+
+            neighbors.extend(candidates.iter().map(|&(_, other)| other));
+            distances.extend(candidates.iter().map(|&(distance, _)| distance));
+            offsets.push(neighbors.len());
+        }
+
+        Self {
+            dimension,
+            offsets,
+            neighbors,
+            distances,
+        }
+    }
+
+    /// Builds the candidate set by calling `compute_candidates` for every node, which has to push
+    /// exactly `candidates_per_node` candidates sorted by increasing distance.
+    fn build(
+        dimension: usize,
+        candidates_per_node: usize,
+        compute_candidates: impl Fn(Node, &mut Vec<(D, Node)>) + Sync,
+    ) -> Self {
+        let mut neighbors = vec![Node(0); dimension * candidates_per_node];
+        let mut distances = vec![D::ZERO; dimension * candidates_per_node];
+
+        let compute_chunk = |first_node: usize, neighbors: &mut [Node], distances: &mut [D]| {
+            let mut candidates = Vec::with_capacity(candidates_per_node);
+            let nodes = neighbors
+                .chunks_mut(candidates_per_node)
+                .zip(distances.chunks_mut(candidates_per_node));
+            for (index, (node_neighbors, node_distances)) in nodes.enumerate() {
+                candidates.clear();
+                compute_candidates(Node(first_node + index), &mut candidates);
+                debug_assert_eq!(candidates.len(), candidates_per_node);
+                for ((neighbor, distance), &(candidate_distance, candidate)) in node_neighbors
+                    .iter_mut()
+                    .zip(node_distances.iter_mut())
+                    .zip(candidates.iter())
+                {
+                    *neighbor = candidate;
+                    *distance = candidate_distance;
+                }
+            }
+        };
+
+        if candidates_per_node == 0 {
+            // Nothing to compute, and chunks of size zero are not allowed
+        } else if dimension * dimension < PARALLELISM_BOUND {
+            compute_chunk(0, &mut neighbors, &mut distances);
+        } else {
+            let nthreads = std::thread::available_parallelism().unwrap();
+            let nodes_per_thread = dimension.div_ceil(nthreads.get());
+            let chunk_size = nodes_per_thread * candidates_per_node;
+
+            std::thread::scope(|scope| {
+                let chunks = neighbors
+                    .chunks_mut(chunk_size)
+                    .zip(distances.chunks_mut(chunk_size));
+                for (index, (neighbors_chunk, distances_chunk)) in chunks.enumerate() {
+                    let compute_chunk = &compute_chunk;
+                    scope.spawn(move || {
+                        compute_chunk(index * nodes_per_thread, neighbors_chunk, distances_chunk)
+                    });
+                }
+            });
+        }
+
+        Self {
+            dimension,
+            offsets: (0..=dimension)
+                .map(|node| node * candidates_per_node)
+                .collect(),
+            neighbors,
+            distances,
+        }
+    }
+}
+
+impl<D: Copy> CandidateSet<D> {
+    /// Returns the number of nodes.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the number of candidates of the node.
+    pub fn candidate_count(&self, node: Node) -> usize {
+        self.range(node).len()
+    }
+
+    /// Returns the total number of candidates of all nodes.
+    pub fn total_candidates(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Returns the candidate neighbors of the node, sorted by increasing distance.
+    pub fn candidates(&self, node: Node) -> &[Node] {
+        &self.neighbors[self.range(node)]
+    }
+
+    /// Returns the distances to the candidate neighbors of the node, in the same order as
+    /// [`CandidateSet::candidates`].
+    pub fn candidate_distances(&self, node: Node) -> &[D] {
+        &self.distances[self.range(node)]
+    }
+
+    /// Iterates over the candidate neighbors of the node together with their distances.
+    pub fn iter(&self, node: Node) -> impl Iterator<Item = (Node, D)> + '_ {
+        self.candidates(node)
+            .iter()
+            .copied()
+            .zip(self.candidate_distances(node).iter().copied())
+    }
+
+    /// Returns whether `to` is a candidate neighbor of `from`.
+    ///
+    /// Candidate sets are not necessarily symmetric, i.e. this may differ from
+    /// `contains(to, from)`.
+    pub fn contains(&self, from: Node, to: Node) -> bool {
+        self.candidates(from).contains(&to)
+    }
+
+    fn range(&self, node: Node) -> std::ops::Range<usize> {
+        assert!(node.0 < self.dimension, "Node {} out of range", node.0);
+        self.offsets[node.0]..self.offsets[node.0 + 1]
+    }
+}
+
+/// Pushes the candidate into the max-heap and removes the farthest candidate if the heap has more
+/// than `bound` elements afterwards.
+#[inline(always)]
+fn push_bounded<D: Ord>(heap: &mut BinaryHeap<(D, Node)>, candidate: (D, Node), bound: usize) {
+    if heap.len() < bound {
+        heap.push(candidate);
+    } else if heap.peek().is_some_and(|farthest| candidate < *farthest) {
+        heap.pop();
+        heap.push(candidate);
+    }
+}
+
+/// Returns the quadrant (0 to 3, counter-clockwise starting with the upper right one) of `other`
+/// relative to `point`. Points on the axes belong to the quadrant counter-clockwise of them.
+#[inline(always)]
+fn quadrant(point: &Point2D, other: &Point2D) -> usize {
+    match (other.x >= point.x, other.y >= point.y) {
+        (true, true) => 0,
+        (false, true) => 1,
+        (false, false) => 2,
+        (true, false) => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Euclidean2D;
+
+    fn grid(side: usize) -> Vec<Point2D> {
+        (0..side * side)
+            .map(|index| Point2D {
+                x: (index % side) as f64 * 10.0,
+                y: (index / side) as f64 * 10.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_k_nearest_on_a_line() {
+        let candidates: CandidateSet = CandidateSet::k_nearest(6, 2, |from, to| {
+            Distance((from.0 as i32 - to.0 as i32).abs())
+        });
+
+        assert_eq!(candidates.dimension(), 6);
+        assert_eq!(candidates.candidates(Node(0)), &[Node(1), Node(2)]);
+        assert_eq!(candidates.candidates(Node(3)), &[Node(2), Node(4)]);
+        assert_eq!(
+            candidates.iter(Node(5)).collect::<Vec<_>>(),
+            vec![(Node(4), Distance(1)), (Node(3), Distance(2))]
+        );
+        assert!(candidates.contains(Node(3), Node(4)));
+        assert!(!candidates.contains(Node(0), Node(3)));
+    }
+
+    #[test]
+    fn test_k_nearest_bigger_than_dimension() {
+        let points = grid(2);
+        let candidates: CandidateSet = CandidateSet::k_nearest_points(&points, &Euclidean2D, 10);
+
+        assert_eq!(candidates.candidate_count(Node(0)), 3);
+        assert_eq!(candidates.candidates(Node(0)), &[Node(1), Node(2), Node(3)]);
+    }
+
+    #[test]
+    fn test_k_nearest_matches_matrix_in_parallel() {
+        // Big enough to be computed in parallel
+        let points = grid(30);
+        let matrix: MatrixSym<Distance> =
+            crate::instance::matrix::FromPoints::from_metric(&points, Euclidean2D);
+
+        let from_points = CandidateSet::k_nearest_points(&points, &Euclidean2D, 5);
+        let from_matrix = CandidateSet::k_nearest_matrix_sym(&matrix, 5);
+        assert_eq!(from_points, from_matrix);
+        assert_eq!(
+            from_points.candidates(Node(31)),
+            &[Node(1), Node(30), Node(32), Node(61), Node(0)]
+        );
+    }
+
+    #[test]
+    fn test_quadrant_neighbors_reach_beyond_cluster() {
+        // A dense cluster around the origin and a single node far to the left
+        let mut points = (0..8)
+            .map(|index| Point2D {
+                x: index as f64,
+                y: (index % 2) as f64,
+            })
+            .collect::<Vec<_>>();
+        points.push(Point2D { x: -100.0, y: 0.0 });
+
+        let nearest: CandidateSet = CandidateSet::k_nearest_points(&points, &Euclidean2D, 4);
+        let quadrant: CandidateSet = CandidateSet::quadrant_neighbors(&points, &Euclidean2D, 4);
+
+        assert!(!nearest.contains(Node(0), Node(8)));
+        assert!(quadrant.contains(Node(0), Node(8)));
+        assert_eq!(quadrant.total_candidates(), 4 * points.len());
+        for node in (0..points.len()).map(Node) {
+            let distances = quadrant.candidate_distances(node);
+            assert!(distances.is_sorted());
+            assert!(!quadrant.contains(node, node));
+        }
+    }
+
+    #[test]
+    fn test_delaunay_with_duplicates() {
+        // A 3x3 grid with the center node duplicated
+        let mut points = grid(3);
+        points.push(points[4]);
+        let candidates: CandidateSet = CandidateSet::delaunay(&points, &Euclidean2D);
+
+        // The duplicate comes first, followed by the four direct neighbors of the center
+        assert_eq!(
+            candidates.candidates(Node(4))[..5],
+            [9, 1, 3, 5, 7].map(Node)
+        );
+        assert_eq!(
+            candidates.candidates(Node(9))[..5],
+            [4, 1, 3, 5, 7].map(Node)
+        );
+        assert_eq!(
+            candidates.candidate_count(Node(4)),
+            candidates.candidate_count(Node(9))
+        );
+        for from in (0..points.len()).map(Node) {
+            assert!(candidates.candidate_distances(from).is_sorted());
+            assert!(!candidates.contains(from, from));
+            for &to in candidates.candidates(from) {
+                assert!(candidates.contains(to, from));
+            }
+        }
+        // The corners are not adjacent to each other
+        assert!(!candidates.contains(Node(0), Node(8)));
+        assert!(!candidates.contains(Node(2), Node(6)));
+    }
+}
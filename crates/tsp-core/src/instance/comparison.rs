@@ -0,0 +1,270 @@
+//! Comparison of tours, e.g. of a heuristic tour with an optimal one.
+//!
+//! All comparisons run in linear time in the number of nodes: The neighbors of every node in a
+//! tour are looked up in an array instead of searching or hashing the edges.
+
+use crate::instance::{
+    distance::DistanceValue,
+    edge::UnEdge,
+    node::Node,
+    tour::{Tour, TourError},
+};
+
+/// The edges two tours share and the edges only one of them contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TourComparison {
+    /// The edges contained in both tours, in the visiting order of the first tour.
+    pub shared: Vec<UnEdge>,
+    /// The edges only contained in the first tour, in its visiting order.
+    pub only_first: Vec<UnEdge>,
+    /// The edges only contained in the second tour, in its visiting order.
+    pub only_second: Vec<UnEdge>,
+}
+
+impl TourComparison {
+    /// Returns the bond distance, i.e. the number of edges of one tour missing in the other.
+    pub fn bond_distance(&self) -> usize {
+        self.only_first.len()
+    }
+}
+
+impl<D: DistanceValue> Tour<D> {
+    /// Splits the edges of both tours into shared edges and edges only one tour contains.
+    ///
+    /// Returns an error if the tours visit a different number of nodes.
+    pub fn compare<E: DistanceValue>(&self, other: &Tour<E>) -> Result<TourComparison, TourError> {
+        other.check_dimension(self.dimension())?;
+        let (shared, only_first) = partition_edges(self, &neighbors(other));
+        let (_, only_second) = partition_edges(other, &neighbors(self));
+        Ok(TourComparison {
+            shared,
+            only_first,
+            only_second,
+        })
+    }
+
+    /// Returns the bond distance to the other tour, i.e. the number of edges of this tour that
+    /// the other tour does not contain. Identical tours have distance 0.
+    ///
+    /// Returns an error if the tours visit a different number of nodes.
+    pub fn bond_distance<E: DistanceValue>(&self, other: &Tour<E>) -> Result<usize, TourError> {
+        other.check_dimension(self.dimension())?;
+        let other_neighbors = neighbors(other);
+        Ok(self
+            .edges()
+            .filter(|edge| !contains(&other_neighbors, edge))
+            .count())
+    }
+
+    /// Returns how much more expensive this tour is than the reference cost, in percent.
+    pub fn gap(&self, reference: D) -> f64 {
+        relative_gap(self.cost().to_f64(), reference.to_f64())
+    }
+}
+
+/// Returns how much bigger the cost is than the reference cost, in percent.
+///
+/// E.g. a tour of cost 105 has a gap of 5% to an optimal tour of cost 100.
+pub fn relative_gap(cost: f64, reference: f64) -> f64 {
+    100.0 * (cost - reference) / reference
+}
+
+/// Counts how often every edge occurs in a set of tours, e.g. to find the backbone of the edges
+/// that (almost) all good tours share.
+#[derive(Debug, Clone)]
+pub struct EdgeFrequencies {
+    /// For every node, the bigger neighbors it is connected to and the number of tours using the
+    /// edge. Tours of similar quality share most edges, so these lists stay short.
+    counts: Vec<Vec<(Node, usize)>>,
+    tours: usize,
+}
+
+impl EdgeFrequencies {
+    /// Creates empty edge frequencies for tours of the given dimension.
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            counts: vec![Vec::new(); dimension],
+            tours: 0,
+        }
+    }
+
+    /// Counts the edges of all tours.
+    ///
+    /// Returns an error if a tour does not visit `dimension` nodes.
+    pub fn from_tours<'a, D: DistanceValue>(
+        dimension: usize,
+        tours: impl IntoIterator<Item = &'a Tour<D>>,
+    ) -> Result<Self, TourError> {
+        let mut frequencies = Self::new(dimension);
+        for tour in tours {
+            frequencies.add(tour)?;
+        }
+        Ok(frequencies)
+    }
+
+    /// Counts the edges of the tour.
+    ///
+    /// Returns an error if the tour does not visit `dimension` nodes.
+    pub fn add<D: DistanceValue>(&mut self, tour: &Tour<D>) -> Result<(), TourError> {
+        tour.check_dimension(self.dimension())?;
+        for edge in tour.edges() {
+            let (smaller, bigger) = ordered(&edge);
+            let counts = &mut self.counts[smaller.0];
+            match counts.iter_mut().find(|(neighbor, _)| *neighbor == bigger) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((bigger, 1)),
+            }
+        }
+        self.tours += 1;
+        Ok(())
+    }
+
+    /// Returns the number of nodes.
+    pub fn dimension(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the number of counted tours.
+    pub fn tours(&self) -> usize {
+        self.tours
+    }
+
+    /// Returns the number of tours containing the edge.
+    pub fn count(&self, edge: UnEdge) -> usize {
+        let (smaller, bigger) = ordered(&edge);
+        self.counts[smaller.0]
+            .iter()
+            .find(|(neighbor, _)| *neighbor == bigger)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    /// Returns the fraction of tours containing the edge, or 0 if no tour was counted.
+    pub fn frequency(&self, edge: UnEdge) -> f64 {
+        if self.tours == 0 {
+            0.0
+        } else {
+            self.count(edge) as f64 / self.tours as f64
+        }
+    }
+
+    /// Iterates over all edges contained in at least one tour and their counts, with the smaller
+    /// node first.
+    pub fn edges(&self) -> impl Iterator<Item = (UnEdge, usize)> + '_ {
+        self.counts.iter().enumerate().flat_map(|(node, counts)| {
+            counts
+                .iter()
+                .map(move |&(neighbor, count)| (UnEdge::new(Node(node), neighbor), count))
+        })
+    }
+
+    /// Returns the edges contained in at least the given fraction of the tours, sorted.
+    ///
+    /// A fraction of 1 returns the edges shared by all tours.
+    pub fn backbone(&self, min_frequency: f64) -> Vec<UnEdge> {
+        let min_count = (min_frequency * self.tours as f64).ceil().max(1.0) as usize;
+        let mut edges = self
+            .edges()
+            .filter(|&(_, count)| count >= min_count)
+            .map(|(edge, _)| edge)
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+}
+
+/// Returns the two neighbors of every node in the tour.
+fn neighbors<D: DistanceValue>(tour: &Tour<D>) -> Vec<[Node; 2]> {
+    let mut neighbors = vec![[Node(0); 2]; tour.dimension()];
+    for edge in tour.edges() {
+        neighbors[edge.from.0][1] = edge.to;
+        neighbors[edge.to.0][0] = edge.from;
+    }
+    neighbors
+}
+
+fn contains(neighbors: &[[Node; 2]], edge: &UnEdge) -> bool {
+    neighbors[edge.from.0].contains(&edge.to)
+}
+
+fn partition_edges<D: DistanceValue>(
+    tour: &Tour<D>,
+    other_neighbors: &[[Node; 2]],
+) -> (Vec<UnEdge>, Vec<UnEdge>) {
+    tour.edges()
+        .partition(|edge| contains(other_neighbors, edge))
+}
+
+fn ordered(edge: &UnEdge) -> (Node, Node) {
+    if edge.from <= edge.to {
+        (edge.from, edge.to)
+    } else {
+        (edge.to, edge.from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::distance::Distance;
+
+    fn tour(indices: &[usize], cost: i32) -> Tour {
+        Tour::new(indices.iter().copied().map(Node).collect(), Distance(cost)).unwrap()
+    }
+
+    #[test]
+    fn test_compare_tours() {
+        let first = tour(&[0, 1, 2, 3, 4, 5], 100);
+        // A 2-opt move of the first tour, replacing (1, 2) and (4, 5) by (1, 4) and (2, 5)
+        let second = tour(&[0, 1, 4, 3, 2, 5], 90);
+
+        let comparison = first.compare(&second).unwrap();
+        assert_eq!(comparison.shared.len(), 4);
+        assert_eq!(
+            comparison.only_first,
+            vec![UnEdge::from((1, 2)), UnEdge::from((4, 5))]
+        );
+        assert_eq!(
+            comparison.only_second,
+            vec![UnEdge::from((1, 4)), UnEdge::from((2, 5))]
+        );
+        assert_eq!(comparison.bond_distance(), 2);
+        assert_eq!(first.bond_distance(&second), Ok(2));
+        assert_eq!(second.bond_distance(&first.clone()), Ok(2));
+        assert_eq!(first.bond_distance(&first), Ok(0));
+        assert_eq!(
+            first.compare(&tour(&[0, 1, 2], 0)),
+            Err(TourError::DimensionMismatch {
+                expected: 6,
+                found: 3
+            })
+        );
+
+        assert!((first.gap(Distance(90)) - 100.0 / 9.0).abs() < 1e-9);
+        assert_eq!(second.gap(Distance(90)), 0.0);
+    }
+
+    #[test]
+    fn test_edge_frequencies() {
+        let tours = [
+            tour(&[0, 1, 2, 3, 4, 5], 0),
+            tour(&[0, 1, 4, 3, 2, 5], 0),
+            tour(&[0, 1, 2, 3, 5, 4], 0),
+        ];
+        let frequencies = EdgeFrequencies::from_tours(6, &tours).unwrap();
+
+        assert_eq!(frequencies.tours(), 3);
+        assert_eq!(frequencies.count(UnEdge::from((1, 0))), 3);
+        assert_eq!(frequencies.count(UnEdge::from((4, 5))), 2);
+        assert_eq!(frequencies.count(UnEdge::from((0, 3))), 0);
+        assert!((frequencies.frequency(UnEdge::from((1, 2))) - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            frequencies.edges().map(|(_, count)| count).sum::<usize>(),
+            3 * 6
+        );
+        assert_eq!(
+            frequencies.backbone(1.0),
+            vec![UnEdge::from((0, 1)), UnEdge::from((2, 3))]
+        );
+        assert_eq!(frequencies.backbone(0.5).len(), 6);
+    }
+}
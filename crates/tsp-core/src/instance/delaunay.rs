@@ -0,0 +1,651 @@
+//! Delaunay triangulation of 2D node coordinates.
+//!
+//! The edges of the Delaunay triangulation contain almost all edges of optimal tours of Euclidean
+//! instances, while there are at most `3 * dimension` of them. This makes them a good sparse
+//! candidate graph, see [`CandidateSet::delaunay`](crate::instance::candidates::CandidateSet).
+//!
+//! The triangulation uses the sweep-hull algorithm of the Delaunator library: Points are added in
+//! the order of their distance to a seed triangle, connecting each point to the visible part of
+//! the convex hull and restoring the Delaunay property by flipping edges. Takes `O(n log n)` time
+//! in practice.
+//!
+//! Duplicate points are not part of the triangulation, instead they are represented by the first
+//! node at the same position (see [`DelaunayTriangulation::representative`]). If all points are
+//! collinear, there are no triangles and the edges connect consecutive points along the line.
+
+use crate::instance::{edge::UnEdge, node::Node, point::Point2D};
+
+/// Marks a missing half-edge or hull entry.
+const EMPTY: usize = usize::MAX;
+
+/// Coordinates closer than this are considered equal.
+const EPSILON: f64 = f64::EPSILON * 2.0;
+
+/// The Delaunay triangulation of a set of 2D points.
+#[derive(Debug, Clone)]
+pub struct DelaunayTriangulation {
+    /// The triangles, each given by three nodes in clockwise order.
+    triangles: Vec<[Node; 3]>,
+    /// The edges of the triangulation (or of the line, if all points are collinear), between
+    /// representatives only.
+    edges: Vec<UnEdge>,
+    /// The representative of every node, i.e. the node itself or the first node at its position.
+    representatives: Vec<Node>,
+}
+
+impl DelaunayTriangulation {
+    /// Computes the Delaunay triangulation of the points, node `i` being at `points[i]`.
+    pub fn new(points: &[Point2D]) -> Self {
+        let representatives = find_representatives(points);
+        let distinct = (0..points.len())
+            .filter(|&index| representatives[index].0 == index)
+            .collect::<Vec<_>>();
+
+        let triangles = match Triangulator::new(points, &distinct) {
+            Some(triangulator) => triangulator.triangulate(),
+            None => Vec::new(),
+        };
+        let triangles = triangles
+            .as_chunks::<3>()
+            .0
+            .iter()
+            .map(|triangle| triangle.map(Node))
+            .collect::<Vec<_>>();
+
+        let mut edges = if triangles.is_empty() {
+            collinear_edges(points, &distinct)
+        } else {
+            triangles
+                .iter()
+                .flat_map(|&[a, b, c]| [UnEdge::new(a, b), UnEdge::new(b, c), UnEdge::new(c, a)])
+                .collect()
+        };
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut triangulation = Self {
+            triangles,
+            edges,
+            representatives,
+        };
+        triangulation.attach_skipped_points(points);
+        triangulation
+    }
+
+    /// Returns the triangles, each given by three nodes in clockwise order.
+    pub fn triangles(&self) -> &[[Node; 3]] {
+        &self.triangles
+    }
+
+    /// Returns the edges of the triangulation, each edge once.
+    ///
+    /// Duplicate points are represented by their [`DelaunayTriangulation::representative`] and
+    /// are not incident to any edge.
+    pub fn edges(&self) -> &[UnEdge] {
+        &self.edges
+    }
+
+    /// Returns the node representing the position of the node in the triangulation, i.e. the node
+    /// with the smallest index at the same position.
+    pub fn representative(&self, node: Node) -> Node {
+        self.representatives[node.0]
+    }
+
+    /// The sweep-hull algorithm skips points that are not strictly outside of the hull due to
+    /// rounding errors. Connects each of them (if any) to its nearest triangulated node, such that
+    /// the graph stays connected.
+    fn attach_skipped_points(&mut self, points: &[Point2D]) {
+        let mut has_edge = vec![false; points.len()];
+        for edge in &self.edges {
+            has_edge[edge.from.0] = true;
+            has_edge[edge.to.0] = true;
+        }
+        let is_skipped = |index: usize| self.representatives[index].0 == index && !has_edge[index];
+        let skipped = (0..points.len())
+            .filter(|&index| is_skipped(index))
+            .collect::<Vec<_>>();
+        if self.edges.is_empty() {
+            // At most one distinct point, there is nothing to connect to
+            return;
+        }
+
+        for index in skipped {
+            let nearest = (0..points.len())
+                .filter(|&other| has_edge[other])
+                .min_by(|&a, &b| {
+                    distance_squared(&points[index], &points[a])
+                        .total_cmp(&distance_squared(&points[index], &points[b]))
+                })
+                .expect("At least one triangulated node exists");
+            self.edges.push(UnEdge::new(Node(nearest), Node(index)));
+        }
+        self.edges.sort_unstable();
+    }
+}
+
+/// Maps every point to the first point at the same position.
+fn find_representatives(points: &[Point2D]) -> Vec<Node> {
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_unstable_by(|&a, &b| {
+        (points[a].x.total_cmp(&points[b].x))
+            .then(points[a].y.total_cmp(&points[b].y))
+            .then(a.cmp(&b))
+    });
+
+    let mut representatives = (0..points.len()).map(Node).collect::<Vec<_>>();
+    for pair in order.windows(2) {
+        if points[pair[0]] == points[pair[1]] {
+            representatives[pair[1]] = representatives[pair[0]];
+        }
+    }
+    representatives
+}
+
+/// Connects consecutive points along the line, for the case that all points are collinear.
+fn collinear_edges(points: &[Point2D], distinct: &[usize]) -> Vec<UnEdge> {
+    let mut order = distinct.to_vec();
+    order.sort_unstable_by(|&a, &b| {
+        (points[a].x.total_cmp(&points[b].x)).then(points[a].y.total_cmp(&points[b].y))
+    });
+    order
+        .windows(2)
+        .map(|pair| UnEdge::new(Node(pair[0]), Node(pair[1])))
+        .collect()
+}
+
+#[inline(always)]
+fn distance_squared(a: &Point2D, b: &Point2D) -> f64 {
+    (a.x - b.x).powi(2) + (a.y - b.y).powi(2)
+}
+
+/// Returns whether `r` lies to the left of the directed line from `p` to `q`, i.e. whether the
+/// three points are in counterclockwise order.
+#[inline(always)]
+fn orient(p: &Point2D, q: &Point2D, r: &Point2D) -> bool {
+    (q.y - p.y) * (r.x - q.x) - (q.x - p.x) * (r.y - q.y) < 0.0
+}
+
+/// Returns the offset of the circumcenter of the triangle `a`, `b`, `c` relative to `a`.
+#[inline(always)]
+fn circumdelta(a: &Point2D, b: &Point2D, c: &Point2D) -> (f64, f64) {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (ex, ey) = (c.x - a.x, c.y - a.y);
+    let bl = dx * dx + dy * dy;
+    let cl = ex * ex + ey * ey;
+    let d = 0.5 / (dx * ey - dy * ex);
+    ((ey * bl - dy * cl) * d, (dx * cl - ex * bl) * d)
+}
+
+#[inline(always)]
+fn circumradius_squared(a: &Point2D, b: &Point2D, c: &Point2D) -> f64 {
+    let (x, y) = circumdelta(a, b, c);
+    x * x + y * y
+}
+
+#[inline(always)]
+fn circumcenter(a: &Point2D, b: &Point2D, c: &Point2D) -> Point2D {
+    let (x, y) = circumdelta(a, b, c);
+    Point2D {
+        x: a.x + x,
+        y: a.y + y,
+    }
+}
+
+/// Returns whether `p` lies inside the circumcircle of the clockwise triangle `a`, `b`, `c`.
+#[inline(always)]
+fn in_circle(a: &Point2D, b: &Point2D, c: &Point2D, p: &Point2D) -> bool {
+    let (dx, dy) = (a.x - p.x, a.y - p.y);
+    let (ex, ey) = (b.x - p.x, b.y - p.y);
+    let (fx, fy) = (c.x - p.x, c.y - p.y);
+    let ap = dx * dx + dy * dy;
+    let bp = ex * ex + ey * ey;
+    let cp = fx * fx + fy * fy;
+    dx * (ey * cp - bp * fy) - dy * (ex * cp - bp * fx) + ap * (ex * fy - ey * fx) < 0.0
+}
+
+#[inline(always)]
+fn next_half_edge(edge: usize) -> usize {
+    if edge % 3 == 2 { edge - 2 } else { edge + 1 }
+}
+
+#[inline(always)]
+fn previous_half_edge(edge: usize) -> usize {
+    if edge % 3 == 0 { edge + 2 } else { edge - 1 }
+}
+
+/// State of the sweep-hull algorithm.
+struct Triangulator<'a> {
+    points: &'a [Point2D],
+    /// The distinct points in the order they are added.
+    order: Vec<usize>,
+    /// Point indices of the triangles, three per triangle.
+    triangles: Vec<usize>,
+    /// The opposite half-edge of every half-edge, or `EMPTY` on the hull.
+    half_edges: Vec<usize>,
+    /// The circumcenter of the seed triangle, the center of the hash of the hull.
+    center: Point2D,
+    hull_start: usize,
+    hull_previous: Vec<usize>,
+    hull_next: Vec<usize>,
+    /// The half-edge of the triangle adjacent to each hull edge.
+    hull_triangle: Vec<usize>,
+    /// Hull nodes bucketed by their pseudo angle around the center, to find visible edges fast.
+    hull_hash: Vec<usize>,
+    /// Reused stack of half-edges to legalize.
+    edge_stack: Vec<usize>,
+}
+
+impl<'a> Triangulator<'a> {
+    /// Finds the seed triangle and initializes the hull with it. Returns `None` if there are
+    /// fewer than three points or all points are collinear.
+    fn new(points: &'a [Point2D], distinct: &[usize]) -> Option<Self> {
+        if distinct.len() < 3 {
+            return None;
+        }
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &index in distinct {
+            let point = &points[index];
+            min_x = min_x.min(point.x);
+            min_y = min_y.min(point.y);
+            max_x = max_x.max(point.x);
+            max_y = max_y.max(point.y);
+        }
+        let bounding_box_center = Point2D {
+            x: (min_x + max_x) / 2.0,
+            y: (min_y + max_y) / 2.0,
+        };
+
+        // The seed triangle consists of the point closest to the center, its nearest neighbor and
+        // the point forming the smallest circumcircle with them
+        let closest_to = |target: &Point2D, exclude: &[usize]| {
+            distinct
+                .iter()
+                .copied()
+                .filter(|index| !exclude.contains(index))
+                .min_by(|&a, &b| {
+                    distance_squared(target, &points[a])
+                        .total_cmp(&distance_squared(target, &points[b]))
+                })
+                .expect("There are at least three distinct points")
+        };
+        let i0 = closest_to(&bounding_box_center, &[]);
+        let i1 = closest_to(&points[i0], &[i0]);
+        let (_, mut i2) = distinct
+            .iter()
+            .copied()
+            .filter(|&index| index != i0 && index != i1)
+            .map(|index| {
+                let radius = circumradius_squared(&points[i0], &points[i1], &points[index]);
+                (radius, index)
+            })
+            .filter(|(radius, _)| radius.is_finite())
+            .min_by(|a, b| a.0.total_cmp(&b.0))?;
+        let mut i1 = i1;
+        if orient(&points[i0], &points[i1], &points[i2]) {
+            std::mem::swap(&mut i1, &mut i2);
+        }
+
+        let center = circumcenter(&points[i0], &points[i1], &points[i2]);
+        let mut order = distinct.to_vec();
+        order.sort_unstable_by(|&a, &b| {
+            distance_squared(&points[a], &center)
+                .total_cmp(&distance_squared(&points[b], &center))
+                .then(a.cmp(&b))
+        });
+
+        let hash_size = (distinct.len() as f64).sqrt().ceil() as usize;
+        let mut triangulator = Self {
+            points,
+            order,
+            triangles: Vec::with_capacity(6 * distinct.len()),
+            half_edges: Vec::with_capacity(6 * distinct.len()),
+            center,
+            hull_start: i0,
+            hull_previous: vec![EMPTY; points.len()],
+            hull_next: vec![EMPTY; points.len()],
+            hull_triangle: vec![EMPTY; points.len()],
+            hull_hash: vec![EMPTY; hash_size],
+            edge_stack: Vec::new(),
+        };
+
+        triangulator.hull_next[i0] = i1;
+        triangulator.hull_previous[i2] = i1;
+        triangulator.hull_next[i1] = i2;
+        triangulator.hull_previous[i0] = i2;
+        triangulator.hull_next[i2] = i0;
+        triangulator.hull_previous[i1] = i0;
+        triangulator.hull_triangle[i0] = 0;
+        triangulator.hull_triangle[i1] = 1;
+        triangulator.hull_triangle[i2] = 2;
+        for index in [i0, i1, i2] {
+            triangulator.hash_edge(index);
+        }
+        triangulator.add_triangle([i0, i1, i2], [EMPTY, EMPTY, EMPTY]);
+
+        Some(triangulator)
+    }
+
+    /// Adds the remaining points and returns the triangles.
+    fn triangulate(mut self) -> Vec<usize> {
+        let seeds = [self.triangles[0], self.triangles[1], self.triangles[2]];
+        let order = std::mem::take(&mut self.order);
+        for (position, &index) in order.iter().enumerate() {
+            if seeds.contains(&index) {
+                continue;
+            }
+            let point = &self.points[index];
+            // Skip near-duplicates, they would create degenerate triangles
+            if position > 0 && nearly_equal(point, &self.points[order[position - 1]]) {
+                continue;
+            }
+            self.add_point(index);
+        }
+        self.triangles
+    }
+
+    fn add_point(&mut self, index: usize) {
+        let points = self.points;
+        let point = &points[index];
+
+        // Find a visible edge of the hull, starting at the hull node with the closest pseudo angle
+        let key = self.hash_key(point);
+        let mut start = EMPTY;
+        for offset in 0..self.hull_hash.len() {
+            start = self.hull_hash[(key + offset) % self.hull_hash.len()];
+            if start != EMPTY && self.hull_next[start] != start {
+                break;
+            }
+        }
+        start = self.hull_previous[start];
+        let mut edge = start;
+        loop {
+            let next = self.hull_next[edge];
+            if orient(point, &points[edge], &points[next]) {
+                break;
+            }
+            edge = next;
+            if edge == start {
+                // Not outside of the hull due to rounding errors, skip the point
+                return;
+            }
+        }
+        let walk_back = edge == start;
+
+        // Add the first triangle from the point
+        let triangle = self.add_triangle(
+            [edge, index, self.hull_next[edge]],
+            [EMPTY, EMPTY, self.hull_triangle[edge]],
+        );
+        self.hull_triangle[index] = self.legalize(triangle + 2);
+        self.hull_triangle[edge] = triangle;
+
+        // Walk forward through the hull, adding more triangles and flipping recursively
+        let mut next = self.hull_next[edge];
+        loop {
+            let after = self.hull_next[next];
+            if !orient(point, &points[next], &points[after]) {
+                break;
+            }
+            let triangle = self.add_triangle(
+                [next, index, after],
+                [self.hull_triangle[index], EMPTY, self.hull_triangle[next]],
+            );
+            self.hull_triangle[index] = self.legalize(triangle + 2);
+            // Mark as removed from the hull
+            self.hull_next[next] = next;
+            next = after;
+        }
+
+        // Walk backward from the other side, adding more triangles and flipping
+        if walk_back {
+            loop {
+                let before = self.hull_previous[edge];
+                if !orient(point, &points[before], &points[edge]) {
+                    break;
+                }
+                let triangle = self.add_triangle(
+                    [before, index, edge],
+                    [EMPTY, self.hull_triangle[edge], self.hull_triangle[before]],
+                );
+                self.legalize(triangle + 2);
+                self.hull_triangle[before] = triangle;
+                self.hull_next[edge] = edge;
+                edge = before;
+            }
+        }
+
+        // Update the hull
+        self.hull_start = edge;
+        self.hull_previous[index] = edge;
+        self.hull_next[edge] = index;
+        self.hull_previous[next] = index;
+        self.hull_next[index] = next;
+        self.hash_edge(index);
+        self.hash_edge(edge);
+    }
+
+    /// Returns the bucket of the point in the hull hash, based on its pseudo angle around the
+    /// center.
+    fn hash_key(&self, point: &Point2D) -> usize {
+        let dx = point.x - self.center.x;
+        let dy = point.y - self.center.y;
+        let p = dx / (dx.abs() + dy.abs());
+        let angle = (if dy > 0.0 { 3.0 - p } else { 1.0 + p }) / 4.0;
+        let len = self.hull_hash.len();
+        ((angle * len as f64).floor() as usize) % len
+    }
+
+    fn hash_edge(&mut self, index: usize) {
+        let key = self.hash_key(&self.points[index]);
+        self.hull_hash[key] = index;
+    }
+
+    /// Adds a triangle and links its half-edges to the given opposite half-edges.
+    fn add_triangle(&mut self, nodes: [usize; 3], opposites: [usize; 3]) -> usize {
+        let triangle = self.triangles.len();
+        self.triangles.extend(nodes);
+        self.half_edges.extend(opposites);
+        for (offset, opposite) in opposites.into_iter().enumerate() {
+            if opposite != EMPTY {
+                self.half_edges[opposite] = triangle + offset;
+            }
+        }
+        triangle
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        self.half_edges[a] = b;
+        if b != EMPTY {
+            self.half_edges[b] = a;
+        }
+    }
+
+    /// Flips the edge `a` and all edges affected by the flip until the Delaunay property holds
+    /// again. Returns the half-edge preceding `a`, which is adjacent to the hull.
+    fn legalize(&mut self, mut a: usize) -> usize {
+        let points = self.points;
+        let mut a_previous;
+        loop {
+            let b = self.half_edges[a];
+            a_previous = previous_half_edge(a);
+
+            if b == EMPTY {
+                match self.edge_stack.pop() {
+                    Some(edge) => {
+                        a = edge;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let a_next = next_half_edge(a);
+            let b_previous = previous_half_edge(b);
+            let p0 = self.triangles[a_previous];
+            let pr = self.triangles[a];
+            let pl = self.triangles[a_next];
+            let p1 = self.triangles[b_previous];
+
+            if in_circle(&points[p0], &points[pr], &points[pl], &points[p1]) {
+                self.triangles[a] = p1;
+                self.triangles[b] = p0;
+
+                let opposite_b_previous = self.half_edges[b_previous];
+                // The flipped edge is on the hull on the other side, fix the hull reference
+                if opposite_b_previous == EMPTY {
+                    let mut edge = self.hull_start;
+                    loop {
+                        if self.hull_triangle[edge] == b_previous {
+                            self.hull_triangle[edge] = a;
+                            break;
+                        }
+                        edge = self.hull_previous[edge];
+                        if edge == self.hull_start {
+                            break;
+                        }
+                    }
+                }
+                self.link(a, opposite_b_previous);
+                self.link(b, self.half_edges[a_previous]);
+                self.link(a_previous, b_previous);
+
+                self.edge_stack.push(next_half_edge(b));
+            } else {
+                match self.edge_stack.pop() {
+                    Some(edge) => a = edge,
+                    None => break,
+                }
+            }
+        }
+        a_previous
+    }
+}
+
+#[inline(always)]
+fn nearly_equal(a: &Point2D, b: &Point2D) -> bool {
+    (a.x - b.x).abs() <= EPSILON && (a.y - b.y).abs() <= EPSILON
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo random points with integer coordinates in `[0, 1000)^2`.
+    fn random_points(count: usize) -> Vec<Point2D> {
+        let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 1000) as f64
+        };
+        (0..count)
+            .map(|_| Point2D {
+                x: next(),
+                y: next(),
+            })
+            .collect()
+    }
+
+    /// Checks that no point lies strictly inside the circumcircle of any triangle.
+    fn assert_delaunay(points: &[Point2D], triangulation: &DelaunayTriangulation) {
+        for &[a, b, c] in triangulation.triangles() {
+            let center = circumcenter(&points[a.0], &points[b.0], &points[c.0]);
+            let radius = distance_squared(&center, &points[a.0]);
+            for point in points {
+                assert!(
+                    distance_squared(&center, point) >= radius * (1.0 - 1e-9),
+                    "{point:?} is inside the circumcircle of {a:?}, {b:?}, {c:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_square() {
+        let points = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.5, 0.4)]
+            .map(|(x, y)| Point2D { x, y });
+        let triangulation = DelaunayTriangulation::new(&points);
+
+        assert_eq!(triangulation.triangles().len(), 4);
+        // The four sides and the four spokes to the center
+        assert_eq!(triangulation.edges().len(), 8);
+        assert!(triangulation.edges().iter().all(|edge| edge.from == Node(4)
+            || edge.to == Node(4)
+            || {
+                let (a, b) = (edge.from.0.min(edge.to.0), edge.from.0.max(edge.to.0));
+                [(0, 1), (0, 2), (1, 3), (2, 3)].contains(&(a, b))
+            }));
+    }
+
+    #[test]
+    fn test_random_points_are_delaunay() {
+        let points = random_points(2000);
+        let triangulation = DelaunayTriangulation::new(&points);
+
+        assert_delaunay(&points, &triangulation);
+        // Euler's formula for a triangulation of n points with h points on the hull: 2n - 2 - h
+        // triangles, so there are at least n - 2 and at most 2n - 5 of them
+        let distinct = (0..points.len())
+            .filter(|&index| triangulation.representative(Node(index)) == Node(index))
+            .count();
+        let triangles = triangulation.triangles().len();
+        assert!(triangles >= distinct - 2 && triangles <= 2 * distinct - 5);
+        for &[a, b, c] in triangulation.triangles() {
+            assert!(!orient(&points[a.0], &points[b.0], &points[c.0]));
+        }
+    }
+
+    #[test]
+    fn test_grid_with_cocircular_points() {
+        let points = (0..400)
+            .map(|index| Point2D {
+                x: (index % 20) as f64,
+                y: (index / 20) as f64,
+            })
+            .collect::<Vec<_>>();
+        let triangulation = DelaunayTriangulation::new(&points);
+
+        assert_delaunay(&points, &triangulation);
+        // Every unit square is split into two triangles
+        assert_eq!(triangulation.triangles().len(), 2 * 19 * 19);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut points = random_points(50);
+        points.extend_from_within(..10);
+        points.push(points[3]);
+        let triangulation = DelaunayTriangulation::new(&points);
+
+        assert_eq!(triangulation.representative(Node(50)), Node(0));
+        assert_eq!(triangulation.representative(Node(60)), Node(3));
+        assert!(
+            triangulation
+                .edges()
+                .iter()
+                .all(|edge| edge.from.0 < 50 && edge.to.0 < 50)
+        );
+        assert_delaunay(&points[..50], &triangulation);
+    }
+
+    #[test]
+    fn test_collinear() {
+        let points = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0]
+            .map(|value| Point2D {
+                x: value,
+                y: 2.0 * value,
+            })
+            .to_vec();
+        let triangulation = DelaunayTriangulation::new(&points);
+
+        assert_eq!(triangulation.triangles().len(), 0);
+        assert_eq!(triangulation.representative(Node(3)), Node(1));
+        assert_eq!(
+            triangulation.edges(),
+            &[(0, 1), (0, 2), (2, 4), (4, 5)].map(UnEdge::from)
+        );
+    }
+}
@@ -1,87 +1,492 @@
+//! Fixed point numbers used for the node penalties of the Lagrangian relaxation.
+//!
+//! All arithmetic is overflow-aware: The `checked_*` methods return a
+//! [`FixedPointOverflowError`], the `saturating_*` methods clamp to [`FixedPoint::MIN`] and
+//! [`FixedPoint::MAX`]. The operators (`+`, `-`, ...) panic on overflow in debug and test builds,
+//! such that an overflow shows up as an error instead of a wrong bound, and saturate in release
+//! builds.
+
 use std::{
+    fmt::Debug,
+    hash::Hash,
     iter::Sum,
     ops::{Add, AddAssign, Div, Mul, Sub, SubAssign},
 };
 
+use thiserror::Error;
+
 use super::Distance;
 
-pub(crate) const FIXED_POINT_FRACTIONAL_BITS: u32 = 5;
+/// The number of fractional bits of [`ScaledDistance`] and [`ScaledDistance64`].
+pub const FIXED_POINT_FRACTIONAL_BITS: u32 = 5;
+
+/// A fixed point number with `i32` backing and [`FIXED_POINT_FRACTIONAL_BITS`] fractional bits.
+pub type ScaledDistance = FixedPoint<i32, FIXED_POINT_FRACTIONAL_BITS>;
+
+/// A fixed point number with `i64` backing and [`FIXED_POINT_FRACTIONAL_BITS`] fractional bits,
+/// for distances whose scaled values do not fit into an `i32`.
+pub type ScaledDistance64 = FixedPoint<i64, FIXED_POINT_FRACTIONAL_BITS>;
+
+/// An arithmetic operation on fixed point numbers overflowed.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Fixed point overflow in {operation}")]
+pub struct FixedPointOverflowError {
+    /// The operation that overflowed, e.g. `"add"`.
+    pub operation: &'static str,
+}
+
+/// Integer types that can back a [`FixedPoint`] number.
+pub trait FixedPointBacking: Copy + Ord + Hash + Default + Debug + Send + Sync + 'static {
+    /// The smallest value of the integer type.
+    const MIN: Self;
+    /// The biggest value of the integer type.
+    const MAX: Self;
+    /// Zero.
+    const ZERO: Self;
+
+    /// Converts an `i32` into the integer type, returns `None` if it does not fit.
+    fn from_i32(value: i32) -> Option<Self>;
+    /// Converts the value into an `i32`, returns `None` if it does not fit.
+    fn to_i32(self) -> Option<i32>;
+    /// Converts an `f64` into the integer type, truncating towards zero. Returns `None` if the
+    /// value is out of range or NaN.
+    fn from_f64(value: f64) -> Option<Self>;
+    /// Converts the value into an `f64`.
+    fn to_f64(self) -> f64;
+    /// Checked addition.
+    fn checked_add(self, other: Self) -> Option<Self>;
+    /// Checked subtraction.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    /// Checked multiplication.
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    /// Checked division.
+    fn checked_div(self, other: Self) -> Option<Self>;
+    /// Saturating addition.
+    fn saturating_add(self, other: Self) -> Self;
+    /// Saturating subtraction.
+    fn saturating_sub(self, other: Self) -> Self;
+    /// Saturating multiplication.
+    fn saturating_mul(self, other: Self) -> Self;
+    /// Arithmetic shift to the right.
+    fn shr(self, bits: u32) -> Self;
+}
+
+macro_rules! impl_fixed_point_backing {
+    ($integer:ty) => {
+        impl FixedPointBacking for $integer {
+            const MAX: Self = <$integer>::MAX;
+            const MIN: Self = <$integer>::MIN;
+            const ZERO: Self = 0;
+
+            #[inline(always)]
+            fn from_i32(value: i32) -> Option<Self> {
+                value.try_into().ok()
+            }
+
+            #[inline(always)]
+            fn to_i32(self) -> Option<i32> {
+                self.try_into().ok()
+            }
+
+            #[inline(always)]
+            fn from_f64(value: f64) -> Option<Self> {
+                // Both bounds are exactly representable as powers of two
+                let in_range = value >= <$integer>::MIN as f64 && value < -(<$integer>::MIN as f64);
+                in_range.then_some(value as $integer)
+            }
+
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            #[inline(always)]
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$integer>::checked_add(self, other)
+            }
+
+            #[inline(always)]
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$integer>::checked_sub(self, other)
+            }
+
+            #[inline(always)]
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$integer>::checked_mul(self, other)
+            }
+
+            #[inline(always)]
+            fn checked_div(self, other: Self) -> Option<Self> {
+                <$integer>::checked_div(self, other)
+            }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
-pub struct ScaledDistance(pub i32);
+            #[inline(always)]
+            fn saturating_add(self, other: Self) -> Self {
+                <$integer>::saturating_add(self, other)
+            }
 
-impl ScaledDistance {
-    pub const MAX: ScaledDistance = ScaledDistance(i32::MAX);
-    pub const MIN: ScaledDistance = ScaledDistance(i32::MIN);
+            #[inline(always)]
+            fn saturating_sub(self, other: Self) -> Self {
+                <$integer>::saturating_sub(self, other)
+            }
+
+            #[inline(always)]
+            fn saturating_mul(self, other: Self) -> Self {
+                <$integer>::saturating_mul(self, other)
+            }
+
+            #[inline(always)]
+            fn shr(self, bits: u32) -> Self {
+                self >> bits
+            }
+        }
+    };
 }
 
-impl ScaledDistance {
-    /// TODO: Ensure that value does not overflow when shifted?
+impl_fixed_point_backing!(i32);
+impl_fixed_point_backing!(i64);
+
+/// A fixed point number with `FRACTIONAL_BITS` fractional bits, backed by the integer type `T`.
+///
+/// The wrapped integer is the raw value, i.e. the number multiplied by `2^FRACTIONAL_BITS`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FixedPoint<T, const FRACTIONAL_BITS: u32>(pub T);
+
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> FixedPoint<T, FRACTIONAL_BITS> {
+    /// The number of fractional bits.
+    pub const FRACTIONAL_BITS: u32 = FRACTIONAL_BITS;
+    /// The biggest representable value.
+    pub const MAX: Self = FixedPoint(T::MAX);
+    /// The smallest representable value.
+    pub const MIN: Self = FixedPoint(T::MIN);
+    /// Zero.
+    pub const ZERO: Self = FixedPoint(T::ZERO);
+
+    /// Creates a fixed point number from its raw value.
+    pub const fn from_raw(raw: T) -> Self {
+        FixedPoint(raw)
+    }
+
+    /// Returns the raw value.
+    pub const fn raw(self) -> T {
+        self.0
+    }
+
+    /// Converts an integer into a fixed point number, returns an error if it is out of range.
+    pub fn checked_from_int(value: T) -> Result<Self, FixedPointOverflowError> {
+        const { assert!(FRACTIONAL_BITS < 31, "Too many fractional bits") };
+        T::from_i32(1 << FRACTIONAL_BITS)
+            .and_then(|scale| value.checked_mul(scale))
+            .map(FixedPoint)
+            .ok_or(FixedPointOverflowError {
+                operation: "conversion from integer",
+            })
+    }
+
+    /// Converts an integer into a fixed point number.
+    ///
+    /// Panics in debug builds and saturates in release builds if the value is out of range, see
+    /// [`FixedPoint::checked_from_int`] for a non-panicking version.
+    pub fn from_int(value: T) -> Self {
+        Self::checked_from_int(value).unwrap_or_else(|error| {
+            on_overflow(
+                error,
+                if value < T::ZERO {
+                    Self::MIN
+                } else {
+                    Self::MAX
+                },
+            )
+        })
+    }
+
+    /// Converts an `i32` into a fixed point number, returns an error if it is out of range.
+    pub fn checked_from_i32(value: i32) -> Result<Self, FixedPointOverflowError> {
+        T::from_i32(value)
+            .ok_or(FixedPointOverflowError {
+                operation: "conversion from integer",
+            })
+            .and_then(Self::checked_from_int)
+    }
+
+    /// Converts an `i32` into a fixed point number.
+    ///
+    /// Panics in debug builds and saturates in release builds if the value is out of range, see
+    /// [`FixedPoint::checked_from_i32`] for a non-panicking version.
     pub fn from_i32(value: i32) -> Self {
-        ScaledDistance(value << FIXED_POINT_FRACTIONAL_BITS)
+        Self::checked_from_i32(value).unwrap_or_else(|error| {
+            on_overflow(error, if value < 0 { Self::MIN } else { Self::MAX })
+        })
+    }
+
+    /// Converts a distance into a fixed point number, returns an error if it is out of range.
+    pub fn checked_from_distance(value: Distance) -> Result<Self, FixedPointOverflowError> {
+        Self::checked_from_i32(value.0)
     }
 
-    /// TODO: Ensure that value does not overflow when shifted?
+    /// Converts a distance into a fixed point number.
+    ///
+    /// Panics in debug builds and saturates in release builds if the distance is out of range,
+    /// see [`FixedPoint::checked_from_distance`] for a non-panicking version.
     pub fn from_distance(value: Distance) -> Self {
-        ScaledDistance(value.0 << FIXED_POINT_FRACTIONAL_BITS)
+        Self::from_i32(value.0)
     }
 
+    /// Converts the number into a distance, rounding down.
+    ///
+    /// Panics in debug builds and saturates in release builds if the distance does not fit into
+    /// an `i32`.
     pub fn to_distance(self) -> Distance {
-        Distance(self.0 >> FIXED_POINT_FRACTIONAL_BITS)
+        Distance(Self::narrow(self.0.shr(FRACTIONAL_BITS)))
     }
 
+    /// Converts the number into a distance, rounding up.
+    ///
+    /// Panics in debug builds and saturates in release builds if the distance does not fit into
+    /// an `i32`.
     pub fn to_distance_rounded_up(self) -> Distance {
-        let adjusted = self.0 + (1 << FIXED_POINT_FRACTIONAL_BITS) - 1;
-        Distance(adjusted >> FIXED_POINT_FRACTIONAL_BITS)
+        Distance(Self::narrow(self.raw_rounded_up()))
+    }
+
+    /// Returns the raw value divided by `2^FRACTIONAL_BITS`, rounded up.
+    pub(crate) fn raw_rounded_up(self) -> T {
+        let scale =
+            T::from_i32(1 << FRACTIONAL_BITS).expect("The scale fits into the backing type");
+        let one = T::from_i32(1).expect("1 fits into every backing type");
+        let floor = self.0.shr(FRACTIONAL_BITS);
+        // Neither operation can overflow: floor * scale lies between self - scale and self, and
+        // floor is at most T::MAX / scale.
+        if floor.checked_mul(scale) == Some(self.0) {
+            floor
+        } else {
+            floor.saturating_add(one)
+        }
+    }
+
+    fn narrow(value: T) -> i32 {
+        value.to_i32().unwrap_or_else(|| {
+            on_overflow(
+                FixedPointOverflowError {
+                    operation: "conversion to distance",
+                },
+                if value < T::ZERO { i32::MIN } else { i32::MAX },
+            )
+        })
+    }
+
+    /// Creates a number from a raw `f64` value, truncating towards zero. Returns an error if the
+    /// value is out of range.
+    pub fn checked_from_raw_f64(raw: f64) -> Result<Self, FixedPointOverflowError> {
+        T::from_f64(raw)
+            .map(FixedPoint)
+            .ok_or(FixedPointOverflowError {
+                operation: "conversion from f64",
+            })
+    }
+
+    /// Checked addition.
+    pub fn checked_add(self, other: Self) -> Result<Self, FixedPointOverflowError> {
+        self.0
+            .checked_add(other.0)
+            .map(FixedPoint)
+            .ok_or(FixedPointOverflowError { operation: "add" })
+    }
+
+    /// Checked subtraction.
+    pub fn checked_sub(self, other: Self) -> Result<Self, FixedPointOverflowError> {
+        self.0
+            .checked_sub(other.0)
+            .map(FixedPoint)
+            .ok_or(FixedPointOverflowError {
+                operation: "subtract",
+            })
+    }
+
+    /// Checked multiplication by an integer.
+    pub fn checked_mul_int(self, factor: i32) -> Result<Self, FixedPointOverflowError> {
+        T::from_i32(factor)
+            .and_then(|factor| self.0.checked_mul(factor))
+            .map(FixedPoint)
+            .ok_or(FixedPointOverflowError {
+                operation: "multiply",
+            })
+    }
+
+    /// Saturating addition.
+    pub fn saturating_add(self, other: Self) -> Self {
+        FixedPoint(self.0.saturating_add(other.0))
+    }
+
+    /// Saturating subtraction.
+    pub fn saturating_sub(self, other: Self) -> Self {
+        FixedPoint(self.0.saturating_sub(other.0))
+    }
+
+    /// Saturating multiplication by an integer.
+    pub fn saturating_mul_int(self, factor: i32) -> Self {
+        match T::from_i32(factor) {
+            Some(factor) => FixedPoint(self.0.saturating_mul(factor)),
+            None if (self.0 < T::ZERO) == (factor < 0) => Self::MAX,
+            None => Self::MIN,
+        }
+    }
+}
+
+/// Reports an overflow: Panics in debug and test builds, otherwise returns the saturated value.
+#[inline(never)]
+#[cold]
+pub(crate) fn on_overflow<V>(error: FixedPointOverflowError, saturated: V) -> V {
+    if cfg!(any(debug_assertions, test)) {
+        panic!("{error}");
     }
+    saturated
 }
 
-impl Add for ScaledDistance {
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> Add for FixedPoint<T, FRACTIONAL_BITS> {
     type Output = Self;
 
+    #[inline(always)]
     fn add(self, other: Self) -> Self::Output {
-        ScaledDistance(self.0 + other.0)
+        self.checked_add(other)
+            .unwrap_or_else(|error| on_overflow(error, self.saturating_add(other)))
     }
 }
 
-impl Sub for ScaledDistance {
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> Sub for FixedPoint<T, FRACTIONAL_BITS> {
     type Output = Self;
 
+    #[inline(always)]
     fn sub(self, other: Self) -> Self::Output {
-        ScaledDistance(self.0 - other.0)
+        self.checked_sub(other)
+            .unwrap_or_else(|error| on_overflow(error, self.saturating_sub(other)))
     }
 }
 
-impl Div<i32> for ScaledDistance {
-    type Output = ScaledDistance;
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> Div<i32> for FixedPoint<T, FRACTIONAL_BITS> {
+    type Output = Self;
 
+    #[inline(always)]
     fn div(self, rhs: i32) -> Self::Output {
-        ScaledDistance(self.0 / rhs)
+        let rhs = T::from_i32(rhs).expect("Every i32 fits into the backing type");
+        let error = FixedPointOverflowError {
+            operation: "divide",
+        };
+        match self.0.checked_div(rhs) {
+            Some(quotient) => FixedPoint(quotient),
+            // Only MIN / -1 overflows (division by zero panics regardless)
+            None if rhs == T::ZERO => panic!("Division of a fixed point number by zero"),
+            None => on_overflow(error, Self::MAX),
+        }
     }
 }
 
-impl<'a> Sum<&'a ScaledDistance> for ScaledDistance {
-    fn sum<I: Iterator<Item = &'a ScaledDistance>>(iter: I) -> Self {
-        iter.fold(ScaledDistance(0), |acc, d| acc + *d)
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> Mul<FixedPoint<T, FRACTIONAL_BITS>> for i32 {
+    type Output = FixedPoint<T, FRACTIONAL_BITS>;
+
+    #[inline(always)]
+    fn mul(self, rhs: FixedPoint<T, FRACTIONAL_BITS>) -> Self::Output {
+        rhs.checked_mul_int(self)
+            .unwrap_or_else(|error| on_overflow(error, rhs.saturating_mul_int(self)))
     }
 }
 
-impl Mul<ScaledDistance> for i32 {
-    type Output = ScaledDistance;
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> AddAssign
+    for FixedPoint<T, FRACTIONAL_BITS>
+{
+    #[inline(always)]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
 
-    fn mul(self, rhs: ScaledDistance) -> Self::Output {
-        ScaledDistance(self * rhs.0)
+impl<T: FixedPointBacking, const FRACTIONAL_BITS: u32> SubAssign
+    for FixedPoint<T, FRACTIONAL_BITS>
+{
+    #[inline(always)]
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
     }
 }
 
-impl AddAssign for ScaledDistance {
-    fn add_assign(&mut self, other: Self) {
-        self.0 += other.0;
+impl<'a, T: FixedPointBacking, const FRACTIONAL_BITS: u32> Sum<&'a Self>
+    for FixedPoint<T, FRACTIONAL_BITS>
+{
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, d| acc + *d)
     }
 }
 
-impl SubAssign for ScaledDistance {
-    fn sub_assign(&mut self, other: Self) {
-        self.0 -= other.0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversion_round_trip() {
+        assert_eq!(ScaledDistance::from_i32(-7).to_distance(), Distance(-7));
+        assert_eq!(ScaledDistance::from_raw(33).to_distance(), Distance(1));
+        assert_eq!(
+            ScaledDistance::from_raw(33).to_distance_rounded_up(),
+            Distance(2)
+        );
+        assert_eq!(
+            ScaledDistance::from_raw(-33).to_distance_rounded_up(),
+            Distance(-1)
+        );
+        assert_eq!(
+            ScaledDistance::from_raw(64).to_distance_rounded_up(),
+            Distance(2)
+        );
+        assert_eq!(
+            FixedPoint::<i32, 10>::from_i32(3),
+            FixedPoint::<i32, 10>::from_raw(3 << 10)
+        );
+    }
+
+    #[test]
+    fn test_checked_operations() {
+        let max_distance = Distance(i32::MAX >> FIXED_POINT_FRACTIONAL_BITS);
+        assert!(ScaledDistance::checked_from_distance(max_distance).is_ok());
+        assert_eq!(
+            ScaledDistance::checked_from_distance(max_distance + Distance(1)),
+            Err(FixedPointOverflowError {
+                operation: "conversion from integer"
+            })
+        );
+        assert!(ScaledDistance64::checked_from_distance(max_distance + Distance(1)).is_ok());
+
+        assert!(
+            ScaledDistance::MAX
+                .checked_add(ScaledDistance::from_raw(1))
+                .is_err()
+        );
+        assert!(
+            ScaledDistance::MIN
+                .checked_sub(ScaledDistance::from_raw(1))
+                .is_err()
+        );
+        assert!(ScaledDistance::MAX.checked_mul_int(2).is_err());
+        assert_eq!(
+            ScaledDistance::from_raw(2).checked_mul_int(-3),
+            Ok(ScaledDistance::from_raw(-6))
+        );
+        assert!(ScaledDistance::checked_from_raw_f64(3e9).is_err());
+        assert!(ScaledDistance::checked_from_raw_f64(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_saturating_operations() {
+        let one = ScaledDistance::from_raw(1);
+        assert_eq!(ScaledDistance::MAX.saturating_add(one), ScaledDistance::MAX);
+        assert_eq!(ScaledDistance::MIN.saturating_sub(one), ScaledDistance::MIN);
+        assert_eq!(
+            ScaledDistance::MAX.saturating_mul_int(-2),
+            ScaledDistance::MIN
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Fixed point overflow in add")]
+    fn test_overflow_panics_in_debug_builds() {
+        let _ = ScaledDistance::MAX + ScaledDistance::from_raw(1);
     }
 }
@@ -5,10 +5,17 @@ use std::{
 };
 
 mod fixed_point_arithmetic;
-use fixed_point_arithmetic::FIXED_POINT_FRACTIONAL_BITS;
-pub use fixed_point_arithmetic::ScaledDistance;
+mod value;
+pub use fixed_point_arithmetic::{
+    FIXED_POINT_FRACTIONAL_BITS, FixedPoint, FixedPointBacking, FixedPointOverflowError,
+    ScaledDistance, ScaledDistance64,
+};
+pub use value::{Distance64, DistanceValue, FloatDistance, ScaledValue};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[repr(transparent)]
 pub struct Distance(pub i32);
 
 impl Distance {
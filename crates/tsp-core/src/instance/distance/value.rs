@@ -0,0 +1,404 @@
+//! Abstraction over the numeric type used for distances.
+//!
+//! [`Distance`] (`i32`) is the default and fastest choice. Tour lengths of big instances (e.g. the
+//! World TSP in meters) or matrices with big entries (e.g. costs in cents) overflow it, for those
+//! [`Distance64`] (`i64`) and [`FloatDistance`] (totally ordered `f64`) are available.
+
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::{
+    Distance, ScaledDistance, ScaledDistance64,
+    fixed_point_arithmetic::{FIXED_POINT_FRACTIONAL_BITS, FixedPoint, on_overflow},
+};
+
+/// A numeric type that can be stored in distance containers and used by the solvers.
+///
+/// Every distance value can be created from a [`Distance`], which is what the TSPLIB95 distance
+/// functions compute.
+pub trait DistanceValue:
+    Copy
+    + Ord
+    + Default
+    + Debug
+    + Display
+    + FromStr
+    + From<Distance>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + AddAssign
+    + Sum<Self>
+    + for<'a> Sum<&'a Self>
+    + Send
+    + Sync
+    + 'static
+{
+    /// The type used for distances with node penalties added in the Lagrangian relaxation.
+    type Scaled: ScaledValue;
+
+    /// The zero distance.
+    const ZERO: Self;
+
+    /// Converts the distance into its scaled representation.
+    fn to_scaled(self) -> Self::Scaled;
+
+    /// Converts a scaled distance back, rounding up to the next representable distance.
+    fn from_scaled_rounded_up(scaled: Self::Scaled) -> Self;
+
+    /// Converts the distance to a float, e.g. for statistics.
+    fn to_f64(self) -> f64;
+}
+
+/// Fixed point (or floating point) distances used for node penalties in the Lagrangian
+/// relaxation.
+///
+/// The raw value of a fixed point type is its underlying integer, i.e. the distance multiplied by
+/// `2^FRACTIONAL_BITS`.
+///
+/// For the [`FixedPoint`] types, the arithmetic operations panic on overflow in debug and test
+/// builds and saturate in release builds.
+pub trait ScaledValue:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + AddAssign
+    + SubAssign
+    + for<'a> Sum<&'a Self>
+    + Send
+    + Sync
+    + 'static
+{
+    /// The smallest value, also used as a sentinel for fixed edges.
+    const MIN: Self;
+    /// The biggest value, also used as a sentinel for missing edges.
+    const MAX: Self;
+    /// The zero value.
+    const ZERO: Self;
+    /// Subgradient steps smaller than or equal to this are not worth taking.
+    const MIN_STEP: Self;
+
+    /// Creates a value from its raw representation, truncating towards zero.
+    fn from_raw_f64(raw: f64) -> Self;
+
+    /// Returns the raw representation of the value.
+    fn to_raw_f64(self) -> f64;
+
+    /// Multiplies the value by an integer factor.
+    fn times(self, factor: i32) -> Self;
+
+    /// Halves the value, truncating towards zero.
+    fn half(self) -> Self;
+}
+
+impl DistanceValue for Distance {
+    type Scaled = ScaledDistance;
+
+    const ZERO: Self = Distance(0);
+
+    #[inline(always)]
+    fn to_scaled(self) -> ScaledDistance {
+        ScaledDistance::from_distance(self)
+    }
+
+    #[inline(always)]
+    fn from_scaled_rounded_up(scaled: ScaledDistance) -> Self {
+        scaled.to_distance_rounded_up()
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self.0 as f64
+    }
+}
+
+/// Implements [`ScaledValue`] for the fixed point numbers with the given backing type.
+macro_rules! impl_scaled_value_for_fixed_point {
+    ($integer:ty) => {
+        impl<const FRACTIONAL_BITS: u32> ScaledValue for FixedPoint<$integer, FRACTIONAL_BITS> {
+            const MAX: Self = FixedPoint::MAX;
+            const MIN: Self = FixedPoint::MIN;
+            const MIN_STEP: Self = FixedPoint(3);
+            const ZERO: Self = FixedPoint::ZERO;
+
+            #[inline(always)]
+            fn from_raw_f64(raw: f64) -> Self {
+                Self::checked_from_raw_f64(raw).unwrap_or_else(|error| {
+                    on_overflow(error, if raw < 0.0 { Self::MIN } else { Self::MAX })
+                })
+            }
+
+            #[inline(always)]
+            fn to_raw_f64(self) -> f64 {
+                self.0 as f64
+            }
+
+            #[inline(always)]
+            fn times(self, factor: i32) -> Self {
+                factor * self
+            }
+
+            #[inline(always)]
+            fn half(self) -> Self {
+                self / 2
+            }
+        }
+    };
+}
+
+impl_scaled_value_for_fixed_point!(i32);
+impl_scaled_value_for_fixed_point!(i64);
+
+impl FromStr for Distance {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Distance)
+    }
+}
+
+/// Implements the arithmetic operators required by [`DistanceValue`] and [`ScaledValue`] for a
+/// newtype around a primitive number.
+macro_rules! impl_arithmetic {
+    ($name:ident) => {
+        impl Add for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, other: Self) -> Self::Output {
+                $name(self.0 + other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            #[inline(always)]
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, other: Self) -> Self::Output {
+                $name(self.0 - other.0)
+            }
+        }
+
+        impl SubAssign for $name {
+            #[inline(always)]
+            fn sub_assign(&mut self, other: Self) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Sum<$name> for $name {
+            fn sum<I: Iterator<Item = $name>>(iter: I) -> Self {
+                iter.fold(<$name>::default(), |acc, d| acc + d)
+            }
+        }
+
+        impl<'a> Sum<&'a $name> for $name {
+            fn sum<I: Iterator<Item = &'a $name>>(iter: I) -> Self {
+                iter.fold(<$name>::default(), |acc, d| acc + *d)
+            }
+        }
+    };
+}
+
+/// A distance backed by an `i64`, for instances whose tour lengths overflow [`Distance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[repr(transparent)]
+pub struct Distance64(pub i64);
+
+impl_arithmetic!(Distance64);
+
+impl Display for Distance64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self.0.to_string();
+        f.pad(&s)
+    }
+}
+
+impl FromStr for Distance64 {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Distance64)
+    }
+}
+
+impl From<Distance> for Distance64 {
+    fn from(distance: Distance) -> Self {
+        Distance64(distance.0 as i64)
+    }
+}
+
+impl DistanceValue for Distance64 {
+    type Scaled = ScaledDistance64;
+
+    const ZERO: Self = Distance64(0);
+
+    #[inline(always)]
+    fn to_scaled(self) -> ScaledDistance64 {
+        ScaledDistance64::from_int(self.0)
+    }
+
+    #[inline(always)]
+    fn from_scaled_rounded_up(scaled: ScaledDistance64) -> Self {
+        Distance64(scaled.raw_rounded_up())
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self.0 as f64
+    }
+}
+
+/// A floating point distance with a total order (see [`f64::total_cmp`]).
+///
+/// Since no rounding is necessary for the Lagrangian relaxation, it is its own scaled type.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[repr(transparent)]
+pub struct FloatDistance(pub f64);
+
+impl_arithmetic!(FloatDistance);
+
+impl PartialEq for FloatDistance {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FloatDistance {}
+
+impl PartialOrd for FloatDistance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloatDistance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for FloatDistance {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl Display for FloatDistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for FloatDistance {
+    type Err = std::num::ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(FloatDistance)
+    }
+}
+
+impl From<Distance> for FloatDistance {
+    fn from(distance: Distance) -> Self {
+        FloatDistance(distance.0 as f64)
+    }
+}
+
+impl DistanceValue for FloatDistance {
+    type Scaled = FloatDistance;
+
+    const ZERO: Self = FloatDistance(0.0);
+
+    #[inline(always)]
+    fn to_scaled(self) -> FloatDistance {
+        self
+    }
+
+    #[inline(always)]
+    fn from_scaled_rounded_up(scaled: FloatDistance) -> Self {
+        scaled
+    }
+
+    #[inline(always)]
+    fn to_f64(self) -> f64 {
+        self.0
+    }
+}
+
+impl ScaledValue for FloatDistance {
+    const MAX: Self = FloatDistance(f64::MAX);
+    const MIN: Self = FloatDistance(f64::MIN);
+    /// The same threshold as for the fixed point types, i.e. `3 / 2^FRACTIONAL_BITS`.
+    const MIN_STEP: Self = FloatDistance(3.0 / (1 << FIXED_POINT_FRACTIONAL_BITS) as f64);
+    const ZERO: Self = FloatDistance(0.0);
+
+    #[inline(always)]
+    fn from_raw_f64(raw: f64) -> Self {
+        FloatDistance(raw)
+    }
+
+    #[inline(always)]
+    fn to_raw_f64(self) -> f64 {
+        self.0
+    }
+
+    #[inline(always)]
+    fn times(self, factor: i32) -> Self {
+        FloatDistance(self.0 * factor as f64)
+    }
+
+    #[inline(always)]
+    fn half(self) -> Self {
+        FloatDistance(self.0 / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaled_round_trip() {
+        assert_eq!(
+            Distance64::from_scaled_rounded_up(Distance64(5_000_000_000).to_scaled()),
+            Distance64(5_000_000_000)
+        );
+        assert_eq!(
+            Distance::from_scaled_rounded_up(
+                Distance(1234).to_scaled() + ScaledDistance::from_raw(1)
+            ),
+            Distance(1235)
+        );
+    }
+
+    #[test]
+    fn test_float_distance_total_order() {
+        let mut distances = [FloatDistance(2.5), FloatDistance(-1.0), FloatDistance(0.25)];
+        distances.sort();
+
+        assert_eq!(
+            distances,
+            [FloatDistance(-1.0), FloatDistance(0.25), FloatDistance(2.5)]
+        );
+        assert!(<FloatDistance as ScaledValue>::MIN < FloatDistance(-1e300));
+        assert_eq!("1.5".parse::<FloatDistance>(), Ok(FloatDistance(1.5)));
+    }
+}
@@ -2,6 +2,7 @@ use crate::instance::{distance::Distance, node::Node};
 
 /// An undirected edge between two nodes.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnEdge {
     pub from: Node,
     pub to: Node,
@@ -0,0 +1,756 @@
+//! A k-d tree over node coordinates for nearest neighbor, k-nearest neighbor and radius queries.
+//!
+//! The tree supports deleting nodes, which turns "nearest neighbor" into "nearest unvisited
+//! neighbor" as needed by construction heuristics. Deleted nodes are only marked, subtrees
+//! without remaining nodes are skipped during queries.
+//!
+//! Queries are exact for every metric: Subtrees are pruned with a lower bound on the distance
+//! from the query point to the bounding box of the subtree, see [`KdMetric`].
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{
+    instance::{
+        distance::Distance,
+        node::Node,
+        point::{GeoPoint, Point2D, Point3D},
+    },
+    metric::{self, Metric, Metric2D, Metric3D, toroidal_delta},
+};
+
+/// The maximum number of coordinates of a [`KdPoint`].
+const MAX_DIMENSIONS: usize = 3;
+
+/// Points that can be stored in a [`KdTree`].
+pub trait KdPoint: Copy {
+    /// The number of coordinates, at most 3.
+    const DIMENSIONS: usize;
+
+    /// Returns the coordinate along the given axis.
+    fn coordinate(&self, axis: usize) -> f64;
+
+    /// Creates a point from its coordinates.
+    fn from_coordinates(coordinates: &[f64]) -> Self;
+}
+
+impl KdPoint for Point2D {
+    const DIMENSIONS: usize = 2;
+
+    #[inline(always)]
+    fn coordinate(&self, axis: usize) -> f64 {
+        match axis {
+            0 => self.x,
+            _ => self.y,
+        }
+    }
+
+    fn from_coordinates(coordinates: &[f64]) -> Self {
+        Point2D {
+            x: coordinates[0],
+            y: coordinates[1],
+        }
+    }
+}
+
+impl KdPoint for Point3D {
+    const DIMENSIONS: usize = 3;
+
+    #[inline(always)]
+    fn coordinate(&self, axis: usize) -> f64 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+
+    fn from_coordinates(coordinates: &[f64]) -> Self {
+        Point3D {
+            x: coordinates[0],
+            y: coordinates[1],
+            z: coordinates[2],
+        }
+    }
+}
+
+impl KdPoint for GeoPoint {
+    const DIMENSIONS: usize = 2;
+
+    #[inline(always)]
+    fn coordinate(&self, axis: usize) -> f64 {
+        match axis {
+            0 => self.latitude,
+            _ => self.longitude,
+        }
+    }
+
+    fn from_coordinates(coordinates: &[f64]) -> Self {
+        GeoPoint {
+            latitude: coordinates[0],
+            longitude: coordinates[1],
+        }
+    }
+}
+
+/// Metrics that can be used with a [`KdTree`].
+pub trait KdMetric: Metric<Point: KdPoint> {
+    /// Returns a lower bound on the distance from `point` to every point in the axis-aligned box
+    /// spanned by `min` and `max`.
+    ///
+    /// The tighter the bound, the more subtrees are pruned. `Distance(0)` is always correct, but
+    /// degrades the queries to a linear scan.
+    fn lower_bound(&self, point: &Self::Point, min: &Self::Point, max: &Self::Point) -> Distance;
+}
+
+/// Returns the point of the box closest to `point`.
+///
+/// This gives an exact lower bound for every metric that does not decrease when the coordinate
+/// differences increase, which includes all rounded TSPLIB95 metrics on plain coordinates.
+#[inline(always)]
+fn clamp_to_box<P: KdPoint>(point: &P, min: &P, max: &P) -> P {
+    let mut coordinates = [0.0; MAX_DIMENSIONS];
+    for (axis, coordinate) in coordinates.iter_mut().enumerate().take(P::DIMENSIONS) {
+        *coordinate = point
+            .coordinate(axis)
+            .clamp(min.coordinate(axis), max.coordinate(axis));
+    }
+    P::from_coordinates(&coordinates[..P::DIMENSIONS])
+}
+
+/// Returns the point of the box closest to `point` on a torus with the given side length.
+///
+/// A coordinate outside of the interval of the box is closest to one of the interval ends, as
+/// every way around the torus enters the interval through one of them.
+#[inline(always)]
+fn clamp_to_box_toroidal<P: KdPoint>(point: &P, min: &P, max: &P, grid_size: f64) -> P {
+    let mut coordinates = [0.0; MAX_DIMENSIONS];
+    for (axis, coordinate) in coordinates.iter_mut().enumerate().take(P::DIMENSIONS) {
+        let value = point.coordinate(axis);
+        let (low, high) = (min.coordinate(axis), max.coordinate(axis));
+        *coordinate = if (low..=high).contains(&value) {
+            value
+        } else if toroidal_delta(value, low, grid_size) <= toroidal_delta(value, high, grid_size) {
+            low
+        } else {
+            high
+        };
+    }
+    P::from_coordinates(&coordinates[..P::DIMENSIONS])
+}
+
+/// Lower bound for great circle distances: No point of the box is closer than the point on the
+/// same meridian as `point` with the closest latitude of the box.
+///
+/// One is subtracted to account for floating point differences between the two computations.
+#[inline(always)]
+fn great_circle_lower_bound(
+    metric: &impl Metric<Point = GeoPoint>,
+    point: &GeoPoint,
+    min: &GeoPoint,
+    max: &GeoPoint,
+) -> Distance {
+    let closest = GeoPoint {
+        latitude: point.latitude.clamp(min.latitude, max.latitude),
+        longitude: point.longitude,
+    };
+    Distance((metric.distance(point, &closest).0 - 1).max(0))
+}
+
+macro_rules! kd_metric_monotone {
+    ($($metric:ty),*) => {
+        $(
+            impl KdMetric for $metric {
+                #[inline(always)]
+                fn lower_bound(
+                    &self,
+                    point: &Self::Point,
+                    min: &Self::Point,
+                    max: &Self::Point,
+                ) -> Distance {
+                    self.distance(point, &clamp_to_box(point, min, max))
+                }
+            }
+        )*
+    };
+}
+
+kd_metric_monotone!(
+    metric::Euclidean2D,
+    metric::Euclidean3D,
+    metric::Maximum2D,
+    metric::Maximum3D,
+    metric::Manhattan2D,
+    metric::Manhattan3D,
+    metric::Ceil2D,
+    metric::Ceil3D,
+    metric::Floor2D,
+    metric::Floor3D,
+    metric::Att
+);
+
+impl KdMetric for metric::Toroidal2D {
+    #[inline(always)]
+    fn lower_bound(&self, point: &Point2D, min: &Point2D, max: &Point2D) -> Distance {
+        self.distance(
+            point,
+            &clamp_to_box_toroidal(point, min, max, self.grid_size),
+        )
+    }
+}
+
+impl KdMetric for metric::Toroidal3D {
+    #[inline(always)]
+    fn lower_bound(&self, point: &Point3D, min: &Point3D, max: &Point3D) -> Distance {
+        self.distance(
+            point,
+            &clamp_to_box_toroidal(point, min, max, self.grid_size),
+        )
+    }
+}
+
+impl KdMetric for metric::Geographical {
+    #[inline(always)]
+    fn lower_bound(&self, point: &GeoPoint, min: &GeoPoint, max: &GeoPoint) -> Distance {
+        great_circle_lower_bound(self, point, min, max)
+    }
+}
+
+impl KdMetric for metric::Geom {
+    #[inline(always)]
+    fn lower_bound(&self, point: &GeoPoint, min: &GeoPoint, max: &GeoPoint) -> Distance {
+        great_circle_lower_bound(self, point, min, max)
+    }
+}
+
+impl KdMetric for metric::GeomMeeus {
+    /// The ellipsoid approximation has no simple bound, so no subtree is pruned.
+    #[inline(always)]
+    fn lower_bound(&self, _point: &GeoPoint, _min: &GeoPoint, _max: &GeoPoint) -> Distance {
+        Distance(0)
+    }
+}
+
+impl KdMetric for metric::GeoMeeus {
+    /// The ellipsoid approximation has no simple bound, so no subtree is pruned.
+    #[inline(always)]
+    fn lower_bound(&self, _point: &GeoPoint, _min: &GeoPoint, _max: &GeoPoint) -> Distance {
+        Distance(0)
+    }
+}
+
+impl KdMetric for Metric2D {
+    /// The geographical metrics convert the coordinates for every distance, which is not
+    /// monotone in the raw coordinates, so no subtree is pruned for them. For big geographical
+    /// instances, build the tree over converted [`GeoPoint`]s with the corresponding metric
+    /// instead.
+    fn lower_bound(&self, point: &Point2D, min: &Point2D, max: &Point2D) -> Distance {
+        match *self {
+            Self::Euclidean
+            | Self::Maximum
+            | Self::Manhattan
+            | Self::Ceil
+            | Self::Floor
+            | Self::Att => self.distance(point, &clamp_to_box(point, min, max)),
+            Self::Toroidal { grid_size } => {
+                self.distance(point, &clamp_to_box_toroidal(point, min, max, grid_size))
+            }
+            Self::Geographical | Self::Geom | Self::GeomMeeus | Self::GeoMeeus => Distance(0),
+        }
+    }
+}
+
+impl KdMetric for Metric3D {
+    fn lower_bound(&self, point: &Point3D, min: &Point3D, max: &Point3D) -> Distance {
+        match *self {
+            Self::Euclidean | Self::Maximum | Self::Manhattan | Self::Ceil | Self::Floor => {
+                self.distance(point, &clamp_to_box(point, min, max))
+            }
+            Self::Toroidal { grid_size } => {
+                self.distance(point, &clamp_to_box_toroidal(point, min, max, grid_size))
+            }
+        }
+    }
+}
+
+/// A k-d tree over the points of an instance, supporting the deletion of nodes.
+///
+/// The tree is stored implicitly: Every subtree occupies a contiguous range of `order`, with its
+/// root (the median along the split axis) in the middle of the range.
+#[derive(Debug, Clone)]
+pub struct KdTree<M: KdMetric> {
+    metric: M,
+    points: Vec<M::Point>,
+    /// The nodes in tree order.
+    order: Vec<Node>,
+    /// The split axis of the subtree rooted at each position of `order`.
+    split_axes: Vec<u8>,
+    /// The number of nodes not yet removed in the subtree rooted at each position of `order`.
+    remaining_in_subtree: Vec<u32>,
+    /// Whether each node (indexed by node) is still contained in the tree.
+    contained: Vec<bool>,
+    /// The position of each node (indexed by node) in `order`.
+    positions: Vec<usize>,
+    /// Bounding box of all points.
+    min: M::Point,
+    max: M::Point,
+}
+
+impl<M: KdMetric> KdTree<M> {
+    /// Builds a k-d tree over the points, node `i` being at `points[i]`.
+    ///
+    /// Takes `O(n log n)` time. Every subtree is split at the median of the axis with the biggest
+    /// spread.
+    pub fn new(points: Vec<M::Point>, metric: M) -> Self {
+        let dimension = points.len();
+        let mut min = [f64::INFINITY; MAX_DIMENSIONS];
+        let mut max = [f64::NEG_INFINITY; MAX_DIMENSIONS];
+        for point in &points {
+            for axis in 0..M::Point::DIMENSIONS {
+                min[axis] = min[axis].min(point.coordinate(axis));
+                max[axis] = max[axis].max(point.coordinate(axis));
+            }
+        }
+
+        let mut tree = Self {
+            metric,
+            order: (0..dimension).map(Node).collect(),
+            split_axes: vec![0; dimension],
+            remaining_in_subtree: vec![0; dimension],
+            contained: vec![true; dimension],
+            positions: vec![0; dimension],
+            min: M::Point::from_coordinates(&min[..M::Point::DIMENSIONS]),
+            max: M::Point::from_coordinates(&max[..M::Point::DIMENSIONS]),
+            points,
+        };
+        tree.build(0, dimension);
+        for (position, node) in tree.order.iter().enumerate() {
+            tree.positions[node.0] = position;
+        }
+        tree
+    }
+
+    fn build(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        let points = &self.points;
+        let range = &mut self.order[start..end];
+
+        // Split along the axis with the biggest spread
+        let mut min = [f64::INFINITY; MAX_DIMENSIONS];
+        let mut max = [f64::NEG_INFINITY; MAX_DIMENSIONS];
+        for node in range.iter() {
+            for axis in 0..M::Point::DIMENSIONS {
+                let coordinate = points[node.0].coordinate(axis);
+                min[axis] = min[axis].min(coordinate);
+                max[axis] = max[axis].max(coordinate);
+            }
+        }
+        let axis = (0..M::Point::DIMENSIONS)
+            .max_by(|&a, &b| (max[a] - min[a]).total_cmp(&(max[b] - min[b])))
+            .unwrap_or(0);
+
+        let middle = range.len() / 2;
+        range.select_nth_unstable_by(middle, |a, b| {
+            points[a.0]
+                .coordinate(axis)
+                .total_cmp(&points[b.0].coordinate(axis))
+        });
+
+        let root = start + middle;
+        self.split_axes[root] = axis as u8;
+        self.remaining_in_subtree[root] = (end - start) as u32;
+        self.build(start, root);
+        self.build(root + 1, end);
+    }
+
+    /// Returns the metric of the tree.
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+
+    /// Returns the point of the node.
+    pub fn point(&self, node: Node) -> &M::Point {
+        &self.points[node.0]
+    }
+
+    /// Returns the number of nodes remaining in the tree.
+    pub fn len(&self) -> usize {
+        if self.order.is_empty() {
+            0
+        } else {
+            // The root of the whole tree is in the middle of `order`
+            self.remaining_in_subtree[self.order.len() / 2] as usize
+        }
+    }
+
+    /// Returns whether all nodes have been removed from the tree (or it was built without any).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether the node is still contained in the tree.
+    pub fn contains(&self, node: Node) -> bool {
+        self.contained[node.0]
+    }
+
+    /// Removes the node from the tree, such that it is not returned by queries anymore.
+    ///
+    /// Returns `false` if the node had already been removed. Takes `O(log n)` time.
+    pub fn remove(&mut self, node: Node) -> bool {
+        if !std::mem::replace(&mut self.contained[node.0], false) {
+            return false;
+        }
+
+        // Descend from the root to the node and update the counts of all subtrees on the way
+        let position = self.positions[node.0];
+        let (mut start, mut end) = (0, self.order.len());
+        loop {
+            let root = start + (end - start) / 2;
+            self.remaining_in_subtree[root] -= 1;
+            match position.cmp(&root) {
+                Ordering::Less => end = root,
+                Ordering::Greater => start = root + 1,
+                Ordering::Equal => return true,
+            }
+        }
+    }
+
+    /// Returns the nearest node to the point, or `None` if the tree is empty.
+    ///
+    /// Ties are broken by the node index.
+    pub fn nearest(&self, point: &M::Point) -> Option<(Node, Distance)> {
+        self.k_nearest(point, 1).pop()
+    }
+
+    /// Returns the `k` nearest nodes to the point sorted by increasing distance (ties broken by
+    /// the node index). Returns fewer nodes if the tree contains fewer than `k`.
+    pub fn k_nearest(&self, point: &M::Point, k: usize) -> Vec<(Node, Distance)> {
+        self.k_nearest_excluding(point, k, None)
+    }
+
+    /// Returns the `k` nearest other nodes to a node of the tree, see [`KdTree::k_nearest`].
+    ///
+    /// The node itself does not need to be contained in the tree anymore.
+    pub fn k_nearest_to_node(&self, node: Node, k: usize) -> Vec<(Node, Distance)> {
+        self.k_nearest_excluding(&self.points[node.0], k, Some(node))
+    }
+
+    fn k_nearest_excluding(
+        &self,
+        point: &M::Point,
+        k: usize,
+        excluded: Option<Node>,
+    ) -> Vec<(Node, Distance)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut collector = NearestCollector {
+            nearest: BinaryHeap::with_capacity(k + 1),
+            k,
+            excluded,
+        };
+        self.search(point, &mut collector);
+        collector
+            .nearest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|(distance, node)| (node, distance))
+            .collect()
+    }
+
+    /// Returns all nodes within the given distance (inclusive) to the point, sorted by increasing
+    /// distance (ties broken by the node index).
+    pub fn within_radius(&self, point: &M::Point, radius: Distance) -> Vec<(Node, Distance)> {
+        let mut collector = RadiusCollector {
+            radius,
+            found: Vec::new(),
+        };
+        self.search(point, &mut collector);
+        collector
+            .found
+            .sort_unstable_by_key(|&(node, distance)| (distance, node));
+        collector.found
+    }
+
+    fn search(&self, point: &M::Point, collector: &mut impl Collector) {
+        self.search_subtree(point, collector, 0, self.order.len(), self.min, self.max);
+    }
+
+    fn search_subtree(
+        &self,
+        point: &M::Point,
+        collector: &mut impl Collector,
+        start: usize,
+        end: usize,
+        min: M::Point,
+        max: M::Point,
+    ) {
+        if start >= end {
+            return;
+        }
+        let root = start + (end - start) / 2;
+        if self.remaining_in_subtree[root] == 0 {
+            return;
+        }
+        if collector
+            .threshold()
+            .is_some_and(|threshold| self.metric.lower_bound(point, &min, &max) > threshold)
+        {
+            return;
+        }
+
+        let node = self.order[root];
+        if self.contained[node.0] {
+            collector.offer(node, self.metric.distance(point, &self.points[node.0]));
+        }
+
+        // Both children are bounded by the split value along the split axis
+        let axis = self.split_axes[root] as usize;
+        let split = self.points[node.0].coordinate(axis);
+        let left_max = with_coordinate(&max, axis, split);
+        let right_min = with_coordinate(&min, axis, split);
+
+        // Visit the side containing the point first, to tighten the threshold early
+        if point.coordinate(axis) < split {
+            self.search_subtree(point, collector, start, root, min, left_max);
+            self.search_subtree(point, collector, root + 1, end, right_min, max);
+        } else {
+            self.search_subtree(point, collector, root + 1, end, right_min, max);
+            self.search_subtree(point, collector, start, root, min, left_max);
+        }
+    }
+}
+
+/// Returns a copy of the point with one coordinate replaced.
+#[inline(always)]
+fn with_coordinate<P: KdPoint>(point: &P, axis: usize, value: f64) -> P {
+    let mut coordinates = [0.0; MAX_DIMENSIONS];
+    for (index, coordinate) in coordinates.iter_mut().enumerate().take(P::DIMENSIONS) {
+        *coordinate = point.coordinate(index);
+    }
+    coordinates[axis] = value;
+    P::from_coordinates(&coordinates[..P::DIMENSIONS])
+}
+
+/// Collects the results of a query.
+trait Collector {
+    /// Subtrees whose lower bound exceeds the threshold are pruned. `None` disables pruning.
+    fn threshold(&self) -> Option<Distance>;
+
+    /// Offers a node of the tree at the given distance to the query point.
+    fn offer(&mut self, node: Node, distance: Distance);
+}
+
+struct NearestCollector {
+    /// Max-heap of the nearest nodes found so far.
+    nearest: BinaryHeap<(Distance, Node)>,
+    k: usize,
+    excluded: Option<Node>,
+}
+
+impl Collector for NearestCollector {
+    #[inline(always)]
+    fn threshold(&self) -> Option<Distance> {
+        if self.nearest.len() < self.k {
+            None
+        } else {
+            self.nearest.peek().map(|&(distance, _)| distance)
+        }
+    }
+
+    #[inline(always)]
+    fn offer(&mut self, node: Node, distance: Distance) {
+        if Some(node) == self.excluded {
+            return;
+        }
+        if self.nearest.len() < self.k {
+            self.nearest.push((distance, node));
+        } else if self
+            .nearest
+            .peek()
+            .is_some_and(|farthest| (distance, node) < *farthest)
+        {
+            self.nearest.pop();
+            self.nearest.push((distance, node));
+        }
+    }
+}
+
+struct RadiusCollector {
+    radius: Distance,
+    found: Vec<(Node, Distance)>,
+}
+
+impl Collector for RadiusCollector {
+    #[inline(always)]
+    fn threshold(&self) -> Option<Distance> {
+        Some(self.radius)
+    }
+
+    #[inline(always)]
+    fn offer(&mut self, node: Node, distance: Distance) {
+        if distance <= self.radius {
+            self.found.push((node, distance));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Euclidean2D, Geographical, Toroidal2D, convert_to_geo_coordinates};
+
+    /// Deterministic pseudo random points in `[0, 1000)^2`.
+    fn random_points(count: usize) -> Vec<Point2D> {
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 100_000) as f64 / 100.0
+        };
+        (0..count)
+            .map(|_| Point2D {
+                x: next(),
+                y: next(),
+            })
+            .collect()
+    }
+
+    /// Brute force k nearest neighbors with the same tie breaking as the tree.
+    fn brute_force<M: Metric>(
+        points: &[M::Point],
+        metric: &M,
+        point: &M::Point,
+        k: usize,
+        contained: impl Fn(Node) -> bool,
+    ) -> Vec<(Node, Distance)> {
+        let mut all = (0..points.len())
+            .map(Node)
+            .filter(|&node| contained(node))
+            .map(|node| (node, metric.distance(point, &points[node.0])))
+            .collect::<Vec<_>>();
+        all.sort_unstable_by_key(|&(node, distance)| (distance, node));
+        all.truncate(k);
+        all
+    }
+
+    #[test]
+    fn test_k_nearest_matches_brute_force() {
+        let points = random_points(500);
+        let queries = random_points(520);
+        for metric in [
+            Metric2D::Euclidean,
+            Metric2D::Maximum,
+            Metric2D::Manhattan,
+            Metric2D::Att,
+            Metric2D::Ceil,
+        ] {
+            let tree = KdTree::new(points.clone(), metric);
+            for query in &queries[500..] {
+                assert_eq!(
+                    tree.k_nearest(query, 7),
+                    brute_force(&points, &metric, query, 7, |_| true),
+                    "{metric:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_toroidal_wraps_around() {
+        let points = random_points(300);
+        let metric = Toroidal2D { grid_size: 1000.0 };
+        let tree = KdTree::new(points.clone(), metric);
+
+        let query = Point2D { x: 999.0, y: 1.0 };
+        assert_eq!(
+            tree.k_nearest(&query, 5),
+            brute_force(&points, &metric, &query, 5, |_| true)
+        );
+    }
+
+    #[test]
+    fn test_geographical() {
+        let points = random_points(300)
+            .iter()
+            .map(|point| {
+                convert_to_geo_coordinates(&Point2D {
+                    x: point.x / 12.0 - 40.0,
+                    y: point.y / 6.0 - 80.0,
+                })
+            })
+            .collect::<Vec<_>>();
+        let tree = KdTree::new(points.clone(), Geographical);
+
+        for node in (0..300).step_by(37).map(Node) {
+            assert_eq!(
+                tree.k_nearest_to_node(node, 4),
+                brute_force(&points, &Geographical, &points[node.0], 5, |other| {
+                    other != node
+                })
+                .into_iter()
+                .take(4)
+                .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_removal_for_nearest_unvisited() {
+        let points = random_points(200);
+        let mut tree = KdTree::new(points.clone(), Euclidean2D);
+
+        // Nearest neighbor tour construction visits every node exactly once
+        let mut current = Node(0);
+        let mut visited = vec![current];
+        tree.remove(current);
+        while let Some((next, _)) = tree.nearest(&points[current.0]) {
+            let expected = brute_force(&points, &Euclidean2D, &points[current.0], 1, |node| {
+                !visited.contains(&node)
+            });
+            assert_eq!(next, expected[0].0);
+            assert!(tree.remove(next));
+            visited.push(next);
+            current = next;
+        }
+
+        assert!(tree.is_empty());
+        assert_eq!(visited.len(), points.len());
+        assert!(!tree.remove(Node(0)));
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let points = random_points(400);
+        let tree = KdTree::new(points.clone(), Euclidean2D);
+        let query = Point2D { x: 500.0, y: 500.0 };
+
+        let expected = brute_force(&points, &Euclidean2D, &query, points.len(), |_| true)
+            .into_iter()
+            .filter(|&(_, distance)| distance <= Distance(100))
+            .collect::<Vec<_>>();
+        assert!(!expected.is_empty());
+        assert_eq!(tree.within_radius(&query, Distance(100)), expected);
+    }
+
+    #[test]
+    fn test_3d_and_duplicates() {
+        let points = vec![
+            Point3D {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            };
+            5
+        ];
+        let tree = KdTree::new(points, Metric3D::Euclidean);
+
+        assert_eq!(tree.len(), 5);
+        assert_eq!(
+            tree.k_nearest_to_node(Node(2), 2),
+            vec![(Node(0), Distance(0)), (Node(1), Distance(0))]
+        );
+    }
+}
@@ -0,0 +1,422 @@
+//! Matrices that store small distances in narrow integer types.
+//!
+//! Many instances have a maximum distance that fits in 8 or 16 bits (e.g. small-coordinate
+//! `EUC_2D` instances or 0/1 matrices derived from HCP instances). Storing them in 1 or 2 bytes
+//! instead of the 4 bytes of a [`Distance`] cuts the memory bandwidth of every pass over the matrix
+//! and lets more of it fit into the caches.
+//!
+//! [`CompactMatrix`] and [`CompactMatrixSym`] are generic over the entry type, so reads are
+//! monomorphized and widen the entries back to [`Distance`] without any dispatch.
+//! [`AnyCompactMatrix`] and [`AnyCompactMatrixSym`] choose the width from the range of the entries
+//! when the matrix is created. Match on them once and pass the contained matrix to the solver.
+
+use crate::instance::{
+    distance::Distance,
+    matrix::{
+        FromPoints, Matrix, MatrixSym, get_lower_triangle_matrix_entry,
+        get_lower_triangle_matrix_entry_row_bigger,
+    },
+    node::Node,
+};
+
+/// The storage width of the entries of a compact matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntryWidth {
+    /// Entries in `0..=255`, stored as `u8`.
+    U8,
+    /// Entries in `0..=65535`, stored as `u16`.
+    U16,
+    /// All other entries, stored as [`Distance`].
+    I32,
+}
+
+impl EntryWidth {
+    /// Returns the narrowest width that can store all the distances.
+    pub fn for_distances(distances: &[Distance]) -> Self {
+        let (min, max) = distances.iter().fold((0, 0), |(min, max), distance| {
+            (distance.0.min(min), distance.0.max(max))
+        });
+        if min < 0 || max > u16::MAX as i32 {
+            EntryWidth::I32
+        } else if max > u8::MAX as i32 {
+            EntryWidth::U16
+        } else {
+            EntryWidth::U8
+        }
+    }
+
+    /// Returns the number of bytes per entry.
+    pub fn bytes(self) -> usize {
+        match self {
+            EntryWidth::U8 => 1,
+            EntryWidth::U16 => 2,
+            EntryWidth::I32 => 4,
+        }
+    }
+}
+
+/// Types the entries of a compact matrix are stored in.
+pub trait CompactEntry: Copy {
+    /// The width of the type.
+    const WIDTH: EntryWidth;
+
+    /// Narrows a distance, which has to fit into the type.
+    fn from_distance(distance: Distance) -> Self;
+
+    /// Widens the entry back to a distance.
+    fn to_distance(self) -> Distance;
+}
+
+impl CompactEntry for u8 {
+    const WIDTH: EntryWidth = EntryWidth::U8;
+
+    #[inline(always)]
+    fn from_distance(distance: Distance) -> Self {
+        distance.0 as u8
+    }
+
+    #[inline(always)]
+    fn to_distance(self) -> Distance {
+        Distance(self as i32)
+    }
+}
+
+impl CompactEntry for u16 {
+    const WIDTH: EntryWidth = EntryWidth::U16;
+
+    #[inline(always)]
+    fn from_distance(distance: Distance) -> Self {
+        distance.0 as u16
+    }
+
+    #[inline(always)]
+    fn to_distance(self) -> Distance {
+        Distance(self as i32)
+    }
+}
+
+impl CompactEntry for Distance {
+    const WIDTH: EntryWidth = EntryWidth::I32;
+
+    #[inline(always)]
+    fn from_distance(distance: Distance) -> Self {
+        distance
+    }
+
+    #[inline(always)]
+    fn to_distance(self) -> Distance {
+        self
+    }
+}
+
+/// Narrows the distances if all of them fit into `T`.
+fn narrow<T: CompactEntry>(distances: &[Distance]) -> Option<Vec<T>> {
+    (EntryWidth::for_distances(distances) <= T::WIDTH).then(|| {
+        distances
+            .iter()
+            .map(|&distance| T::from_distance(distance))
+            .collect()
+    })
+}
+
+fn widen<T: CompactEntry>(data: &[T]) -> Vec<Distance> {
+    data.iter().map(|entry| entry.to_distance()).collect()
+}
+
+/// Row major full matrix of distances stored as `T`, the compact counterpart of
+/// [`Matrix<Distance>`].
+#[derive(Debug, Clone)]
+pub struct CompactMatrix<T> {
+    data: Vec<T>,
+    dimension: usize,
+}
+
+impl<T: CompactEntry> CompactMatrix<T> {
+    /// Creates a compact copy of the matrix, or returns `None` if an entry does not fit into `T`.
+    pub fn new(matrix: &Matrix<Distance>) -> Option<Self> {
+        Some(Self {
+            data: narrow(matrix.data())?,
+            dimension: matrix.dimension(),
+        })
+    }
+
+    /// Returns the dimension of the matrix.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the width in which the entries are stored.
+    pub fn entry_width(&self) -> EntryWidth {
+        T::WIDTH
+    }
+
+    /// Returns the stored entries.
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Access the data at (from, to).
+    #[inline(always)]
+    pub fn get_data(&self, from: Node, to: Node) -> Distance {
+        self.data[from.0 * self.dimension + to.0].to_distance()
+    }
+
+    /// Widens the matrix back to a [`Matrix<Distance>`].
+    pub fn to_matrix(&self) -> Matrix<Distance> {
+        Matrix::new(widen(&self.data), self.dimension)
+    }
+}
+
+/// Row major lower triangular matrix of distances stored as `T`, the compact counterpart of
+/// [`MatrixSym<Distance>`].
+#[derive(Debug, Clone)]
+pub struct CompactMatrixSym<T> {
+    data: Vec<T>,
+    dimension: usize,
+}
+
+impl<T: CompactEntry> CompactMatrixSym<T> {
+    /// Creates a compact copy of the matrix, or returns `None` if an entry does not fit into `T`.
+    pub fn new(matrix: &MatrixSym<Distance>) -> Option<Self> {
+        Some(Self {
+            data: narrow(matrix.data())?,
+            dimension: matrix.dimension(),
+        })
+    }
+
+    /// Returns the dimension of the matrix.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the width in which the entries are stored.
+    pub fn entry_width(&self) -> EntryWidth {
+        T::WIDTH
+    }
+
+    /// Returns the stored entries, in the layout of [`MatrixSym::data`].
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Access the data at (from, to).
+    #[inline(always)]
+    pub fn get_data(&self, from: Node, to: Node) -> Distance {
+        self.data[get_lower_triangle_matrix_entry(from.0, to.0)].to_distance()
+    }
+
+    /// Access the data at (from, to), assuming that from >= to.
+    #[inline(always)]
+    pub fn get_data_from_bigger(&self, from: Node, to: Node) -> Distance {
+        debug_assert!(from.0 >= to.0);
+        self.data[get_lower_triangle_matrix_entry_row_bigger(from.0, to.0)].to_distance()
+    }
+
+    /// Widens the matrix back to a [`MatrixSym<Distance>`].
+    pub fn to_matrix_sym(&self) -> MatrixSym<Distance> {
+        MatrixSym::new(widen(&self.data), self.dimension)
+    }
+}
+
+/// A [`CompactMatrix`] in the narrowest width that can store the distances.
+#[derive(Debug, Clone)]
+pub enum AnyCompactMatrix {
+    /// Entries stored as `u8`.
+    U8(CompactMatrix<u8>),
+    /// Entries stored as `u16`.
+    U16(CompactMatrix<u16>),
+    /// Entries stored as [`Distance`].
+    I32(CompactMatrix<Distance>),
+}
+
+impl AnyCompactMatrix {
+    /// Creates a compact copy of the matrix in the narrowest possible width.
+    pub fn new(matrix: &Matrix<Distance>) -> Self {
+        let dimension = matrix.dimension();
+        match EntryWidth::for_distances(matrix.data()) {
+            EntryWidth::U8 => AnyCompactMatrix::U8(CompactMatrix {
+                data: narrow(matrix.data()).expect("The entries fit by the chosen width"),
+                dimension,
+            }),
+            EntryWidth::U16 => AnyCompactMatrix::U16(CompactMatrix {
+                data: narrow(matrix.data()).expect("The entries fit by the chosen width"),
+                dimension,
+            }),
+            EntryWidth::I32 => AnyCompactMatrix::I32(CompactMatrix {
+                data: matrix.data().to_vec(),
+                dimension,
+            }),
+        }
+    }
+
+    /// Returns the dimension of the matrix.
+    pub fn dimension(&self) -> usize {
+        match self {
+            AnyCompactMatrix::U8(matrix) => matrix.dimension(),
+            AnyCompactMatrix::U16(matrix) => matrix.dimension(),
+            AnyCompactMatrix::I32(matrix) => matrix.dimension(),
+        }
+    }
+
+    /// Returns the width in which the entries are stored.
+    pub fn entry_width(&self) -> EntryWidth {
+        match self {
+            AnyCompactMatrix::U8(matrix) => matrix.entry_width(),
+            AnyCompactMatrix::U16(matrix) => matrix.entry_width(),
+            AnyCompactMatrix::I32(matrix) => matrix.entry_width(),
+        }
+    }
+
+    /// Widens the matrix back to a [`Matrix<Distance>`].
+    pub fn to_matrix(&self) -> Matrix<Distance> {
+        match self {
+            AnyCompactMatrix::U8(matrix) => matrix.to_matrix(),
+            AnyCompactMatrix::U16(matrix) => matrix.to_matrix(),
+            AnyCompactMatrix::I32(matrix) => matrix.to_matrix(),
+        }
+    }
+}
+
+impl FromPoints for AnyCompactMatrix {
+    fn from_points<PointType: Sync>(
+        points: &[PointType],
+        distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
+    ) -> Self {
+        Self::new(&Matrix::from_points(points, distance_function))
+    }
+}
+
+/// A [`CompactMatrixSym`] in the narrowest width that can store the distances.
+#[derive(Debug, Clone)]
+pub enum AnyCompactMatrixSym {
+    /// Entries stored as `u8`.
+    U8(CompactMatrixSym<u8>),
+    /// Entries stored as `u16`.
+    U16(CompactMatrixSym<u16>),
+    /// Entries stored as [`Distance`].
+    I32(CompactMatrixSym<Distance>),
+}
+
+impl AnyCompactMatrixSym {
+    /// Creates a compact copy of the matrix in the narrowest possible width.
+    pub fn new(matrix: &MatrixSym<Distance>) -> Self {
+        let dimension = matrix.dimension();
+        match EntryWidth::for_distances(matrix.data()) {
+            EntryWidth::U8 => AnyCompactMatrixSym::U8(CompactMatrixSym {
+                data: narrow(matrix.data()).expect("The entries fit by the chosen width"),
+                dimension,
+            }),
+            EntryWidth::U16 => AnyCompactMatrixSym::U16(CompactMatrixSym {
+                data: narrow(matrix.data()).expect("The entries fit by the chosen width"),
+                dimension,
+            }),
+            EntryWidth::I32 => AnyCompactMatrixSym::I32(CompactMatrixSym {
+                data: matrix.data().to_vec(),
+                dimension,
+            }),
+        }
+    }
+
+    /// Returns the dimension of the matrix.
+    pub fn dimension(&self) -> usize {
+        match self {
+            AnyCompactMatrixSym::U8(matrix) => matrix.dimension(),
+            AnyCompactMatrixSym::U16(matrix) => matrix.dimension(),
+            AnyCompactMatrixSym::I32(matrix) => matrix.dimension(),
+        }
+    }
+
+    /// Returns the width in which the entries are stored.
+    pub fn entry_width(&self) -> EntryWidth {
+        match self {
+            AnyCompactMatrixSym::U8(matrix) => matrix.entry_width(),
+            AnyCompactMatrixSym::U16(matrix) => matrix.entry_width(),
+            AnyCompactMatrixSym::I32(matrix) => matrix.entry_width(),
+        }
+    }
+
+    /// Widens the matrix back to a [`MatrixSym<Distance>`].
+    pub fn to_matrix_sym(&self) -> MatrixSym<Distance> {
+        match self {
+            AnyCompactMatrixSym::U8(matrix) => matrix.to_matrix_sym(),
+            AnyCompactMatrixSym::U16(matrix) => matrix.to_matrix_sym(),
+            AnyCompactMatrixSym::I32(matrix) => matrix.to_matrix_sym(),
+        }
+    }
+}
+
+impl FromPoints for AnyCompactMatrixSym {
+    fn from_points<PointType: Sync>(
+        points: &[PointType],
+        distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
+    ) -> Self {
+        Self::new(&MatrixSym::from_points(points, distance_function))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_width_is_chosen_from_range() {
+        let matrix = |max: i32| {
+            MatrixSym::new_from_distance_function(5, |from, to| {
+                Distance(if from == to {
+                    0
+                } else {
+                    max + 1 - (from.0 + to.0) as i32
+                })
+            })
+        };
+
+        for (max, width) in [
+            (255, EntryWidth::U8),
+            (256, EntryWidth::U16),
+            (65535, EntryWidth::U16),
+            (65536, EntryWidth::I32),
+        ] {
+            let distances = matrix(max);
+            let compact = AnyCompactMatrixSym::new(&distances);
+            assert_eq!(compact.entry_width(), width);
+            assert_eq!(compact.to_matrix_sym().data(), distances.data());
+
+            let full = AnyCompactMatrix::new(&distances.to_edge_data_matrix());
+            assert_eq!(full.entry_width(), width);
+            assert_eq!(
+                full.to_matrix().data(),
+                distances.to_edge_data_matrix().data()
+            );
+        }
+
+        let negative = Matrix::new(vec![Distance(0), Distance(-1), Distance(1), Distance(0)], 2);
+        assert_eq!(
+            AnyCompactMatrix::new(&negative).entry_width(),
+            EntryWidth::I32
+        );
+    }
+
+    #[test]
+    fn test_entries_are_widened_on_access() {
+        let distances = MatrixSym::new_from_distance_function(5, |from, to| {
+            Distance(if from == to {
+                0
+            } else {
+                300 - (from.0 + to.0) as i32
+            })
+        });
+        assert!(CompactMatrixSym::<u8>::new(&distances).is_none());
+
+        let compact = CompactMatrixSym::<u16>::new(&distances).unwrap();
+        assert_eq!(compact.data().len(), distances.data().len());
+        assert_eq!(compact.get_data(Node(1), Node(3)), Distance(296));
+        assert_eq!(
+            compact.get_data_from_bigger(Node(4), Node(0)),
+            Distance(296)
+        );
+
+        let full = CompactMatrix::<u16>::new(&distances.to_edge_data_matrix()).unwrap();
+        assert_eq!(full.get_data(Node(2), Node(1)), Distance(297));
+        assert!(CompactMatrix::<Distance>::new(&distances.to_edge_data_matrix()).is_some());
+    }
+}
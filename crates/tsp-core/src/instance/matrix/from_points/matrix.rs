@@ -1,34 +1,37 @@
-use std::cell::UnsafeCell;
-
 use log::trace;
-use tsp_core::instance::{InstanceMetadata, distance::Distance, matrix::Matrix};
 
-use super::ParseFromTSPLib;
-use crate::distance_container::find_row_column_from_lower_triangle_index;
+use super::{FromPoints, find_row_column_from_lower_triangle_index};
+use crate::instance::{
+    distance::{Distance, DistanceValue},
+    matrix::Matrix,
+};
 
 // TODO: Add more fine grained benchmarks to determine optimal parallelism bound
 const PARALLELISM_BOUND: usize = 100_000;
 
-impl ParseFromTSPLib for Matrix<Distance> {
-    fn from_node_coord_section<PointType: Sync + Send>(
-        node_data: &Vec<PointType>,
-        metadata: &InstanceMetadata,
+impl<D: DistanceValue> FromPoints for Matrix<D> {
+    fn from_points<PointType: Sync>(
+        points: &[PointType],
         distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
     ) -> Self {
-        compute_dists_from_node_coords(&node_data, metadata.dimension, distance_function)
+        compute_dists_from_node_coords(points, distance_function)
     }
 }
 
-/// TODO: Add documentation
-fn compute_dists_from_node_coords<PointType: Send + Sync>(
+/// Computes the full distance matrix by computing the lower triangle (in parallel, for bigger
+/// instances) and mirroring it.
+fn compute_dists_from_node_coords<D: DistanceValue, PointType: Sync>(
     point_data: &[PointType],
-    dimension: usize,
     distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
-) -> Matrix<Distance> {
+) -> Matrix<D> {
+    let dimension = point_data.len();
+    if dimension == 0 {
+        return Matrix::new(Vec::new(), 0);
+    }
     let total_size = dimension * dimension;
     let number_of_entries = (dimension * (dimension + 1)) / 2;
 
-    let mut distance_data = vec![Distance(0); total_size];
+    let mut distance_data = vec![D::ZERO; total_size];
 
     if total_size < PARALLELISM_BOUND {
         compute_dists_from_node_coords_chunk(
@@ -93,7 +96,7 @@ fn compute_dists_from_node_coords<PointType: Send + Sync>(
                         number_of_entries_current_chunk,
                         distance_function,
                         dimension,
-                    )
+                    );
                 });
 
                 current_first_entry_index += entries_per_chunk;
@@ -113,8 +116,8 @@ fn compute_dists_from_node_coords<PointType: Send + Sync>(
 }
 
 #[inline(always)]
-fn compute_dists_from_node_coords_chunk<PointType>(
-    chunk: &mut [Distance],
+fn compute_dists_from_node_coords_chunk<D: DistanceValue, PointType>(
+    chunk: &mut [D],
     point_data: &[PointType],
     chunk_start_index: usize,
     first_entry_index: usize,
@@ -122,6 +125,11 @@ fn compute_dists_from_node_coords_chunk<PointType>(
     distance_function: impl Fn(&PointType, &PointType) -> Distance + Copy,
     dimension: usize,
 ) {
+    let mut chunk = DistanceChunk {
+        data: chunk,
+        start_index: chunk_start_index,
+        dimension,
+    };
     let (start_row, start_column) = find_row_column_from_lower_triangle_index(first_entry_index);
     let (end_row, end_column) = find_row_column_from_lower_triangle_index(
         first_entry_index + number_of_entries_in_chunk - 1,
@@ -141,14 +149,12 @@ fn compute_dists_from_node_coords_chunk<PointType>(
         .skip(start_column)
     {
         compute_and_set_distance(
-            chunk,
+            &mut chunk,
             start_row,
             column,
-            chunk_start_index,
             start_row_point_data,
             column_point_data,
             distance_function,
-            dimension,
         );
     }
 
@@ -157,14 +163,12 @@ fn compute_dists_from_node_coords_chunk<PointType>(
         // We can omit the column = start_row case, as it is always zero distance
         for (column, column_point_data) in point_data.iter().enumerate().take(row) {
             compute_and_set_distance(
-                chunk,
+                &mut chunk,
                 row,
                 column,
-                chunk_start_index,
                 row_point_data,
                 column_point_data,
                 distance_function,
-                dimension,
             );
         }
     }
@@ -173,53 +177,50 @@ fn compute_dists_from_node_coords_chunk<PointType>(
     // We take() to only iterate up to end_column, since it's one indexed, we add 1
     for (column, column_point_data) in point_data.iter().enumerate().take(end_column + 1) {
         compute_and_set_distance(
-            chunk,
+            &mut chunk,
             end_row,
             column,
-            chunk_start_index,
             end_row_point_data,
             column_point_data,
             distance_function,
-            dimension,
         );
     }
 }
 
 #[inline(always)]
-fn compute_and_set_distance<PointType>(
-    chunk: &mut [Distance],
+fn compute_and_set_distance<D: DistanceValue, PointType>(
+    chunk: &mut DistanceChunk<D>,
     row: usize,
     column: usize,
-    chunk_start_index: usize,
     row_point_data: &PointType,
     column_point_data: &PointType,
     distance_function: impl Fn(&PointType, &PointType) -> Distance,
-    dimension: usize,
 ) {
-    let distance = distance_function(row_point_data, column_point_data);
+    let distance = D::from(distance_function(row_point_data, column_point_data));
 
-    set_distance(chunk, distance, row, column, chunk_start_index, dimension);
+    chunk.set_distance(distance, row, column);
 }
 
-#[inline(always)]
-fn set_distance(
-    chunk: &mut [Distance],
-    distance: Distance,
-    row: usize,
-    column: usize,
-    chunk_start_index: usize,
+/// A part of the row-major distance data of a matrix, starting at `start_index`.
+struct DistanceChunk<'a, D> {
+    data: &'a mut [D],
+    start_index: usize,
     dimension: usize,
-) {
-    let index_in_chunk = row * dimension + column - chunk_start_index;
-
-    debug_assert!(
-        chunk.len() > index_in_chunk,
-        "Computed index {} for i: {}, j: {} is out of bounds for distance data of length {}",
-        index_in_chunk,
-        row,
-        column,
-        chunk.len()
-    );
-    // Safety: Index is computed to be within bounds of distance_data
-    unsafe { *chunk.get_unchecked_mut(index_in_chunk) = distance };
+}
+
+impl<D: DistanceValue> DistanceChunk<'_, D> {
+    #[inline(always)]
+    fn set_distance(&mut self, distance: D, row: usize, column: usize) {
+        let index_in_chunk = row * self.dimension + column - self.start_index;
+
+        debug_assert!(
+            self.data.len() > index_in_chunk,
+            "Computed index {} for i: {}, j: {} is out of bounds for distance data of length {}",
+            index_in_chunk,
+            row,
+            column,
+            self.data.len()
+        );
+        self.data[index_in_chunk] = distance;
+    }
 }
@@ -1,33 +1,32 @@
-use tsp_core::instance::{
-    InstanceMetadata,
-    distance::Distance,
+use super::{FromPoints, find_row_column_from_lower_triangle_index};
+use crate::instance::{
+    distance::{Distance, DistanceValue},
     matrix::{MatrixSym, get_lower_triangle_matrix_entry_row_bigger},
 };
 
-use super::ParseFromTSPLib;
-use crate::distance_container::find_row_column_from_lower_triangle_index;
-
 // TODO: Add more fine grained benchmarks to determine optimal parallelism bound
 const PARALLELISM_BOUND: usize = 300_000;
 
-impl ParseFromTSPLib for MatrixSym<Distance> {
-    fn from_node_coord_section<PointType: Sync + Send>(
-        node_data: &Vec<PointType>,
-        metadata: &InstanceMetadata,
+impl<D: DistanceValue> FromPoints for MatrixSym<D> {
+    fn from_points<PointType: Sync>(
+        points: &[PointType],
         distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
     ) -> Self {
-        compute_dists_from_node_coords(&node_data, metadata.dimension, distance_function)
+        compute_dists_from_node_coords(points, distance_function)
     }
 }
 
-fn compute_dists_from_node_coords<PointType: Send + Sync>(
+fn compute_dists_from_node_coords<D: DistanceValue, PointType: Sync>(
     point_data: &[PointType],
-    dimension: usize,
     distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
-) -> MatrixSym<Distance> {
+) -> MatrixSym<D> {
+    let dimension = point_data.len();
+    if dimension == 0 {
+        return MatrixSym::new(Vec::new(), 0);
+    }
     let total_size = dimension * (dimension + 1) / 2;
 
-    let mut distance_data = vec![Distance(0); total_size];
+    let mut distance_data = vec![D::ZERO; total_size];
 
     if total_size < PARALLELISM_BOUND {
         compute_dists_from_node_coords_chunk(&mut distance_data, point_data, 0, distance_function);
@@ -45,7 +44,7 @@ fn compute_dists_from_node_coords<PointType: Send + Sync>(
                         point_data,
                         current_chunk_start,
                         distance_function,
-                    )
+                    );
                 });
 
                 current_chunk_start += chunk_size;
@@ -57,8 +56,8 @@ fn compute_dists_from_node_coords<PointType: Send + Sync>(
 }
 
 #[inline(always)]
-fn compute_dists_from_node_coords_chunk<PointType>(
-    chunk: &mut [Distance],
+fn compute_dists_from_node_coords_chunk<D: DistanceValue, PointType>(
+    chunk: &mut [D],
     point_data: &[PointType],
     chunk_start_index: usize,
     distance_function: impl Fn(&PointType, &PointType) -> Distance + Copy,
@@ -119,8 +118,8 @@ fn compute_dists_from_node_coords_chunk<PointType>(
 }
 
 #[inline(always)]
-fn compute_and_set_distance<PointType>(
-    chunk: &mut [Distance],
+fn compute_and_set_distance<D: DistanceValue, PointType>(
+    chunk: &mut [D],
     row: usize,
     column: usize,
     chunk_start_index: usize,
@@ -128,15 +127,15 @@ fn compute_and_set_distance<PointType>(
     column_point_data: &PointType,
     distance_function: impl Fn(&PointType, &PointType) -> Distance,
 ) {
-    let distance = distance_function(row_point_data, column_point_data);
+    let distance = D::from(distance_function(row_point_data, column_point_data));
 
     set_distance(chunk, distance, row, column, chunk_start_index);
 }
 
 #[inline(always)]
-fn set_distance(
-    chunk: &mut [Distance],
-    distance: Distance,
+fn set_distance<D: DistanceValue>(
+    chunk: &mut [D],
+    distance: D,
     row: usize,
     column: usize,
     chunk_start_index: usize,
@@ -152,6 +151,5 @@ fn set_distance(
         column,
         chunk.len()
     );
-    // Safety: Index is computed to be within bounds of distance_data
-    unsafe { *chunk.get_unchecked_mut(index_in_chunk) = distance };
+    chunk[index_in_chunk] = distance;
 }
@@ -0,0 +1,96 @@
+//! Computation of distance containers from node coordinates.
+//!
+//! For bigger instances, the distances are computed in parallel using scoped threads.
+
+use crate::{
+    instance::{
+        distance::Distance,
+        point::{GeoPoint, Point2D, Point3D},
+    },
+    metric::{self, Metric, Metric2D, Metric3D},
+};
+
+mod matrix;
+mod matrix_sym;
+
+/// Distance containers that can be computed from node coordinates and a distance function.
+pub trait FromPoints: Sized {
+    /// Computes the distances between all pairs of points using the given distance function.
+    ///
+    /// The distance function is assumed to be symmetric, that is, only the distances for one
+    /// direction are computed. The dimension of the resulting container is the number of points.
+    fn from_points<PointType: Sync>(
+        points: &[PointType],
+        distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
+    ) -> Self;
+
+    /// Computes the distances between all pairs of points using the given metric.
+    fn from_metric<M: Metric + Copy + Sync + Send>(points: &[M::Point], metric: M) -> Self
+    where
+        M::Point: Sync,
+    {
+        Self::from_points(points, move |point_a, point_b| {
+            metric.distance(point_a, point_b)
+        })
+    }
+
+    /// Computes the distances between all pairs of 2D points using the given TSPLIB95 metric.
+    ///
+    /// The metric is resolved once, so that the distance computation is monomorphized for each
+    /// metric instead of dispatching on every pair of points.
+    fn from_points_2d(points: &[Point2D], metric: Metric2D) -> Self {
+        match metric {
+            Metric2D::Euclidean => Self::from_metric(points, metric::Euclidean2D),
+            Metric2D::Maximum => Self::from_metric(points, metric::Maximum2D),
+            Metric2D::Manhattan => Self::from_metric(points, metric::Manhattan2D),
+            Metric2D::Ceil => Self::from_metric(points, metric::Ceil2D),
+            Metric2D::Floor => Self::from_metric(points, metric::Floor2D),
+            Metric2D::Att => Self::from_metric(points, metric::Att),
+            Metric2D::Geographical => Self::from_metric(
+                &convert_points(points, metric::convert_to_geo_coordinates),
+                metric::Geographical,
+            ),
+            Metric2D::Geom => Self::from_metric(
+                &convert_points(points, metric::convert_degrees_to_geo_coordinates),
+                metric::Geom,
+            ),
+            Metric2D::GeomMeeus => Self::from_metric(
+                &convert_points(points, metric::convert_degrees_to_geo_coordinates),
+                metric::GeomMeeus,
+            ),
+            Metric2D::GeoMeeus => Self::from_metric(
+                &convert_points(points, metric::convert_to_geo_coordinates),
+                metric::GeoMeeus,
+            ),
+            Metric2D::Toroidal { grid_size } => {
+                Self::from_metric(points, metric::Toroidal2D { grid_size })
+            }
+        }
+    }
+
+    /// Computes the distances between all pairs of 3D points using the given TSPLIB95 metric.
+    fn from_points_3d(points: &[Point3D], metric: Metric3D) -> Self {
+        match metric {
+            Metric3D::Euclidean => Self::from_metric(points, metric::Euclidean3D),
+            Metric3D::Maximum => Self::from_metric(points, metric::Maximum3D),
+            Metric3D::Manhattan => Self::from_metric(points, metric::Manhattan3D),
+            Metric3D::Ceil => Self::from_metric(points, metric::Ceil3D),
+            Metric3D::Floor => Self::from_metric(points, metric::Floor3D),
+            Metric3D::Toroidal { grid_size } => {
+                Self::from_metric(points, metric::Toroidal3D { grid_size })
+            }
+        }
+    }
+}
+
+/// Converts the points into geographical coordinates once, instead of for every pair of points.
+fn convert_points(points: &[Point2D], conversion: impl Fn(&Point2D) -> GeoPoint) -> Vec<GeoPoint> {
+    // TODO(perf): Possibly parallelize the conversion to geo coordinates
+    points.iter().map(conversion).collect()
+}
+
+fn find_row_column_from_lower_triangle_index(index: usize) -> (usize, usize) {
+    let row = (-0.5 + ((0.25 + 2.0 * index as f64).sqrt())).floor() as usize;
+    let column = index - (row * (row + 1)) / 2;
+    (row, column)
+}
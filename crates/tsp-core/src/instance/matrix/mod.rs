@@ -2,9 +2,17 @@ use std::fmt::Display;
 
 use crate::instance::node::Node;
 
+mod compact;
+mod from_points;
 pub(crate) mod symmetric;
+pub use compact::{
+    AnyCompactMatrix, AnyCompactMatrixSym, CompactEntry, CompactMatrix, CompactMatrixSym,
+    EntryWidth,
+};
+pub use from_points::FromPoints;
 pub use symmetric::{
-    MatrixSym, get_lower_triangle_matrix_entry, get_lower_triangle_matrix_entry_row_bigger,
+    MatrixSym, MatrixSymViewRestricted, get_lower_triangle_matrix_entry,
+    get_lower_triangle_matrix_entry_row_bigger,
 };
 
 #[derive(Debug, Clone)]
@@ -14,6 +22,11 @@ pub use symmetric::{
 ///
 /// The underlying data is guaranteed to have length dimension * dimension.
 /// That is, data from node i (row) to node j (column) is at index (i * dimension + j).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "crate::instance::serialization::MatrixData<Data>")
+)]
 pub struct Matrix<Data> {
     data: Vec<Data>,
     dimension: usize,
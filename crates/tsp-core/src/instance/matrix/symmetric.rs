@@ -7,6 +7,11 @@ use crate::instance::{matrix::Matrix, node::Node};
 /// The underlying data is guaranteed to have length dimension * (dimension + 1) / 2 where dimension
 /// is the number of nodes.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "crate::instance::serialization::MatrixSymData<Data>")
+)]
 pub struct MatrixSym<Data> {
     data: Vec<Data>,
     dimension: usize,
@@ -21,6 +26,12 @@ impl<Data> MatrixSym<Data> {
         Self { data, dimension }
     }
 
+    /// Returns the dimension of the matrix. That is, the number of nodes, which is the same as the
+    /// number of rows and columns.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
     /// Returns a reference to the underlying data.
     pub fn data(&self) -> &Vec<Data> {
         &self.data
@@ -154,6 +165,12 @@ pub struct MatrixSymViewRestricted<'a, Data> {
 }
 
 impl<'a, Data: Copy> MatrixSymViewRestricted<'a, Data> {
+    /// Returns the number of nodes of the view.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Access the data at (from, to).
     #[inline(always)]
     pub fn get_data(&self, from: Node, to: Node) -> Data {
         debug_assert!(
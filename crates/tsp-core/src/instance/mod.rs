@@ -5,6 +5,7 @@ use crate::{
         distance::Distance,
         edge::UnEdge,
         matrix::{Matrix, MatrixSym},
+        point::Point2D,
     },
     tsp_lib_spec::{
         DisplayDataType, EdgeDataFormat, EdgeWeightFormat, EdgeWeightType, NodeCoordType,
@@ -12,12 +13,35 @@ use crate::{
     },
 };
 
+mod build;
+pub mod candidates;
+pub mod comparison;
+pub mod delaunay;
 pub mod distance;
 pub mod edge;
+pub mod kd_tree;
 pub mod matrix;
 pub mod node;
+pub mod oracle;
+pub mod point;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod sparse;
+pub mod stats;
+pub mod tour;
+pub mod transform;
+
+pub use build::BuildError;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "crate::instance::serialization::TSPSymInstanceData<DistanceContainer>",
+        bound(deserialize = "DistanceContainer: serde::Deserialize<'de> + oracle::DistanceOracle")
+    )
+)]
 pub struct TSPSymInstance<DistanceContainer> {
     metadata: InstanceMetadata,
     /// Flattened distance matrix
@@ -25,6 +49,10 @@ pub struct TSPSymInstance<DistanceContainer> {
     /// Row major order, i.e. distance from node i to node j is at index (i * num_nodes + j).
     /// Node indexing starts at 0.
     distances: DistanceContainer,
+    /// 2D coordinates of the nodes used for displaying the instance, if available.
+    ///
+    /// Indexed by node, i.e. the display coordinates of node i are at index i.
+    display_data: Option<Vec<Point2D>>,
 }
 
 impl<DistanceContainer> TSPSymInstance<DistanceContainer> {
@@ -33,30 +61,53 @@ impl<DistanceContainer> TSPSymInstance<DistanceContainer> {
         Self {
             metadata,
             distances: distance_container,
+            display_data: None,
         }
     }
 
+    /// Attaches 2D display coordinates to the instance.
+    ///
+    /// Panics if the number of points does not equal the dimension of the instance.
+    pub fn with_display_data(mut self, display_data: Vec<Point2D>) -> Self {
+        assert_eq!(display_data.len(), self.metadata.dimension);
+        self.display_data = Some(display_data);
+        self
+    }
+
     pub fn metadata(&self) -> &InstanceMetadata {
         &self.metadata
     }
+
+    /// Returns the distance container.
+    pub fn distances(&self) -> &DistanceContainer {
+        &self.distances
+    }
+
+    /// Returns the 2D display coordinates of the nodes, if available.
+    ///
+    /// For instances with `DISPLAY_DATA_TYPE: COORD_DISPLAY` (the default for instances with 2D
+    /// node coordinates), these are the node coordinates themselves.
+    pub fn display_data(&self) -> Option<&[Point2D]> {
+        self.display_data.as_deref()
+    }
 }
 
-impl TSPSymInstance<MatrixSym<Distance>> {
-    pub fn raw_distances(&self) -> &[Distance] {
+impl<D> TSPSymInstance<MatrixSym<D>> {
+    pub fn raw_distances(&self) -> &[D] {
         self.distances.data()
     }
 
-    pub fn distance_matrix(&self) -> &MatrixSym<Distance> {
+    pub fn distance_matrix(&self) -> &MatrixSym<D> {
         &self.distances
     }
 }
 
-impl TSPSymInstance<Matrix<Distance>> {
-    pub fn raw_distances(&self) -> &[Distance] {
+impl<D> TSPSymInstance<Matrix<D>> {
+    pub fn raw_distances(&self) -> &[D] {
         self.distances.data()
     }
 
-    pub fn distance_matrix(&self) -> &Matrix<Distance> {
+    pub fn distance_matrix(&self) -> &Matrix<D> {
         &self.distances
     }
 }
@@ -73,6 +124,7 @@ impl<DistanceContainer: Display> Display for TSPSymInstance<DistanceContainer> {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstanceMetadata {
     pub name: String,
     pub problem_type: ProblemType,
@@ -85,27 +137,28 @@ pub struct InstanceMetadata {
     /// Defaults to NO_COORDS
     pub node_coord_type: NodeCoordType,
     pub display_data_type: Option<DisplayDataType>,
+    /// Side length of the torus for the `TOR_2D` and `TOR_3D` edge weight types (LKH extension)
+    pub grid_size: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
-pub struct UnTour {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnTour<D = Distance> {
     pub edges: Vec<UnEdge>,
-    pub cost: Distance,
+    pub cost: D,
 }
 
-impl PartialEq for UnTour {
+impl<D: PartialEq> PartialEq for UnTour<D> {
     fn eq(&self, other: &Self) -> bool {
         self.cost == other.cost && self.edges.len() == other.edges.len() && {
-            let mut res = true;
-            for edge in &self.edges {
-                if !other.edges.contains(edge) {
-                    res = false;
-                    break;
-                }
-            }
-            res
+            // Sorting normalizes the edge order, and `UnEdge` ignores the direction of edges
+            let mut edges = self.edges.clone();
+            let mut other_edges = other.edges.clone();
+            edges.sort_unstable();
+            other_edges.sort_unstable();
+            edges == other_edges
         }
     }
 }
 
-impl Eq for UnTour {}
+impl<D: Eq> Eq for UnTour<D> {}
@@ -0,0 +1,252 @@
+//! A common interface for everything that can answer distance queries.
+//!
+//! Solvers take a [`DistanceOracle`] instead of a concrete container, so the same solver works
+//! with full matrices, symmetric (lower triangular) matrices, restricted views and distances that
+//! are computed on demand from node coordinates.
+
+use crate::{
+    instance::{
+        TSPSymInstance,
+        distance::{Distance, DistanceValue},
+        matrix::{
+            CompactEntry, CompactMatrix, CompactMatrixSym, Matrix, MatrixSym,
+            MatrixSymViewRestricted,
+        },
+        node::Node,
+    },
+    metric::Metric,
+};
+
+/// Answers distance queries between the nodes `0..dimension`.
+pub trait DistanceOracle {
+    /// The type of the distances.
+    type Value: Copy;
+
+    /// Returns the number of nodes.
+    fn dimension(&self) -> usize;
+
+    /// Returns the distance from `from` to `to`.
+    fn get(&self, from: Node, to: Node) -> Self::Value;
+
+    /// Returns the distances from `from` to all nodes, if they are stored contiguously.
+    ///
+    /// Containers that do not store full rows (e.g. symmetric matrices or coordinate-backed
+    /// oracles) return `None`, in which case [`DistanceOracle::get`] has to be used.
+    #[inline(always)]
+    fn row(&self, from: Node) -> Option<&[Self::Value]> {
+        let _ = from;
+        None
+    }
+}
+
+impl<O: DistanceOracle + ?Sized> DistanceOracle for &O {
+    type Value = O::Value;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        (**self).dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> Self::Value {
+        (**self).get(from, to)
+    }
+
+    #[inline(always)]
+    fn row(&self, from: Node) -> Option<&[Self::Value]> {
+        (**self).row(from)
+    }
+}
+
+impl<D: Copy> DistanceOracle for Matrix<D> {
+    type Value = D;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> D {
+        self.get_data(from, to)
+    }
+
+    #[inline(always)]
+    fn row(&self, from: Node) -> Option<&[D]> {
+        Some(self.get_adjacency_list(from))
+    }
+}
+
+impl<D: Copy> DistanceOracle for MatrixSym<D> {
+    type Value = D;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> D {
+        self.get_data(from, to)
+    }
+}
+
+impl<D: Copy> DistanceOracle for MatrixSymViewRestricted<'_, D> {
+    type Value = D;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> D {
+        self.get_data(from, to)
+    }
+}
+
+impl<T: CompactEntry> DistanceOracle for CompactMatrix<T> {
+    type Value = Distance;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> Distance {
+        self.get_data(from, to)
+    }
+}
+
+impl<T: CompactEntry> DistanceOracle for CompactMatrixSym<T> {
+    type Value = Distance;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> Distance {
+        self.get_data(from, to)
+    }
+}
+
+impl<C: DistanceOracle> DistanceOracle for TSPSymInstance<C> {
+    type Value = C::Value;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.distances.dimension()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> Self::Value {
+        self.distances.get(from, to)
+    }
+
+    #[inline(always)]
+    fn row(&self, from: Node) -> Option<&[Self::Value]> {
+        self.distances.row(from)
+    }
+}
+
+/// Computes distances on demand from node coordinates, without storing any matrix.
+///
+/// Takes `O(dimension)` memory instead of the `O(dimension^2)` of a matrix, at the cost of
+/// evaluating the metric on every query.
+#[derive(Debug, Clone)]
+pub struct MetricOracle<M: Metric, D = Distance> {
+    points: Vec<M::Point>,
+    metric: M,
+    _distance: std::marker::PhantomData<D>,
+}
+
+impl<M: Metric, D: DistanceValue> MetricOracle<M, D> {
+    /// Creates an oracle for the points, node `i` being at `points[i]`.
+    pub fn new(points: Vec<M::Point>, metric: M) -> Self {
+        Self {
+            points,
+            metric,
+            _distance: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the node coordinates.
+    pub fn points(&self) -> &[M::Point] {
+        &self.points
+    }
+
+    /// Returns the metric.
+    pub fn metric(&self) -> &M {
+        &self.metric
+    }
+}
+
+impl<M: Metric, D: DistanceValue> DistanceOracle for MetricOracle<M, D> {
+    type Value = D;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.points.len()
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> D {
+        D::from(
+            self.metric
+                .distance(&self.points[from.0], &self.points[to.0]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{instance::point::Point2D, metric::Euclidean2D};
+
+    fn total_distance<O: DistanceOracle<Value = Distance>>(oracle: O) -> Distance {
+        (0..oracle.dimension())
+            .flat_map(|from| (0..oracle.dimension()).map(move |to| (Node(from), Node(to))))
+            .map(|(from, to)| oracle.get(from, to))
+            .sum()
+    }
+
+    #[test]
+    fn test_containers_agree() {
+        let points = (0..7)
+            .map(|index| Point2D {
+                x: (index * index % 5) as f64 * 3.0,
+                y: index as f64 * 2.0,
+            })
+            .collect::<Vec<_>>();
+        let oracle: MetricOracle<_> = MetricOracle::new(points, Euclidean2D);
+        let matrix = Matrix::new_from_distance_function(7, |from, to| oracle.get(from, to));
+        let matrix_sym = MatrixSym::new_from_distance_function(7, |from, to| oracle.get(from, to));
+
+        let expected = total_distance(&oracle);
+        assert_eq!(total_distance(&matrix), expected);
+        assert_eq!(total_distance(&matrix_sym), expected);
+        assert_eq!(
+            total_distance(CompactMatrix::<u8>::new(&matrix).unwrap()),
+            expected
+        );
+        assert_eq!(
+            total_distance(CompactMatrixSym::<u16>::new(&matrix_sym).unwrap()),
+            expected
+        );
+        assert_eq!(
+            matrix.row(Node(3)).unwrap()[5],
+            matrix_sym.get(Node(5), Node(3))
+        );
+        assert!(matrix_sym.row(Node(3)).is_none());
+
+        let restricted = matrix_sym.restrict_to_first_n(4);
+        assert_eq!(restricted.dimension(), 4);
+        assert_eq!(
+            restricted.get(Node(1), Node(3)),
+            oracle.get(Node(3), Node(1))
+        );
+    }
+}
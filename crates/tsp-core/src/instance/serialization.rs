@@ -0,0 +1,264 @@
+//! Validated deserialization for the types with invariants, used with the `serde` feature.
+//!
+//! Matrices and tours are deserialized into their plain data first and checked afterwards, so
+//! corrupted input results in an error instead of a matrix with the wrong number of entries or a
+//! tour that is not a permutation, and instances are checked against the dimension in their
+//! metadata. Matrices are encoded as their dimension and flat data (only
+//! the lower triangle for [`MatrixSym`]), which is as compact as the in-memory layout.
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::instance::{
+    InstanceMetadata, TSPSymInstance,
+    distance::DistanceValue,
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+    oracle::DistanceOracle,
+    point::Point2D,
+    tour::{Tour, TourError},
+};
+
+/// The length of the deserialized matrix data does not match its dimension.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Matrix data of length {length} does not match dimension {dimension}")]
+pub struct MatrixLengthError {
+    length: usize,
+    dimension: usize,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MatrixData<Data> {
+    data: Vec<Data>,
+    dimension: usize,
+}
+
+impl<Data> TryFrom<MatrixData<Data>> for Matrix<Data> {
+    type Error = MatrixLengthError;
+
+    fn try_from(raw: MatrixData<Data>) -> Result<Self, Self::Error> {
+        if raw.dimension.checked_mul(raw.dimension) != Some(raw.data.len()) {
+            return Err(MatrixLengthError {
+                length: raw.data.len(),
+                dimension: raw.dimension,
+            });
+        }
+        Ok(Matrix::new(raw.data, raw.dimension))
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MatrixSymData<Data> {
+    data: Vec<Data>,
+    dimension: usize,
+}
+
+impl<Data> TryFrom<MatrixSymData<Data>> for MatrixSym<Data> {
+    type Error = MatrixLengthError;
+
+    fn try_from(raw: MatrixSymData<Data>) -> Result<Self, Self::Error> {
+        let expected = raw
+            .dimension
+            .checked_add(1)
+            .and_then(|next| next.checked_mul(raw.dimension))
+            .map(|size| size / 2);
+        if expected != Some(raw.data.len()) {
+            return Err(MatrixLengthError {
+                length: raw.data.len(),
+                dimension: raw.dimension,
+            });
+        }
+        Ok(MatrixSym::new(raw.data, raw.dimension))
+    }
+}
+
+/// A deserialized instance whose parts do not match the dimension in its metadata.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InstanceDataError {
+    /// The distance container has a different dimension than the metadata.
+    #[error("Distances of dimension {distances} do not match dimension {dimension}")]
+    DistanceDimension {
+        /// Dimension of the distance container
+        distances: usize,
+        /// Dimension in the metadata
+        dimension: usize,
+    },
+    /// The display data does not have one point per node.
+    #[error("Display data of length {length} does not match dimension {dimension}")]
+    DisplayDataLength {
+        /// Number of display points
+        length: usize,
+        /// Dimension in the metadata
+        dimension: usize,
+    },
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TSPSymInstanceData<DistanceContainer> {
+    metadata: InstanceMetadata,
+    distances: DistanceContainer,
+    display_data: Option<Vec<Point2D>>,
+}
+
+impl<DistanceContainer: DistanceOracle> TryFrom<TSPSymInstanceData<DistanceContainer>>
+    for TSPSymInstance<DistanceContainer>
+{
+    type Error = InstanceDataError;
+
+    fn try_from(raw: TSPSymInstanceData<DistanceContainer>) -> Result<Self, Self::Error> {
+        let dimension = raw.metadata.dimension;
+        if raw.distances.dimension() != dimension {
+            return Err(InstanceDataError::DistanceDimension {
+                distances: raw.distances.dimension(),
+                dimension,
+            });
+        }
+        if let Some(display_data) = &raw.display_data
+            && display_data.len() != dimension
+        {
+            return Err(InstanceDataError::DisplayDataLength {
+                length: display_data.len(),
+                dimension,
+            });
+        }
+        Ok(Self {
+            metadata: raw.metadata,
+            distances: raw.distances,
+            display_data: raw.display_data,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TourData<D> {
+    nodes: Vec<Node>,
+    cost: D,
+}
+
+impl<D: DistanceValue> TryFrom<TourData<D>> for Tour<D> {
+    type Error = TourError;
+
+    fn try_from(raw: TourData<D>) -> Result<Self, Self::Error> {
+        Tour::new(raw.nodes, raw.cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instance::{
+            InstanceMetadata, TSPSymInstance, UnTour,
+            distance::{Distance, Distance64, FloatDistance},
+            edge::UnEdge,
+            point::Point2D,
+        },
+        tsp_lib_spec::{DisplayDataType, EdgeWeightType, NodeCoordType, ProblemType},
+    };
+
+    fn instance() -> TSPSymInstance<MatrixSym<Distance>> {
+        let metadata = InstanceMetadata {
+            name: "triangle".to_string(),
+            problem_type: ProblemType::TSP,
+            comment: Some("Three nodes".to_string()),
+            dimension: 3,
+            capacity: None,
+            edge_weight_type: EdgeWeightType::EUC_2D,
+            edge_weight_format: None,
+            edge_data_format: None,
+            node_coord_type: NodeCoordType::TWOD_COORDS,
+            display_data_type: Some(DisplayDataType::COORD_DISPLAY),
+            grid_size: None,
+        };
+        let distances = MatrixSym::new([0, 3, 0, 4, 5, 0].map(Distance).to_vec(), 3);
+        TSPSymInstance::new(distances, metadata).with_display_data(vec![
+            Point2D { x: 0.0, y: 0.0 },
+            Point2D { x: 3.0, y: 0.0 },
+            Point2D { x: 0.0, y: 4.0 },
+        ])
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let instance = instance();
+        let json = serde_json::to_string(&instance).unwrap();
+        // Newtypes are transparent and the matrix is stored as its flat lower triangle
+        assert!(json.contains(r#""distances":{"data":[0,3,0,4,5,0],"dimension":3}"#));
+
+        let decoded: TSPSymInstance<MatrixSym<Distance>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{decoded:?}"), format!("{instance:?}"));
+
+        let tour = UnTour {
+            edges: vec![
+                UnEdge::from((0, 1)),
+                UnEdge::from((1, 2)),
+                UnEdge::from((2, 0)),
+            ],
+            cost: FloatDistance(12.0),
+        };
+        let json = serde_json::to_string(&tour).unwrap();
+        assert_eq!(
+            json,
+            r#"{"edges":[{"from":0,"to":1},{"from":1,"to":2},{"from":2,"to":0}],"cost":12.0}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<UnTour<FloatDistance>>(&json).unwrap(),
+            tour
+        );
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let matrix = Matrix::new_from_distance_function(4, |from, to| {
+            Distance64((from.0 * 10 + to.0) as i64)
+        });
+        let bytes = bincode::serialize(&matrix).unwrap();
+        // Length prefix, the entries and the dimension, without any per-entry overhead
+        assert_eq!(bytes.len(), 8 + 16 * 8 + 8);
+        let decoded: Matrix<Distance64> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.data(), matrix.data());
+
+        let tour = Tour::new([2, 0, 3, 1].map(Node).to_vec(), Distance(7)).unwrap();
+        let decoded: Tour = bincode::deserialize(&bincode::serialize(&tour).unwrap()).unwrap();
+        assert_eq!(decoded, tour);
+    }
+
+    #[test]
+    fn test_invalid_data_is_rejected() {
+        let error = serde_json::from_str::<Matrix<Distance>>(r#"{"data":[0,1,2],"dimension":2}"#)
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Matrix data of length 3 does not match dimension 2")
+        );
+        assert!(
+            serde_json::from_str::<MatrixSym<Distance>>(r#"{"data":[0,1],"dimension":2}"#).is_err()
+        );
+
+        let json = serde_json::to_string(&instance()).unwrap();
+        let wrong_dimension = json.replace(r#""dimension":3,"#, r#""dimension":4,"#);
+        let error = serde_json::from_str::<TSPSymInstance<MatrixSym<Distance>>>(&wrong_dimension)
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Distances of dimension 3 do not match dimension 4")
+        );
+        let missing_point = json.replace(r#",{"x":0.0,"y":4.0}"#, "");
+        let error = serde_json::from_str::<TSPSymInstance<MatrixSym<Distance>>>(&missing_point)
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Display data of length 2 does not match dimension 3")
+        );
+
+        let error = serde_json::from_str::<Tour>(r#"{"nodes":[0,1,1],"cost":3}"#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Node 1 is visited more than once")
+        );
+    }
+}
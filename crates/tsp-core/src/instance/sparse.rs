@@ -0,0 +1,561 @@
+//! Sparse graphs in compressed sparse row (CSR) format.
+//!
+//! In contrast to [`Matrix`] and [`MatrixSym`], which store data for all `n^2` (or `n^2 / 2`)
+//! pairs of nodes, the sparse graphs only store the edges that actually exist. This is useful for
+//! instances that are not complete graphs (e.g. HCP instances or road networks) and for solving on
+//! a restricted set of candidate edges.
+//!
+//! Every edge is identified by an [`EdgeId`], which indexes both the edge weights and any
+//! additional per-edge attributes stored in an [`EdgeMap`] (e.g. whether an edge is fixed or
+//! excluded in a branch and bound node).
+
+use std::ops::{Index, IndexMut};
+
+use crate::instance::{
+    candidates::CandidateSet,
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+};
+
+/// Identifies an edge of a [`SparseGraph`] or [`SparseGraphSym`].
+///
+/// The ids of a graph are `0..edge_count`. For [`SparseGraphSym`], both directions of an edge
+/// share the same id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(pub usize);
+
+/// Per-edge attributes of a sparse graph, indexed by [`EdgeId`].
+///
+/// Create one with [`SparseGraph::edge_map`] or [`SparseGraphSym::edge_map`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeMap<Data> {
+    data: Vec<Data>,
+}
+
+impl<Data> EdgeMap<Data> {
+    /// Returns the attributes of all edges, indexed by the edge ids.
+    pub fn data(&self) -> &[Data] {
+        &self.data
+    }
+
+    /// Returns the attributes of all edges mutably, indexed by the edge ids.
+    pub fn data_mut(&mut self) -> &mut [Data] {
+        &mut self.data
+    }
+}
+
+impl<Data> Index<EdgeId> for EdgeMap<Data> {
+    type Output = Data;
+
+    #[inline(always)]
+    fn index(&self, edge: EdgeId) -> &Data {
+        &self.data[edge.0]
+    }
+}
+
+impl<Data> IndexMut<EdgeId> for EdgeMap<Data> {
+    #[inline(always)]
+    fn index_mut(&mut self, edge: EdgeId) -> &mut Data {
+        &mut self.data[edge.0]
+    }
+}
+
+/// A directed sparse graph with data (e.g. distances) on every edge.
+///
+/// The outgoing edges of node `i` are stored at the indices `offsets[i]..offsets[i + 1]`, sorted
+/// by their target node. The index of an edge is its [`EdgeId`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGraph<Data> {
+    offsets: Vec<usize>,
+    targets: Vec<Node>,
+    data: Vec<Data>,
+}
+
+impl<Data> SparseGraph<Data> {
+    /// Creates a graph from a list of `(from, to, data)` edges.
+    ///
+    /// Self loops are ignored, since tours never use them. If an edge is given multiple times,
+    /// the first occurrence is kept. Panics if a node is not smaller than `dimension`.
+    pub fn from_edges(
+        dimension: usize,
+        edges: impl IntoIterator<Item = (Node, Node, Data)>,
+    ) -> Self {
+        let mut edges = edges
+            .into_iter()
+            .filter(|(from, to, _)| from != to)
+            .inspect(|&(from, to, _)| check_nodes(dimension, from, to))
+            .collect::<Vec<_>>();
+        // Stable, so the first occurrence of a duplicate stays first
+        edges.sort_by_key(|&(from, to, _)| (from, to));
+        edges.dedup_by_key(|&mut (from, to, _)| (from, to));
+
+        let offsets = compute_offsets(dimension, edges.iter().map(|&(from, ..)| from));
+        let (targets, data) = edges.into_iter().map(|(_, to, data)| (to, data)).unzip();
+        Self {
+            offsets,
+            targets,
+            data,
+        }
+    }
+
+    /// Creates a complete graph from a distance matrix, i.e. with an edge between every pair of
+    /// distinct nodes.
+    pub fn from_matrix(matrix: &Matrix<Data>) -> Self
+    where
+        Data: Copy,
+    {
+        Self::from_matrix_filtered(matrix, |_, _, _| true)
+    }
+
+    /// Creates a graph from a distance matrix, keeping only the edges for which `keep` returns
+    /// `true`.
+    pub fn from_matrix_filtered(
+        matrix: &Matrix<Data>,
+        keep: impl Fn(Node, Node, Data) -> bool,
+    ) -> Self
+    where
+        Data: Copy,
+    {
+        let dimension = matrix.dimension();
+        let edges = (0..dimension)
+            .flat_map(|from| (0..dimension).map(move |to| (Node(from), Node(to))))
+            .map(|(from, to)| (from, to, matrix.get_data(from, to)))
+            .filter(|&(from, to, data)| keep(from, to, data));
+        Self::from_edges(dimension, edges)
+    }
+
+    /// Returns the number of nodes.
+    pub fn dimension(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns the number of outgoing edges of the node.
+    pub fn degree(&self, from: Node) -> usize {
+        self.range(from).len()
+    }
+
+    /// Returns the targets of the outgoing edges of the node, sorted increasingly.
+    pub fn neighbors(&self, from: Node) -> &[Node] {
+        &self.targets[self.range(from)]
+    }
+
+    /// Iterates over the outgoing edges of the node as `(target, edge id)` pairs, sorted by
+    /// target.
+    pub fn edges_from(&self, from: Node) -> impl Iterator<Item = (Node, EdgeId)> + '_ {
+        let range = self.range(from);
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(range.map(EdgeId))
+    }
+
+    /// Returns the id of the edge from `from` to `to`, if it exists. Takes `O(log degree)` time.
+    pub fn find_edge(&self, from: Node, to: Node) -> Option<EdgeId> {
+        let range = self.range(from);
+        self.targets[range.clone()]
+            .binary_search(&to)
+            .ok()
+            .map(|index| EdgeId(range.start + index))
+    }
+
+    /// Returns whether the edge from `from` to `to` exists.
+    pub fn contains(&self, from: Node, to: Node) -> bool {
+        self.find_edge(from, to).is_some()
+    }
+
+    /// Returns the source and target of the edge. Takes `O(log dimension)` time.
+    pub fn endpoints(&self, edge: EdgeId) -> (Node, Node) {
+        let from = self.offsets.partition_point(|&offset| offset <= edge.0) - 1;
+        (Node(from), self.targets[edge.0])
+    }
+
+    /// Returns the data of the edge.
+    #[inline(always)]
+    pub fn data(&self, edge: EdgeId) -> &Data {
+        &self.data[edge.0]
+    }
+
+    /// Returns the data of all edges, indexed by the edge ids.
+    pub fn edge_data(&self) -> &[Data] {
+        &self.data
+    }
+
+    /// Creates per-edge attribute storage for this graph, with every edge set to `value`.
+    pub fn edge_map<A: Clone>(&self, value: A) -> EdgeMap<A> {
+        EdgeMap {
+            data: vec![value; self.edge_count()],
+        }
+    }
+
+    fn range(&self, node: Node) -> std::ops::Range<usize> {
+        self.offsets[node.0]..self.offsets[node.0 + 1]
+    }
+}
+
+impl<Data: Copy> SparseGraph<Data> {
+    /// Returns the data of the edge from `from` to `to`, if it exists.
+    pub fn get_data(&self, from: Node, to: Node) -> Option<Data> {
+        self.find_edge(from, to).map(|edge| self.data[edge.0])
+    }
+
+    /// Converts to a dense matrix, using `missing` for all non-existent edges (including the
+    /// diagonal).
+    pub fn to_matrix(&self, missing: Data) -> Matrix<Data> {
+        let mut matrix = Matrix::new_from_dimension_with_value(self.dimension(), missing);
+        for from in (0..self.dimension()).map(Node) {
+            for (to, edge) in self.edges_from(from) {
+                matrix.set_data(from, to, self.data[edge.0]);
+            }
+        }
+        matrix
+    }
+}
+
+/// An undirected sparse graph with data (e.g. distances) on every edge.
+///
+/// Every edge is stored once with its data, and appears in the adjacency lists of both of its
+/// nodes. The adjacency list of node `i` is stored at the indices `offsets[i]..offsets[i + 1]`,
+/// sorted by the neighbor node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGraphSym<Data> {
+    offsets: Vec<usize>,
+    adjacent: Vec<(Node, EdgeId)>,
+    endpoints: Vec<(Node, Node)>,
+    data: Vec<Data>,
+}
+
+impl<Data> SparseGraphSym<Data> {
+    /// Creates a graph from a list of `(from, to, data)` edges. The direction of the edges is
+    /// ignored.
+    ///
+    /// Self loops are ignored, since tours never use them. If an edge is given multiple times (in
+    /// either direction), the first occurrence is kept. Panics if a node is not smaller than
+    /// `dimension`.
+    pub fn from_edges(
+        dimension: usize,
+        edges: impl IntoIterator<Item = (Node, Node, Data)>,
+    ) -> Self {
+        let mut edges = edges
+            .into_iter()
+            .filter(|(from, to, _)| from != to)
+            .inspect(|&(from, to, _)| check_nodes(dimension, from, to))
+            .map(|(from, to, data)| (from.min(to), from.max(to), data))
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|&(smaller, bigger, _)| (smaller, bigger));
+        edges.dedup_by_key(|&mut (smaller, bigger, _)| (smaller, bigger));
+        let (endpoints, data): (Vec<_>, Vec<_>) = edges
+            .into_iter()
+            .map(|(smaller, bigger, data)| ((smaller, bigger), data))
+            .unzip();
+
+        let offsets = compute_offsets(
+            dimension,
+            endpoints
+                .iter()
+                .flat_map(|&(smaller, bigger)| [smaller, bigger]),
+        );
+        let mut next = offsets[..dimension].to_vec();
+        let mut adjacent = vec![(Node(0), EdgeId(0)); 2 * endpoints.len()];
+        // The endpoints are sorted, so all adjacency lists end up sorted by the neighbor: The
+        // first loop adds the smaller neighbors of every node in increasing order, the second loop
+        // the bigger ones
+        for (edge, &(smaller, bigger)) in endpoints.iter().enumerate() {
+            adjacent[next[bigger.0]] = (smaller, EdgeId(edge));
+            next[bigger.0] += 1;
+        }
+        for (edge, &(smaller, bigger)) in endpoints.iter().enumerate() {
+            adjacent[next[smaller.0]] = (bigger, EdgeId(edge));
+            next[smaller.0] += 1;
+        }
+
+        Self {
+            offsets,
+            adjacent,
+            endpoints,
+            data,
+        }
+    }
+
+    /// Creates a complete graph from a symmetric distance matrix, i.e. with an edge between every
+    /// pair of distinct nodes.
+    pub fn from_matrix_sym(matrix: &MatrixSym<Data>) -> Self
+    where
+        Data: Copy,
+    {
+        Self::from_matrix_sym_filtered(matrix, |_, _, _| true)
+    }
+
+    /// Creates a graph from a symmetric distance matrix, keeping only the edges for which `keep`
+    /// returns `true`.
+    pub fn from_matrix_sym_filtered(
+        matrix: &MatrixSym<Data>,
+        keep: impl Fn(Node, Node, Data) -> bool,
+    ) -> Self
+    where
+        Data: Copy,
+    {
+        let dimension = matrix.dimension();
+        let edges = (0..dimension)
+            .flat_map(|from| (0..from).map(move |to| (Node(from), Node(to))))
+            .map(|(from, to)| (from, to, matrix.get_data_from_bigger(from, to)))
+            .filter(|&(from, to, data)| keep(from, to, data));
+        Self::from_edges(dimension, edges)
+    }
+
+    /// Creates a graph from the candidate edges, i.e. with an edge between two nodes if either
+    /// is a candidate of the other.
+    pub fn from_candidates(candidates: &CandidateSet<Data>) -> Self
+    where
+        Data: Copy,
+    {
+        let dimension = candidates.dimension();
+        let edges = (0..dimension).map(Node).flat_map(|from| {
+            candidates
+                .iter(from)
+                .map(move |(to, distance)| (from, to, distance))
+        });
+        Self::from_edges(dimension, edges)
+    }
+
+    /// Returns the number of nodes.
+    pub fn dimension(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the number of (undirected) edges.
+    pub fn edge_count(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    /// Returns the number of edges incident to the node.
+    pub fn degree(&self, node: Node) -> usize {
+        self.range(node).len()
+    }
+
+    /// Iterates over the neighbors of the node, sorted increasingly.
+    pub fn neighbors(&self, node: Node) -> impl Iterator<Item = Node> + '_ {
+        self.adjacent[self.range(node)]
+            .iter()
+            .map(|&(neighbor, _)| neighbor)
+    }
+
+    /// Returns the incident edges of the node as `(neighbor, edge id)` pairs, sorted by
+    /// neighbor.
+    pub fn edges_at(&self, node: Node) -> &[(Node, EdgeId)] {
+        &self.adjacent[self.range(node)]
+    }
+
+    /// Returns the id of the edge between `from` and `to`, if it exists. Takes `O(log degree)`
+    /// time.
+    pub fn find_edge(&self, from: Node, to: Node) -> Option<EdgeId> {
+        let edges = self.edges_at(from);
+        edges
+            .binary_search_by_key(&to, |&(neighbor, _)| neighbor)
+            .ok()
+            .map(|index| edges[index].1)
+    }
+
+    /// Returns whether the edge between `from` and `to` exists.
+    pub fn contains(&self, from: Node, to: Node) -> bool {
+        self.find_edge(from, to).is_some()
+    }
+
+    /// Returns the nodes of the edge, the smaller one first.
+    #[inline(always)]
+    pub fn endpoints(&self, edge: EdgeId) -> (Node, Node) {
+        self.endpoints[edge.0]
+    }
+
+    /// Returns the data of the edge.
+    #[inline(always)]
+    pub fn data(&self, edge: EdgeId) -> &Data {
+        &self.data[edge.0]
+    }
+
+    /// Returns the data of all edges, indexed by the edge ids.
+    pub fn edge_data(&self) -> &[Data] {
+        &self.data
+    }
+
+    /// Creates per-edge attribute storage for this graph, with every edge set to `value`.
+    pub fn edge_map<A: Clone>(&self, value: A) -> EdgeMap<A> {
+        EdgeMap {
+            data: vec![value; self.edge_count()],
+        }
+    }
+
+    fn range(&self, node: Node) -> std::ops::Range<usize> {
+        self.offsets[node.0]..self.offsets[node.0 + 1]
+    }
+}
+
+impl<Data: Copy> SparseGraphSym<Data> {
+    /// Returns the data of the edge between `from` and `to`, if it exists.
+    pub fn get_data(&self, from: Node, to: Node) -> Option<Data> {
+        self.find_edge(from, to).map(|edge| self.data[edge.0])
+    }
+
+    /// Converts to a dense symmetric matrix, using `missing` for all non-existent edges
+    /// (including the diagonal).
+    pub fn to_matrix_sym(&self, missing: Data) -> MatrixSym<Data> {
+        let mut matrix = MatrixSym::new_from_dimension_with_value(self.dimension(), missing);
+        for (&(smaller, bigger), &data) in self.endpoints.iter().zip(&self.data) {
+            matrix.set_data_from_bigger(bigger, smaller, data);
+        }
+        matrix
+    }
+
+    /// Converts to a dense matrix, using `missing` for all non-existent edges (including the
+    /// diagonal).
+    pub fn to_matrix(&self, missing: Data) -> Matrix<Data> {
+        let mut matrix = Matrix::new_from_dimension_with_value(self.dimension(), missing);
+        for (&(smaller, bigger), &data) in self.endpoints.iter().zip(&self.data) {
+            matrix.set_data_symmetric(smaller, bigger, data);
+        }
+        matrix
+    }
+
+    /// Converts to a directed graph with both directions of every edge.
+    pub fn to_directed(&self) -> SparseGraph<Data> {
+        let edges =
+            self.endpoints
+                .iter()
+                .zip(&self.data)
+                .flat_map(|(&(smaller, bigger), &data)| {
+                    [(smaller, bigger, data), (bigger, smaller, data)]
+                });
+        SparseGraph::from_edges(self.dimension(), edges)
+    }
+}
+
+#[inline(always)]
+fn check_nodes(dimension: usize, from: Node, to: Node) {
+    assert!(
+        from.0 < dimension && to.0 < dimension,
+        "Edge ({}, {}) out of range for dimension {}",
+        from.0,
+        to.0,
+        dimension
+    );
+}
+
+/// Computes the CSR offsets from the source node of every adjacency entry.
+fn compute_offsets(dimension: usize, sources: impl Iterator<Item = Node>) -> Vec<usize> {
+    let mut offsets = vec![0; dimension + 1];
+    for source in sources {
+        offsets[source.0 + 1] += 1;
+    }
+    for node in 0..dimension {
+        offsets[node + 1] += offsets[node];
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instance::distance::Distance;
+
+    fn edges(list: &[(usize, usize, i32)]) -> Vec<(Node, Node, Distance)> {
+        list.iter()
+            .map(|&(from, to, distance)| (Node(from), Node(to), Distance(distance)))
+            .collect()
+    }
+
+    #[test]
+    fn test_directed() {
+        let graph = SparseGraph::from_edges(
+            4,
+            edges(&[
+                (0, 2, 5),
+                (0, 1, 3),
+                (2, 0, 7),
+                (1, 1, 9),
+                (0, 2, 8),
+                (3, 0, 1),
+            ]),
+        );
+
+        assert_eq!(graph.dimension(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.neighbors(Node(0)), &[Node(1), Node(2)]);
+        assert_eq!(graph.degree(Node(1)), 0);
+        assert_eq!(graph.get_data(Node(0), Node(2)), Some(Distance(5)));
+        assert_eq!(graph.get_data(Node(2), Node(0)), Some(Distance(7)));
+        assert_eq!(graph.get_data(Node(1), Node(0)), None);
+
+        let edge = graph.find_edge(Node(3), Node(0)).unwrap();
+        assert_eq!(graph.endpoints(edge), (Node(3), Node(0)));
+        assert_eq!(*graph.data(edge), Distance(1));
+
+        let mut fixed = graph.edge_map(false);
+        fixed[edge] = true;
+        assert_eq!(fixed.data().iter().filter(|&&fixed| fixed).count(), 1);
+    }
+
+    #[test]
+    fn test_symmetric() {
+        let graph = SparseGraphSym::from_edges(
+            4,
+            edges(&[(2, 0, 5), (0, 1, 3), (0, 2, 7), (3, 3, 9), (3, 1, 1)]),
+        );
+
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(
+            graph.neighbors(Node(0)).collect::<Vec<_>>(),
+            vec![Node(1), Node(2)]
+        );
+        assert_eq!(
+            graph.neighbors(Node(1)).collect::<Vec<_>>(),
+            vec![Node(0), Node(3)]
+        );
+        assert_eq!(graph.get_data(Node(0), Node(2)), Some(Distance(5)));
+        assert_eq!(graph.get_data(Node(2), Node(0)), Some(Distance(5)));
+        assert_eq!(
+            graph.find_edge(Node(1), Node(3)),
+            graph.find_edge(Node(3), Node(1))
+        );
+        assert_eq!(
+            graph.endpoints(graph.find_edge(Node(3), Node(1)).unwrap()),
+            (Node(1), Node(3))
+        );
+        assert!(!graph.contains(Node(2), Node(3)));
+
+        let directed = graph.to_directed();
+        assert_eq!(directed.edge_count(), 6);
+        assert_eq!(directed.get_data(Node(3), Node(1)), Some(Distance(1)));
+    }
+
+    #[test]
+    fn test_matrix_conversions() {
+        let matrix = MatrixSym::new_from_distance_function(5, |from, to| {
+            Distance((from.0 as i32 - to.0 as i32).abs())
+        });
+        let graph = SparseGraphSym::from_matrix_sym_filtered(&matrix, |_, _, distance| {
+            distance <= Distance(2)
+        });
+        assert_eq!(graph.edge_count(), 4 + 3);
+        assert_eq!(graph.degree(Node(2)), 4);
+
+        let dense = graph.to_matrix_sym(Distance(100));
+        assert_eq!(dense.get_data(Node(1), Node(3)), Distance(2));
+        assert_eq!(dense.get_data(Node(4), Node(0)), Distance(100));
+        assert_eq!(dense.get_data(Node(2), Node(2)), Distance(100));
+
+        let complete = SparseGraph::from_matrix(&matrix.to_edge_data_matrix());
+        assert_eq!(complete.edge_count(), 5 * 4);
+        assert_eq!(
+            complete.to_matrix(Distance(0)).data(),
+            matrix.to_edge_data_matrix().data()
+        );
+        assert_eq!(
+            graph.to_matrix(Distance(100)).data(),
+            graph.to_directed().to_matrix(Distance(100)).data()
+        );
+    }
+}
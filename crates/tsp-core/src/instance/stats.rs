@@ -0,0 +1,671 @@
+//! Statistics and metric property checks of instances, to decide which solver to use.
+//!
+//! All statistics are computed from a [`DistanceOracle`], so they work for every distance
+//! container. The quadratic and cubic parts (the distance distribution, nearest neighbors,
+//! symmetry and the triangle inequality) are computed exactly for small instances and on a random
+//! sample for big ones, see [`StatsConfig::max_samples`]. Whether a result is exact is reported in
+//! its `exhaustive` field.
+
+use std::cmp::Ordering;
+
+use crate::instance::{
+    TSPSymInstance, distance::DistanceValue, node::Node, oracle::DistanceOracle, point::Point2D,
+};
+
+/// The quantiles reported for the distance distribution.
+const QUANTILES: [f64; 7] = [0.01, 0.05, 0.25, 0.5, 0.75, 0.95, 0.99];
+
+/// Configuration of [`InstanceStats::compute`].
+#[derive(Debug, Clone)]
+pub struct StatsConfig {
+    /// The maximum number of distance queries (or triples for the triangle inequality) per
+    /// statistic. Statistics that need more queries are computed on a random sample.
+    pub max_samples: usize,
+    /// The seed of the random sampling, the statistics are deterministic for a fixed seed.
+    pub seed: u64,
+    /// The number of bins of the distance histogram.
+    pub histogram_bins: usize,
+    /// The maximum number of counterexamples reported per property check.
+    pub max_counterexamples: usize,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            max_samples: 1 << 20,
+            seed: 0x5EED,
+            histogram_bins: 20,
+            max_counterexamples: 10,
+        }
+    }
+}
+
+/// Statistics of an instance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceStats {
+    /// The number of nodes.
+    pub dimension: usize,
+    /// Statistics of the display coordinates, if the instance has any.
+    pub coordinates: Option<CoordinateStats>,
+    /// Groups of nodes with identical display coordinates, sorted by their first node.
+    pub duplicate_points: Vec<Vec<Node>>,
+    /// The distribution of the distances between different nodes.
+    pub distances: DistanceDistribution,
+    /// The distribution of the distances from the nodes to their nearest neighbors.
+    pub nearest_neighbors: NearestNeighborStats,
+    /// Whether `d(a, b) = d(b, a)` for all nodes.
+    pub symmetry: PropertyCheck<Asymmetry>,
+    /// Whether `d(a, c) <= d(a, b) + d(b, c)` for all nodes.
+    pub triangle_inequality: PropertyCheck<TriangleViolation>,
+}
+
+/// Statistics of the display coordinates of an instance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoordinateStats {
+    /// The lower left corner of the bounding box.
+    pub min: Point2D,
+    /// The upper right corner of the bounding box.
+    pub max: Point2D,
+    /// The mean of the coordinates.
+    pub centroid: Point2D,
+    /// The standard deviation of the coordinates in each direction.
+    pub spread: Point2D,
+}
+
+/// The distribution of the distances between different nodes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistanceDistribution {
+    /// Whether all pairs of nodes were considered, instead of a random sample.
+    pub exhaustive: bool,
+    /// The number of distances the statistics are based on.
+    pub count: usize,
+    /// The smallest distance.
+    pub min: f64,
+    /// The biggest distance.
+    pub max: f64,
+    /// The mean distance.
+    pub mean: f64,
+    /// The standard deviation of the distances.
+    pub std_dev: f64,
+    /// The quantiles of the distances.
+    pub quantiles: Vec<Quantile>,
+    /// A histogram of the distances.
+    pub histogram: Histogram,
+}
+
+/// The value below which the given fraction of the values lies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quantile {
+    /// The fraction, in `[0, 1]`.
+    pub fraction: f64,
+    /// The value of the quantile.
+    pub value: f64,
+}
+
+/// A histogram with bins of equal width.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram {
+    /// The lower bound of the first bin.
+    pub start: f64,
+    /// The width of every bin.
+    pub bin_width: f64,
+    /// The number of values per bin. The last bin includes its upper bound.
+    pub counts: Vec<usize>,
+}
+
+/// The distribution of the distances from the nodes to their nearest neighbors.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NearestNeighborStats {
+    /// Whether all nodes were considered, instead of a random sample.
+    pub exhaustive: bool,
+    /// The number of nodes the statistics are based on.
+    pub count: usize,
+    /// The smallest nearest neighbor distance.
+    pub min: f64,
+    /// The biggest nearest neighbor distance.
+    pub max: f64,
+    /// The mean nearest neighbor distance.
+    pub mean: f64,
+    /// The median nearest neighbor distance.
+    pub median: f64,
+}
+
+/// The result of checking a property of the distances.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PropertyCheck<Counterexample> {
+    /// Whether all cases were checked, instead of a random sample.
+    pub exhaustive: bool,
+    /// The number of checked cases.
+    pub checked: usize,
+    /// The number of checked cases that violate the property.
+    pub violations: usize,
+    /// Some of the violating cases, see [`StatsConfig::max_counterexamples`].
+    pub counterexamples: Vec<Counterexample>,
+}
+
+impl<Counterexample> PropertyCheck<Counterexample> {
+    /// Returns whether no violation was found. If the check is not exhaustive, the property may
+    /// still be violated by cases that were not sampled.
+    pub fn holds(&self) -> bool {
+        self.violations == 0
+    }
+
+    fn new(exhaustive: bool) -> Self {
+        Self {
+            exhaustive,
+            checked: 0,
+            violations: 0,
+            counterexamples: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, counterexample: Option<Counterexample>, max_counterexamples: usize) {
+        self.checked += 1;
+        if let Some(counterexample) = counterexample {
+            self.violations += 1;
+            if self.counterexamples.len() < max_counterexamples {
+                self.counterexamples.push(counterexample);
+            }
+        }
+    }
+}
+
+/// A pair of nodes with different distances in both directions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Asymmetry {
+    /// The first node.
+    pub from: Node,
+    /// The second node.
+    pub to: Node,
+    /// The distance from `from` to `to`.
+    pub forward: f64,
+    /// The distance from `to` to `from`.
+    pub backward: f64,
+}
+
+/// Three nodes with `d(a, c) > d(a, b) + d(b, c)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriangleViolation {
+    /// The start node.
+    pub a: Node,
+    /// The intermediate node.
+    pub b: Node,
+    /// The end node.
+    pub c: Node,
+    /// The distance `d(a, c)`.
+    pub direct: f64,
+    /// The distance `d(a, b) + d(b, c)`.
+    pub detour: f64,
+}
+
+impl InstanceStats {
+    /// Computes the statistics of the instance.
+    pub fn compute<C, D>(instance: &TSPSymInstance<C>, config: &StatsConfig) -> Self
+    where
+        C: DistanceOracle<Value = D>,
+        D: DistanceValue,
+    {
+        let distances = instance.distances();
+        let mut random = SplitMix64(config.seed);
+        Self {
+            dimension: distances.dimension(),
+            coordinates: instance.display_data().and_then(coordinate_stats),
+            duplicate_points: instance
+                .display_data()
+                .map(duplicate_points)
+                .unwrap_or_default(),
+            distances: distance_distribution(distances, config, &mut random),
+            nearest_neighbors: nearest_neighbor_stats(distances, config, &mut random),
+            symmetry: check_symmetry(distances, config, &mut random),
+            triangle_inequality: check_triangle_inequality(distances, config, &mut random),
+        }
+    }
+}
+
+fn coordinate_stats(points: &[Point2D]) -> Option<CoordinateStats> {
+    let first = *points.first()?;
+    let count = points.len() as f64;
+    let (mut min, mut max) = (first, first);
+    let mut centroid = Point2D { x: 0.0, y: 0.0 };
+    for point in points {
+        min.x = min.x.min(point.x);
+        min.y = min.y.min(point.y);
+        max.x = max.x.max(point.x);
+        max.y = max.y.max(point.y);
+        centroid.x += point.x / count;
+        centroid.y += point.y / count;
+    }
+    let variance = points
+        .iter()
+        .fold(Point2D { x: 0.0, y: 0.0 }, |sum, point| Point2D {
+            x: sum.x + (point.x - centroid.x).powi(2) / count,
+            y: sum.y + (point.y - centroid.y).powi(2) / count,
+        });
+    Some(CoordinateStats {
+        min,
+        max,
+        centroid,
+        spread: Point2D {
+            x: variance.x.sqrt(),
+            y: variance.y.sqrt(),
+        },
+    })
+}
+
+fn duplicate_points(points: &[Point2D]) -> Vec<Vec<Node>> {
+    let compare = |a: &Point2D, b: &Point2D| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y));
+    let mut order = (0..points.len()).map(Node).collect::<Vec<_>>();
+    // The sort is stable, so the nodes of each group stay sorted
+    order.sort_by(|a, b| compare(&points[a.0], &points[b.0]));
+
+    let mut groups = order
+        .chunk_by(|a, b| compare(&points[a.0], &points[b.0]) == Ordering::Equal)
+        .filter(|group| group.len() > 1)
+        .map(<[Node]>::to_vec)
+        .collect::<Vec<_>>();
+    groups.sort_unstable_by_key(|group| group[0]);
+    groups
+}
+
+fn distance_distribution<D: DistanceValue>(
+    distances: &impl DistanceOracle<Value = D>,
+    config: &StatsConfig,
+    random: &mut SplitMix64,
+) -> DistanceDistribution {
+    let dimension = distances.dimension();
+    let pairs = dimension.saturating_mul(dimension.saturating_sub(1));
+    let exhaustive = pairs <= config.max_samples;
+
+    let mut values = if exhaustive {
+        (0..dimension)
+            .flat_map(|from| (0..dimension).map(move |to| (Node(from), Node(to))))
+            .filter(|(from, to)| from != to)
+            .map(|(from, to)| distances.get(from, to).to_f64())
+            .collect::<Vec<_>>()
+    } else {
+        (0..config.max_samples)
+            .map(|_| {
+                let [from, to] = random.distinct_nodes(dimension);
+                distances.get(from, to).to_f64()
+            })
+            .collect()
+    };
+    values.sort_unstable_by(f64::total_cmp);
+
+    let (min, max) = match (values.first(), values.last()) {
+        (Some(&min), Some(&max)) => (min, max),
+        _ => (0.0, 0.0),
+    };
+    let (mean, std_dev) = mean_and_std_dev(&values);
+    let bins = config.histogram_bins.max(1);
+    let bin_width = (max - min) / bins as f64;
+    let mut counts = vec![0; bins];
+    for &value in &values {
+        let bin = if bin_width > 0.0 {
+            ((value - min) / bin_width) as usize
+        } else {
+            0
+        };
+        counts[bin.min(bins - 1)] += 1;
+    }
+
+    DistanceDistribution {
+        exhaustive,
+        count: values.len(),
+        min,
+        max,
+        mean,
+        std_dev,
+        quantiles: QUANTILES
+            .iter()
+            .map(|&fraction| Quantile {
+                fraction,
+                value: quantile(&values, fraction),
+            })
+            .collect(),
+        histogram: Histogram {
+            start: min,
+            bin_width,
+            counts,
+        },
+    }
+}
+
+fn nearest_neighbor_stats<D: DistanceValue>(
+    distances: &impl DistanceOracle<Value = D>,
+    config: &StatsConfig,
+    random: &mut SplitMix64,
+) -> NearestNeighborStats {
+    let dimension = distances.dimension();
+    let exhaustive = dimension.saturating_mul(dimension) <= config.max_samples;
+    let nodes = if exhaustive {
+        (0..dimension).map(Node).collect::<Vec<_>>()
+    } else {
+        (0..(config.max_samples / dimension).max(1))
+            .map(|_| Node(random.below(dimension)))
+            .collect()
+    };
+
+    let mut values = nodes
+        .into_iter()
+        .filter_map(|from| {
+            (0..dimension)
+                .map(Node)
+                .filter(|&to| to != from)
+                .map(|to| distances.get(from, to).to_f64())
+                .min_by(f64::total_cmp)
+        })
+        .collect::<Vec<_>>();
+    values.sort_unstable_by(f64::total_cmp);
+
+    NearestNeighborStats {
+        exhaustive,
+        count: values.len(),
+        min: values.first().copied().unwrap_or_default(),
+        max: values.last().copied().unwrap_or_default(),
+        mean: mean_and_std_dev(&values).0,
+        median: quantile(&values, 0.5),
+    }
+}
+
+fn check_symmetry<D: DistanceValue>(
+    distances: &impl DistanceOracle<Value = D>,
+    config: &StatsConfig,
+    random: &mut SplitMix64,
+) -> PropertyCheck<Asymmetry> {
+    let dimension = distances.dimension();
+    let exhaustive =
+        dimension.saturating_mul(dimension.saturating_sub(1)) / 2 <= config.max_samples;
+    let mut check = PropertyCheck::new(exhaustive);
+    let mut check_pair = |from: Node, to: Node| {
+        let forward = distances.get(from, to);
+        let backward = distances.get(to, from);
+        let asymmetry = (forward != backward).then(|| Asymmetry {
+            from,
+            to,
+            forward: forward.to_f64(),
+            backward: backward.to_f64(),
+        });
+        check.record(asymmetry, config.max_counterexamples);
+    };
+
+    if exhaustive {
+        for from in (0..dimension).map(Node) {
+            for to in (0..from.0).map(Node) {
+                check_pair(from, to);
+            }
+        }
+    } else {
+        for _ in 0..config.max_samples {
+            let [from, to] = random.distinct_nodes(dimension);
+            check_pair(from, to);
+        }
+    }
+    check
+}
+
+fn check_triangle_inequality<D: DistanceValue>(
+    distances: &impl DistanceOracle<Value = D>,
+    config: &StatsConfig,
+    random: &mut SplitMix64,
+) -> PropertyCheck<TriangleViolation> {
+    let dimension = distances.dimension();
+    // Saturates for big instances (the product overflows for dimensions above ~2.6 million on 64
+    // bit targets), which are sampled anyway
+    let triples = dimension
+        .saturating_mul(dimension.saturating_sub(1))
+        .saturating_mul(dimension.saturating_sub(2));
+    let exhaustive = triples <= config.max_samples;
+    let mut check = PropertyCheck::new(exhaustive);
+    // Compared as floats, so big distances (e.g. forbidden edges) cannot overflow
+    let mut check_triple = |a: Node, b: Node, c: Node| {
+        let direct = distances.get(a, c).to_f64();
+        let detour = distances.get(a, b).to_f64() + distances.get(b, c).to_f64();
+        let violation = (direct > detour).then_some(TriangleViolation {
+            a,
+            b,
+            c,
+            direct,
+            detour,
+        });
+        check.record(violation, config.max_counterexamples);
+    };
+
+    if exhaustive {
+        for a in (0..dimension).map(Node) {
+            for c in (0..dimension).map(Node).filter(|&c| c != a) {
+                for b in (0..dimension).map(Node).filter(|&b| b != a && b != c) {
+                    check_triple(a, b, c);
+                }
+            }
+        }
+    } else {
+        for _ in 0..config.max_samples {
+            let [a, b, c] = random.distinct_nodes(dimension);
+            check_triple(a, b, c);
+        }
+    }
+    check
+}
+
+fn mean_and_std_dev(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    (mean, variance.sqrt())
+}
+
+/// Returns the quantile of sorted values, using the nearest rank.
+fn quantile(sorted_values: &[f64], fraction: f64) -> f64 {
+    if sorted_values.is_empty() {
+        return 0.0;
+    }
+    let rank = (fraction * sorted_values.len() as f64).ceil() as usize;
+    sorted_values[rank.clamp(1, sorted_values.len()) - 1]
+}
+
+/// A small deterministic pseudo random number generator for sampling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`, with a negligible bias for the bounds used here.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// Returns `N` pairwise different nodes. Requires `dimension >= N`.
+    fn distinct_nodes<const N: usize>(&mut self, dimension: usize) -> [Node; N] {
+        let mut nodes = [Node(0); N];
+        for index in 0..N {
+            nodes[index] = loop {
+                let node = Node(self.below(dimension));
+                if !nodes[..index].contains(&node) {
+                    break node;
+                }
+            };
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instance::{
+            InstanceMetadata,
+            distance::Distance,
+            matrix::{Matrix, MatrixSym},
+        },
+        tsp_lib_spec::{EdgeWeightType, NodeCoordType, ProblemType},
+    };
+
+    fn metadata(dimension: usize) -> InstanceMetadata {
+        InstanceMetadata {
+            name: "stats".to_string(),
+            problem_type: ProblemType::TSP,
+            comment: None,
+            dimension,
+            capacity: None,
+            edge_weight_type: EdgeWeightType::EXPLICIT,
+            edge_data_format: None,
+            edge_weight_format: None,
+            node_coord_type: NodeCoordType::NO_COORDS,
+            display_data_type: None,
+            grid_size: None,
+        }
+    }
+
+    /// A 10 x 10 grid with unit spacing and rounded Euclidean distances, with node 0 duplicated
+    /// at node 100.
+    fn grid() -> TSPSymInstance<MatrixSym<Distance>> {
+        let mut points = (0..100)
+            .map(|index| Point2D {
+                x: (index % 10) as f64,
+                y: (index / 10) as f64,
+            })
+            .collect::<Vec<_>>();
+        points.push(points[0]);
+        let distances = MatrixSym::new_from_distance_function(points.len(), |from, to| {
+            let (a, b) = (points[from.0], points[to.0]);
+            Distance(((a.x - b.x).hypot(a.y - b.y) + 0.5) as i32)
+        });
+        TSPSymInstance::new(distances, metadata(points.len())).with_display_data(points)
+    }
+
+    #[test]
+    fn test_grid_statistics() {
+        let stats = InstanceStats::compute(&grid(), &StatsConfig::default());
+        assert_eq!(stats.dimension, 101);
+        let coordinates = stats.coordinates.as_ref().unwrap();
+        assert_eq!(coordinates.min, Point2D { x: 0.0, y: 0.0 });
+        assert_eq!(coordinates.max, Point2D { x: 9.0, y: 9.0 });
+        assert_eq!(stats.duplicate_points, vec![vec![Node(0), Node(100)]]);
+
+        assert!(stats.distances.exhaustive);
+        assert_eq!(stats.distances.count, 101 * 100);
+        assert_eq!(stats.distances.min, 0.0);
+        assert_eq!(stats.distances.max, 13.0);
+        assert_eq!(
+            stats.distances.histogram.counts.iter().sum::<usize>(),
+            101 * 100
+        );
+        assert!(
+            stats
+                .distances
+                .quantiles
+                .windows(2)
+                .all(|pair| pair[0].value <= pair[1].value)
+        );
+
+        assert_eq!(stats.nearest_neighbors.count, 101);
+        assert_eq!(stats.nearest_neighbors.min, 0.0);
+        assert_eq!(stats.nearest_neighbors.max, 1.0);
+
+        assert!(stats.symmetry.holds());
+        assert!(stats.symmetry.exhaustive);
+        // Rounding breaks the triangle inequality, e.g. from (0, 0) to (2, 3) the direct distance
+        // is 4, but the detour via (1, 1) is 1 + 2
+        assert!(!stats.triangle_inequality.holds());
+        assert!(stats.triangle_inequality.exhaustive);
+        for violation in &stats.triangle_inequality.counterexamples {
+            assert!(violation.direct > violation.detour);
+        }
+    }
+
+    #[test]
+    fn test_asymmetric_matrix() {
+        let mut distances = Matrix::new_from_distance_function(4, |from, to| {
+            Distance((from.0 as i32 - to.0 as i32).abs())
+        });
+        distances.set_data(Node(0), Node(3), Distance(10));
+        let instance = TSPSymInstance::new(distances, metadata(4));
+        let stats = InstanceStats::compute(&instance, &StatsConfig::default());
+
+        assert!(stats.coordinates.is_none());
+        assert_eq!(stats.symmetry.checked, 6);
+        assert_eq!(
+            stats.symmetry.counterexamples,
+            vec![Asymmetry {
+                from: Node(3),
+                to: Node(0),
+                forward: 3.0,
+                backward: 10.0,
+            }]
+        );
+        assert!(stats.triangle_inequality.exhaustive);
+        assert_eq!(stats.triangle_inequality.checked, 24);
+        // 0 -> 3 directly costs 10, but 3 via 1 or 2
+        assert_eq!(stats.triangle_inequality.violations, 2);
+
+        let sampled = InstanceStats::compute(
+            &instance,
+            &StatsConfig {
+                max_samples: 5,
+                ..StatsConfig::default()
+            },
+        );
+        assert!(!sampled.distances.exhaustive);
+        assert_eq!(sampled.distances.count, 5);
+        assert_eq!(sampled.triangle_inequality.checked, 5);
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&stats).unwrap();
+            assert_eq!(serde_json::from_str::<InstanceStats>(&json).unwrap(), stats);
+        }
+    }
+
+    /// Answers every query with the same distance, for dimensions too big to store.
+    struct ConstantOracle(usize);
+
+    impl DistanceOracle for ConstantOracle {
+        type Value = Distance;
+
+        fn dimension(&self) -> usize {
+            self.0
+        }
+
+        fn get(&self, _: Node, _: Node) -> Distance {
+            Distance(1)
+        }
+    }
+
+    #[test]
+    fn test_huge_dimension_is_sampled() {
+        // n * (n - 1) * (n - 2) overflows a u64 for this dimension
+        let oracle = ConstantOracle(3_000_000);
+        let config = StatsConfig {
+            max_samples: 10,
+            ..StatsConfig::default()
+        };
+        let check = check_triangle_inequality(&oracle, &config, &mut SplitMix64(config.seed));
+        assert!(!check.exhaustive);
+        assert_eq!(check.checked, 10);
+        assert!(check.holds());
+    }
+}
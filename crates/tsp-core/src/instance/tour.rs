@@ -0,0 +1,340 @@
+//! Ordered tours, i.e. tours given as the sequence in which the nodes are visited.
+
+use thiserror::Error;
+
+use crate::instance::{
+    UnTour,
+    distance::{Distance, DistanceValue},
+    edge::UnEdge,
+    node::Node,
+};
+
+/// Reasons why a sequence of nodes or a set of edges is not a Hamiltonian cycle.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TourError {
+    /// The tour does not contain any node.
+    #[error("A tour has to visit at least one node")]
+    Empty,
+    /// A node index is not smaller than the number of nodes of the tour.
+    #[error("Node {} is out of range for a tour of dimension {dimension}", node.0)]
+    NodeOutOfRange {
+        /// The offending node.
+        node: Node,
+        /// The number of nodes of the tour.
+        dimension: usize,
+    },
+    /// A node is visited more than once.
+    #[error("Node {} is visited more than once", .0.0)]
+    DuplicateNode(Node),
+    /// A node is not incident to exactly two edges.
+    #[error("Node {} has degree {degree} instead of 2", node.0)]
+    InvalidDegree {
+        /// The offending node.
+        node: Node,
+        /// The number of edges incident to the node.
+        degree: usize,
+    },
+    /// The edges form more than one cycle.
+    #[error(
+        "The edges form subtours, the subtour through node 0 only visits {visited} of {dimension} \
+         nodes"
+    )]
+    Subtour {
+        /// The number of nodes on the cycle through node 0.
+        visited: usize,
+        /// The number of nodes of the tour.
+        dimension: usize,
+    },
+    /// The tour does not visit the expected number of nodes.
+    #[error("The tour has dimension {found}, but the instance has dimension {expected}")]
+    DimensionMismatch {
+        /// The dimension of the instance.
+        expected: usize,
+        /// The number of nodes of the tour.
+        found: usize,
+    },
+}
+
+/// A Hamiltonian cycle given by the order in which the nodes are visited.
+///
+/// The tour is always stored in its canonical form: It starts at node 0 and continues with the
+/// smaller of the two neighbors of node 0. Two tours visiting the same cycle are therefore equal
+/// (and hash equally) regardless of the rotation and direction they were constructed with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "crate::instance::serialization::TourData<D>",
+        bound(deserialize = "D: DistanceValue + serde::Deserialize<'de>")
+    )
+)]
+pub struct Tour<D = Distance> {
+    nodes: Vec<Node>,
+    cost: D,
+}
+
+impl<D: DistanceValue> Tour<D> {
+    /// Creates a tour from the sequence of visited nodes and its cost.
+    ///
+    /// Returns an error if the nodes are not a permutation of `0..nodes.len()`. The cost is not
+    /// checked, see [`Tour::from_nodes`] to compute it instead.
+    pub fn new(nodes: Vec<Node>, cost: D) -> Result<Self, TourError> {
+        validate_permutation(&nodes)?;
+        let mut tour = Self { nodes, cost };
+        tour.canonicalize();
+        Ok(tour)
+    }
+
+    /// Creates a tour from the sequence of visited nodes and computes its cost with the given
+    /// distance function.
+    ///
+    /// Returns an error if the nodes are not a permutation of `0..nodes.len()`.
+    pub fn from_nodes(
+        nodes: Vec<Node>,
+        distance_function: impl Fn(Node, Node) -> D,
+    ) -> Result<Self, TourError> {
+        let mut tour = Self::new(nodes, D::ZERO)?;
+        tour.recompute_cost(distance_function);
+        Ok(tour)
+    }
+
+    /// Returns the visited nodes in canonical order.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Returns the cost of the tour.
+    pub fn cost(&self) -> D {
+        self.cost
+    }
+
+    /// Returns the number of nodes visited by the tour.
+    pub fn dimension(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns an error if the tour does not visit exactly the given number of nodes.
+    pub fn check_dimension(&self, dimension: usize) -> Result<(), TourError> {
+        if self.dimension() == dimension {
+            Ok(())
+        } else {
+            Err(TourError::DimensionMismatch {
+                expected: dimension,
+                found: self.dimension(),
+            })
+        }
+    }
+
+    /// Iterates over the edges of the tour in visiting order, including the edge closing the
+    /// cycle.
+    pub fn edges(&self) -> impl Iterator<Item = UnEdge> + '_ {
+        let next_nodes = self.nodes.iter().skip(1).chain(self.nodes.first());
+        self.nodes
+            .iter()
+            .zip(next_nodes)
+            .map(|(&from, &to)| UnEdge::new(from, to))
+    }
+
+    /// Recomputes the cost of the tour with the given distance function, stores and returns it.
+    ///
+    /// Any distance container can be used, e.g. `|from, to| matrix.get_data(from, to)`.
+    pub fn recompute_cost(&mut self, distance_function: impl Fn(Node, Node) -> D) -> D {
+        self.cost = self
+            .edges()
+            .map(|edge| distance_function(edge.from, edge.to))
+            .sum();
+        self.cost
+    }
+
+    /// Rotates the tour to start at node 0 and reverses it if necessary, such that the second
+    /// node is the smaller neighbor of node 0.
+    fn canonicalize(&mut self) {
+        let start = self
+            .nodes
+            .iter()
+            .position(|&node| node == Node(0))
+            .expect("A validated tour contains node 0");
+        self.nodes.rotate_left(start);
+
+        if self.nodes.len() > 2 && self.nodes[self.nodes.len() - 1] < self.nodes[1] {
+            self.nodes[1..].reverse();
+        }
+    }
+}
+
+impl<D: DistanceValue> TryFrom<UnTour<D>> for Tour<D> {
+    type Error = TourError;
+
+    /// Reconstructs the visiting order from the edges of the tour.
+    ///
+    /// Returns an error if the edges do not form a Hamiltonian cycle on the nodes
+    /// `0..edges.len()`.
+    fn try_from(un_tour: UnTour<D>) -> Result<Self, Self::Error> {
+        let dimension = un_tour.edges.len();
+        if dimension == 0 {
+            return Err(TourError::Empty);
+        }
+
+        let mut neighbors = vec![Vec::with_capacity(2); dimension];
+        for edge in &un_tour.edges {
+            for node in [edge.from, edge.to] {
+                if node.0 >= dimension {
+                    return Err(TourError::NodeOutOfRange { node, dimension });
+                }
+            }
+            neighbors[edge.from.0].push(edge.to);
+            neighbors[edge.to.0].push(edge.from);
+        }
+        if let Some((node, node_neighbors)) = neighbors
+            .iter()
+            .enumerate()
+            .find(|(_, node_neighbors)| node_neighbors.len() != 2)
+        {
+            return Err(TourError::InvalidDegree {
+                node: Node(node),
+                degree: node_neighbors.len(),
+            });
+        }
+
+        // Walk along the cycle through node 0, every node has exactly two neighbors here
+        let mut nodes = Vec::with_capacity(dimension);
+        let mut previous = Node(0);
+        let mut current = Node(0);
+        loop {
+            nodes.push(current);
+            let next = if neighbors[current.0][0] != previous || nodes.len() == 1 {
+                neighbors[current.0][0]
+            } else {
+                neighbors[current.0][1]
+            };
+            previous = current;
+            current = next;
+            if current == Node(0) || nodes.len() > dimension {
+                break;
+            }
+        }
+
+        if nodes.len() != dimension {
+            return Err(TourError::Subtour {
+                visited: nodes.len(),
+                dimension,
+            });
+        }
+        Tour::new(nodes, un_tour.cost)
+    }
+}
+
+impl<D: DistanceValue> From<Tour<D>> for UnTour<D> {
+    fn from(tour: Tour<D>) -> Self {
+        UnTour {
+            edges: tour.edges().collect(),
+            cost: tour.cost,
+        }
+    }
+}
+
+/// Checks that the nodes are a permutation of `0..nodes.len()`.
+pub(crate) fn validate_permutation(nodes: &[Node]) -> Result<(), TourError> {
+    let dimension = nodes.len();
+    if dimension == 0 {
+        return Err(TourError::Empty);
+    }
+
+    let mut visited = vec![false; dimension];
+    for &node in nodes {
+        if node.0 >= dimension {
+            return Err(TourError::NodeOutOfRange { node, dimension });
+        }
+        if visited[node.0] {
+            return Err(TourError::DuplicateNode(node));
+        }
+        visited[node.0] = true;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(indices: &[usize]) -> Vec<Node> {
+        indices.iter().copied().map(Node).collect()
+    }
+
+    #[test]
+    fn test_canonical_rotation_and_direction() {
+        let tour_a = Tour::new(nodes(&[2, 3, 0, 4, 1]), Distance(7)).unwrap();
+        let tour_b = Tour::new(nodes(&[1, 4, 0, 3, 2]), Distance(7)).unwrap();
+
+        assert_eq!(tour_a.nodes(), nodes(&[0, 3, 2, 1, 4]).as_slice());
+        assert_eq!(tour_a, tour_b);
+    }
+
+    #[test]
+    fn test_invalid_node_sequences() {
+        assert_eq!(Tour::new(vec![], Distance(0)), Err(TourError::Empty));
+        assert_eq!(
+            Tour::new(nodes(&[0, 1, 1]), Distance(0)),
+            Err(TourError::DuplicateNode(Node(1)))
+        );
+        assert_eq!(
+            Tour::new(nodes(&[0, 3, 1]), Distance(0)),
+            Err(TourError::NodeOutOfRange {
+                node: Node(3),
+                dimension: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_un_tour_round_trip() {
+        let tour = Tour::from_nodes(nodes(&[0, 2, 1, 3]), |from, to| {
+            Distance((from.0 as i32 - to.0 as i32).abs())
+        })
+        .unwrap();
+        assert_eq!(tour.cost(), Distance(2 + 1 + 2 + 3));
+
+        let un_tour = UnTour::from(tour.clone());
+        assert_eq!(un_tour.edges.len(), 4);
+        assert_eq!(Tour::try_from(un_tour), Ok(tour));
+    }
+
+    #[test]
+    fn test_un_tour_with_subtours() {
+        let un_tour = UnTour {
+            edges: [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]
+                .into_iter()
+                .map(UnEdge::from)
+                .collect(),
+            cost: Distance(0),
+        };
+
+        assert_eq!(
+            Tour::try_from(un_tour),
+            Err(TourError::Subtour {
+                visited: 3,
+                dimension: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_un_tour_with_invalid_degree() {
+        let un_tour = UnTour {
+            edges: [(0, 1), (0, 2), (0, 3), (1, 2)]
+                .into_iter()
+                .map(UnEdge::from)
+                .collect(),
+            cost: Distance(0),
+        };
+
+        assert_eq!(
+            Tour::try_from(un_tour),
+            Err(TourError::InvalidDegree {
+                node: Node(0),
+                degree: 3
+            })
+        );
+    }
+}
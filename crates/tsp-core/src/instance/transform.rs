@@ -0,0 +1,534 @@
+//! Transformations deriving one instance from another.
+//!
+//! Every transformation keeps the information needed to translate solutions of the derived
+//! instance back to the node ids of the original instance (and vice versa):
+//! - [`AtspToStsp`] turns an asymmetric instance into a symmetric one with twice as many nodes.
+//! - [`NodeSubset`] extracts an arbitrary subset of the nodes.
+//! - [`Relabeling`] permutes the node ids, e.g. into Hilbert curve order for cache locality.
+
+use thiserror::Error;
+
+use crate::instance::{
+    distance::{Distance, DistanceValue},
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+    point::Point2D,
+    tour::{Tour, TourError, validate_permutation},
+};
+
+/// Errors when creating a transformation or translating a solution.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// The tour is invalid for the transformed instance.
+    #[error(transparent)]
+    Tour(#[from] TourError),
+    /// A node is not smaller than the dimension of the original instance.
+    #[error("Node {} is out of range for dimension {dimension}", node.0)]
+    NodeOutOfRange {
+        /// The offending node.
+        node: Node,
+        /// The dimension of the original instance.
+        dimension: usize,
+    },
+    /// A node is selected more than once for a subset.
+    #[error("Node {} is selected more than once", .0.0)]
+    DuplicateNode(Node),
+    /// A tour of the symmetric instance does not use the edge between a node and its copy, so it
+    /// does not correspond to a tour of the asymmetric instance.
+    #[error("The tour does not use the edge between node {} and its copy", .0.0)]
+    MissingTieEdge(Node),
+}
+
+/// The Jonker-Volgenant transformation of an asymmetric instance with `n` nodes into a symmetric
+/// instance with `2n` nodes.
+///
+/// Every node `i` gets a copy `n + i`, representing leaving `i`. The edge between a node and its
+/// copy costs zero, the edge from the copy `n + i` to node `j` costs the original distance from
+/// `i` to `j` plus a big constant `M`, and all other edges cost `2M`. `M` is bigger than the cost
+/// of any tour of the original instance, so every optimal symmetric tour alternates between nodes
+/// and their copies and costs `n * M` more than the corresponding asymmetric tour.
+///
+/// The distances of the original instance must be non-negative. Since the symmetric distances are
+/// much bigger than the original ones, a wide distance type (e.g.
+/// [`Distance64`](crate::instance::distance::Distance64)) may be needed to avoid overflows.
+#[derive(Debug, Clone)]
+pub struct AtspToStsp<D = Distance> {
+    original_dimension: usize,
+    offset: D,
+    matrix: MatrixSym<D>,
+}
+
+impl<D: DistanceValue> AtspToStsp<D> {
+    /// Transforms the asymmetric distance matrix.
+    pub fn new(matrix: &Matrix<D>) -> Self {
+        let dimension = matrix.dimension();
+        // Bigger than the cost of any tour
+        let big = (0..dimension)
+            .map(|from| {
+                (0..dimension)
+                    .filter(|&to| to != from)
+                    .map(|to| matrix.get_data(Node(from), Node(to)))
+                    .max()
+                    .unwrap_or(D::ZERO)
+            })
+            .sum::<D>()
+            + D::from(Distance(1));
+        let forbidden = big + big;
+
+        let symmetric = MatrixSym::new_from_distance_function(2 * dimension, |a, b| {
+            let (smaller, bigger) = (a.0.min(b.0), a.0.max(b.0));
+            if smaller == bigger || bigger == smaller + dimension {
+                D::ZERO
+            } else if smaller < dimension && bigger >= dimension {
+                // From the copy of `bigger - dimension` to `smaller`
+                matrix.get_data(Node(bigger - dimension), Node(smaller)) + big
+            } else {
+                forbidden
+            }
+        });
+
+        Self {
+            original_dimension: dimension,
+            offset: (0..dimension).map(|_| big).sum(),
+            matrix: symmetric,
+        }
+    }
+
+    /// Returns the symmetric distance matrix.
+    pub fn matrix(&self) -> &MatrixSym<D> {
+        &self.matrix
+    }
+
+    /// Returns the symmetric distance matrix, consuming the transformation.
+    pub fn into_matrix(self) -> MatrixSym<D> {
+        self.matrix
+    }
+
+    /// Returns the dimension of the original asymmetric instance.
+    pub fn original_dimension(&self) -> usize {
+        self.original_dimension
+    }
+
+    /// Returns the difference between the cost of a tour of the symmetric instance and the
+    /// corresponding tour of the asymmetric instance.
+    pub fn offset(&self) -> D {
+        self.offset
+    }
+
+    /// Converts the cost of a symmetric tour (or a bound on it) into the cost of the asymmetric
+    /// tour.
+    pub fn original_cost(&self, symmetric_cost: D) -> D {
+        symmetric_cost - self.offset
+    }
+
+    /// Translates a tour of the symmetric instance back into the order in which the asymmetric
+    /// tour visits the original nodes, starting at node 0.
+    ///
+    /// Returns an error if the tour does not alternate between nodes and their copies.
+    pub fn tour_to_original(&self, tour: &Tour<D>) -> Result<Vec<Node>, TransformError> {
+        let dimension = self.original_dimension;
+        tour.check_dimension(2 * dimension)?;
+
+        // Canonical tours start at node 0, the copy of node 0 has to follow in travel direction
+        let nodes = tour.nodes();
+        let ordered = if nodes.get(1) == Some(&Node(dimension)) {
+            nodes.to_vec()
+        } else {
+            let mut reversed = nodes.to_vec();
+            reversed[1..].reverse();
+            reversed
+        };
+
+        ordered
+            .as_chunks::<2>()
+            .0
+            .iter()
+            .map(|&[node, copy]| {
+                if node.0 < dimension && copy.0 == node.0 + dimension {
+                    Ok(node)
+                } else {
+                    Err(TransformError::MissingTieEdge(Node(node.0 % dimension)))
+                }
+            })
+            .collect()
+    }
+
+    /// Translates a tour of the asymmetric instance, given as the sequence of visited nodes, into
+    /// a tour of the symmetric instance.
+    pub fn tour_from_original(&self, nodes: &[Node]) -> Result<Tour<D>, TransformError> {
+        validate_permutation(nodes)?;
+        if nodes.len() != self.original_dimension {
+            return Err(TourError::DimensionMismatch {
+                expected: self.original_dimension,
+                found: nodes.len(),
+            }
+            .into());
+        }
+        let symmetric_nodes = nodes
+            .iter()
+            .flat_map(|&node| [node, node + self.original_dimension])
+            .collect();
+        Ok(Tour::from_nodes(symmetric_nodes, |from, to| {
+            self.matrix.get_data(from, to)
+        })?)
+    }
+}
+
+/// A subset of the nodes of an instance, numbered consecutively in the order they are given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeSubset {
+    /// The original node of every node of the subset.
+    original_nodes: Vec<Node>,
+    /// The node of the subset of every original node, if it is part of the subset.
+    subset_nodes: Vec<Option<Node>>,
+}
+
+impl NodeSubset {
+    /// Creates the subset of the nodes of an instance with `original_dimension` nodes. Node `i`
+    /// of the subset is `nodes[i]`.
+    ///
+    /// Returns an error if a node is out of range or given more than once.
+    pub fn new(original_dimension: usize, nodes: Vec<Node>) -> Result<Self, TransformError> {
+        let mut subset_nodes = vec![None; original_dimension];
+        for (index, &node) in nodes.iter().enumerate() {
+            match subset_nodes.get_mut(node.0) {
+                None => {
+                    return Err(TransformError::NodeOutOfRange {
+                        node,
+                        dimension: original_dimension,
+                    });
+                }
+                Some(Some(_)) => return Err(TransformError::DuplicateNode(node)),
+                Some(subset_node) => *subset_node = Some(Node(index)),
+            }
+        }
+        Ok(Self {
+            original_nodes: nodes,
+            subset_nodes,
+        })
+    }
+
+    /// Returns the number of nodes of the subset.
+    pub fn dimension(&self) -> usize {
+        self.original_nodes.len()
+    }
+
+    /// Returns the number of nodes of the original instance.
+    pub fn original_dimension(&self) -> usize {
+        self.subset_nodes.len()
+    }
+
+    /// Returns the original node of a node of the subset.
+    #[inline(always)]
+    pub fn original_node(&self, node: Node) -> Node {
+        self.original_nodes[node.0]
+    }
+
+    /// Returns the node of the subset of an original node, if it is part of the subset.
+    #[inline(always)]
+    pub fn subset_node(&self, original: Node) -> Option<Node> {
+        self.subset_nodes[original.0]
+    }
+
+    /// Translates a sequence of nodes of the subset (e.g. a tour) into original nodes.
+    pub fn nodes_to_original(&self, nodes: &[Node]) -> Vec<Node> {
+        nodes.iter().map(|&node| self.original_node(node)).collect()
+    }
+
+    /// Extracts the distances between the nodes of the subset.
+    pub fn restrict_matrix<Data: Copy>(&self, matrix: &Matrix<Data>) -> Matrix<Data> {
+        assert_eq!(matrix.dimension(), self.original_dimension());
+        Matrix::new_from_distance_function(self.dimension(), |from, to| {
+            matrix.get_data(self.original_node(from), self.original_node(to))
+        })
+    }
+
+    /// Extracts the distances between the nodes of the subset.
+    pub fn restrict_matrix_sym<Data: Copy>(&self, matrix: &MatrixSym<Data>) -> MatrixSym<Data> {
+        assert_eq!(matrix.dimension(), self.original_dimension());
+        MatrixSym::new_from_distance_function(self.dimension(), |from, to| {
+            matrix.get_data(self.original_node(from), self.original_node(to))
+        })
+    }
+
+    /// Extracts the per-node data (e.g. coordinates) of the nodes of the subset.
+    pub fn restrict_points<P: Clone>(&self, points: &[P]) -> Vec<P> {
+        assert_eq!(points.len(), self.original_dimension());
+        self.original_nodes
+            .iter()
+            .map(|node| points[node.0].clone())
+            .collect()
+    }
+}
+
+/// A permutation of the node ids of an instance.
+///
+/// Relabeling does not change the instance itself, but the order of the nodes in memory. E.g.
+/// with [`Relabeling::hilbert_order`], nodes close to each other get close ids, so the distances
+/// between them are close to each other in a distance matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relabeling {
+    /// A subset containing all nodes, in the new order.
+    nodes: NodeSubset,
+}
+
+impl Relabeling {
+    /// Creates the relabeling where the node with new id `i` is the original node `order[i]`.
+    ///
+    /// Returns an error if `order` is not a permutation of `0..order.len()`.
+    pub fn new(order: Vec<Node>) -> Result<Self, TransformError> {
+        validate_permutation(&order)?;
+        Ok(Self {
+            nodes: NodeSubset::new(order.len(), order)?,
+        })
+    }
+
+    /// Orders the nodes along a Hilbert curve through the bounding box of the points.
+    pub fn hilbert_order(points: &[Point2D]) -> Self {
+        /// The curve passes through a grid of `2^ORDER x 2^ORDER` cells.
+        const ORDER: u32 = 16;
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for point in points {
+            min_x = min_x.min(point.x);
+            min_y = min_y.min(point.y);
+            max_x = max_x.max(point.x);
+            max_y = max_y.max(point.y);
+        }
+        let cells = ((1u32 << ORDER) - 1) as f64;
+        let scale = cells / (max_x - min_x).max(max_y - min_y).max(f64::MIN_POSITIVE);
+
+        let mut keys = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let x = ((point.x - min_x) * scale) as u32;
+                let y = ((point.y - min_y) * scale) as u32;
+                (hilbert_index(ORDER, x, y), Node(index))
+            })
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+
+        Self::new(keys.into_iter().map(|(_, node)| node).collect())
+            .expect("Sorting the nodes yields a permutation")
+    }
+
+    /// Returns the number of nodes.
+    pub fn dimension(&self) -> usize {
+        self.nodes.dimension()
+    }
+
+    /// Returns the original id of the node with the given new id.
+    #[inline(always)]
+    pub fn original_node(&self, node: Node) -> Node {
+        self.nodes.original_node(node)
+    }
+
+    /// Returns the new id of the node with the given original id.
+    #[inline(always)]
+    pub fn relabeled_node(&self, original: Node) -> Node {
+        self.nodes
+            .subset_node(original)
+            .expect("A relabeling contains all nodes")
+    }
+
+    /// Reorders the distance matrix according to the new ids.
+    pub fn relabel_matrix<Data: Copy>(&self, matrix: &Matrix<Data>) -> Matrix<Data> {
+        self.nodes.restrict_matrix(matrix)
+    }
+
+    /// Reorders the distance matrix according to the new ids.
+    pub fn relabel_matrix_sym<Data: Copy>(&self, matrix: &MatrixSym<Data>) -> MatrixSym<Data> {
+        self.nodes.restrict_matrix_sym(matrix)
+    }
+
+    /// Reorders the per-node data (e.g. coordinates) according to the new ids.
+    pub fn relabel_points<P: Clone>(&self, points: &[P]) -> Vec<P> {
+        self.nodes.restrict_points(points)
+    }
+
+    /// Translates a tour of the relabeled instance back to the original ids. The cost stays the
+    /// same.
+    pub fn tour_to_original<D: DistanceValue>(
+        &self,
+        tour: &Tour<D>,
+    ) -> Result<Tour<D>, TransformError> {
+        tour.check_dimension(self.dimension())?;
+        let nodes = self.nodes.nodes_to_original(tour.nodes());
+        Ok(Tour::new(nodes, tour.cost())?)
+    }
+
+    /// Translates a tour of the original instance to the new ids. The cost stays the same.
+    pub fn tour_from_original<D: DistanceValue>(
+        &self,
+        tour: &Tour<D>,
+    ) -> Result<Tour<D>, TransformError> {
+        tour.check_dimension(self.dimension())?;
+        let nodes = tour
+            .nodes()
+            .iter()
+            .map(|&node| self.relabeled_node(node))
+            .collect();
+        Ok(Tour::new(nodes, tour.cost())?)
+    }
+}
+
+/// Returns the position of the cell `(x, y)` along the Hilbert curve through a grid of
+/// `2^order x 2^order` cells.
+fn hilbert_index(order: u32, mut x: u32, mut y: u32) -> u64 {
+    let side = 1u32 << order;
+    let mut index = 0;
+    let mut half = side / 2;
+    while half > 0 {
+        let right = x & half != 0;
+        let top = y & half != 0;
+        index += (half as u64).pow(2) * ((3 * right as u64) ^ top as u64);
+        // Rotate the quadrant, such that the curve through it starts at its lower left cell
+        if !top {
+            if right {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        half /= 2;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns all tours starting at node 0 (in both directions).
+    fn all_tours(dimension: usize) -> Vec<Vec<Node>> {
+        fn extend(tour: &mut Vec<Node>, dimension: usize, tours: &mut Vec<Vec<Node>>) {
+            if tour.len() == dimension {
+                tours.push(tour.clone());
+                return;
+            }
+            for node in (1..dimension).map(Node) {
+                if !tour.contains(&node) {
+                    tour.push(node);
+                    extend(tour, dimension, tours);
+                    tour.pop();
+                }
+            }
+        }
+        let mut tours = Vec::new();
+        extend(&mut vec![Node(0)], dimension, &mut tours);
+        tours
+    }
+
+    #[test]
+    fn test_atsp_to_stsp() {
+        #[rustfmt::skip]
+        let data = [
+            0, 1, 9, 4,
+            8, 0, 2, 9,
+            9, 7, 0, 3,
+            1, 9, 6, 0,
+        ].map(Distance).to_vec();
+        let matrix = Matrix::new(data, 4);
+        let transformation = AtspToStsp::new(&matrix);
+        assert_eq!(transformation.matrix().dimension(), 8);
+
+        let asymmetric_cost = |nodes: &[Node]| {
+            (0..nodes.len())
+                .map(|index| matrix.get_data(nodes[index], nodes[(index + 1) % nodes.len()]))
+                .sum::<Distance>()
+        };
+        let best_asymmetric = all_tours(4)
+            .iter()
+            .map(|nodes| asymmetric_cost(nodes))
+            .min()
+            .unwrap();
+        assert_eq!(best_asymmetric, Distance(7));
+
+        let best_symmetric = all_tours(8)
+            .into_iter()
+            .map(|nodes| {
+                Tour::from_nodes(nodes, |from, to| transformation.matrix().get_data(from, to))
+                    .unwrap()
+            })
+            .min_by_key(|tour| tour.cost())
+            .unwrap();
+        assert_eq!(
+            transformation.original_cost(best_symmetric.cost()),
+            best_asymmetric
+        );
+        let original = transformation.tour_to_original(&best_symmetric).unwrap();
+        assert_eq!(original, [0, 1, 2, 3].map(Node));
+
+        let back = transformation.tour_from_original(&original).unwrap();
+        assert_eq!(back, best_symmetric);
+
+        let not_alternating = Tour::new((0..8).map(Node).collect(), Distance(0)).unwrap();
+        assert!(matches!(
+            transformation.tour_to_original(&not_alternating),
+            Err(TransformError::MissingTieEdge(_))
+        ));
+    }
+
+    #[test]
+    fn test_node_subset() {
+        let matrix = MatrixSym::new_from_distance_function(6, |from, to| {
+            Distance((10 * from.0 + to.0).max(10 * to.0 + from.0) as i32)
+        });
+        let subset = NodeSubset::new(6, vec![Node(4), Node(1), Node(5)]).unwrap();
+
+        let restricted = subset.restrict_matrix_sym(&matrix);
+        assert_eq!(restricted.dimension(), 3);
+        assert_eq!(restricted.get_data(Node(0), Node(1)), Distance(41));
+        assert_eq!(restricted.get_data(Node(2), Node(0)), Distance(54));
+        assert_eq!(subset.subset_node(Node(5)), Some(Node(2)));
+        assert_eq!(subset.subset_node(Node(0)), None);
+        assert_eq!(
+            subset.nodes_to_original(&[Node(0), Node(2), Node(1)]),
+            vec![Node(4), Node(5), Node(1)]
+        );
+
+        assert_eq!(
+            NodeSubset::new(6, vec![Node(1), Node(1)]),
+            Err(TransformError::DuplicateNode(Node(1)))
+        );
+        assert_eq!(
+            NodeSubset::new(6, vec![Node(6)]),
+            Err(TransformError::NodeOutOfRange {
+                node: Node(6),
+                dimension: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_hilbert_relabeling() {
+        // A 4x4 grid, ids in row major order
+        let points = (0..16)
+            .map(|index| Point2D {
+                x: (index % 4) as f64,
+                y: (index / 4) as f64,
+            })
+            .collect::<Vec<_>>();
+        let relabeling = Relabeling::hilbert_order(&points);
+        let relabeled = relabeling.relabel_points(&points);
+
+        // Consecutive nodes along the Hilbert curve are neighbors in the grid
+        for pair in relabeled.windows(2) {
+            assert_eq!(
+                (pair[0].x - pair[1].x).abs() + (pair[0].y - pair[1].y).abs(),
+                1.0
+            );
+        }
+        assert_eq!(relabeling.original_node(Node(0)), Node(0));
+        assert_eq!(relabeling.original_node(Node(15)), Node(3));
+
+        let row_major = Tour::new((0..16).map(Node).collect(), Distance(42)).unwrap();
+        let translated = relabeling.tour_from_original(&row_major).unwrap();
+        assert_eq!(translated.cost(), Distance(42));
+        assert_eq!(relabeling.tour_to_original(&translated).unwrap(), row_major);
+
+        assert!(Relabeling::new(vec![Node(0), Node(2)]).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 #![warn(missing_debug_implementations, missing_docs)]
 
 pub mod instance;
+pub mod metric;
 pub mod tsp_lib_spec;
 
 #[cfg(test)]
@@ -0,0 +1,806 @@
+//! Distance functions for node coordinates as defined in TSPLIB95, plus the extensions of LKH.
+//!
+//! All distances are rounded to integers as required by the specification. Computations are
+//! carried out in double precision arithmetic.
+//!
+//! Every distance function is available as a plain function and as a type implementing [`Metric`]
+//! (zero-sized, except for the toroidal metrics which carry the grid size). The latter can be
+//! passed around generically without losing the ability of the compiler to inline the distance
+//! computation. [`Metric2D`] and [`Metric3D`] select the metric at runtime from an
+//! [`EdgeWeightType`].
+
+use std::f64::consts::PI;
+
+use crate::{
+    instance::{
+        InstanceMetadata,
+        distance::Distance,
+        point::{GeoPoint, Point2D, Point3D},
+    },
+    tsp_lib_spec::EdgeWeightType,
+};
+
+/// A distance function between points of a fixed point type.
+///
+/// Metrics are expected to be symmetric, i.e. `distance(a, b) == distance(b, a)`.
+pub trait Metric {
+    /// The point type the metric is defined on.
+    type Point;
+
+    /// Computes the distance between two points.
+    fn distance(&self, point_a: &Self::Point, point_b: &Self::Point) -> Distance;
+}
+
+macro_rules! metric_type {
+    ($(#[$attr:meta])* $name:ident, $point:ty, $function:path) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name;
+
+        impl Metric for $name {
+            type Point = $point;
+
+            #[inline(always)]
+            fn distance(&self, point_a: &$point, point_b: &$point) -> Distance {
+                $function(point_a, point_b)
+            }
+        }
+    };
+}
+
+metric_type!(
+    /// The `EUC_2D` metric, see [`euclidean_distance_2d`].
+    Euclidean2D,
+    Point2D,
+    euclidean_distance_2d
+);
+metric_type!(
+    /// The `EUC_3D` metric, see [`euclidean_distance_3d`].
+    Euclidean3D,
+    Point3D,
+    euclidean_distance_3d
+);
+metric_type!(
+    /// The `MAX_2D` metric, see [`max_distance_2d`].
+    Maximum2D,
+    Point2D,
+    max_distance_2d
+);
+metric_type!(
+    /// The `MAX_3D` metric, see [`max_distance_3d`].
+    Maximum3D,
+    Point3D,
+    max_distance_3d
+);
+metric_type!(
+    /// The `MAN_2D` metric, see [`manhattan_distance_2d`].
+    Manhattan2D,
+    Point2D,
+    manhattan_distance_2d
+);
+metric_type!(
+    /// The `MAN_3D` metric, see [`manhattan_distance_3d`].
+    Manhattan3D,
+    Point3D,
+    manhattan_distance_3d
+);
+metric_type!(
+    /// The `CEIL_2D` metric, see [`ceil_distance_2d`].
+    Ceil2D,
+    Point2D,
+    ceil_distance_2d
+);
+metric_type!(
+    /// The `ATT` (pseudo-Euclidean) metric, see [`att_distance_2d`].
+    Att,
+    Point2D,
+    att_distance_2d
+);
+metric_type!(
+    /// The `GEO` metric on already converted coordinates, see [`geographical_distance`] and
+    /// [`convert_to_geo_coordinates`].
+    Geographical,
+    GeoPoint,
+    geographical_distance
+);
+
+metric_type!(
+    /// The `FLOOR_2D` metric (LKH extension), see [`floor_distance_2d`].
+    Floor2D,
+    Point2D,
+    floor_distance_2d
+);
+metric_type!(
+    /// The `FLOOR_3D` metric (LKH extension), see [`floor_distance_3d`].
+    Floor3D,
+    Point3D,
+    floor_distance_3d
+);
+metric_type!(
+    /// The `CEIL_3D` metric (LKH extension), see [`ceil_distance_3d`].
+    Ceil3D,
+    Point3D,
+    ceil_distance_3d
+);
+metric_type!(
+    /// The `GEOM` metric (LKH extension) on already converted coordinates, see
+    /// [`geom_distance`] and [`convert_degrees_to_geo_coordinates`].
+    Geom,
+    GeoPoint,
+    geom_distance
+);
+metric_type!(
+    /// The `GEOM_MEEUS` metric (LKH extension) on already converted coordinates, see
+    /// [`geom_meeus_distance`] and [`convert_degrees_to_geo_coordinates`].
+    GeomMeeus,
+    GeoPoint,
+    geom_meeus_distance
+);
+metric_type!(
+    /// The `GEO_MEEUS` metric (LKH extension) on already converted coordinates, see
+    /// [`geo_meeus_distance`] and [`convert_to_geo_coordinates`].
+    GeoMeeus,
+    GeoPoint,
+    geo_meeus_distance
+);
+
+/// Default side length of the torus for `TOR_2D` and `TOR_3D` if the instance does not specify a
+/// `GRID_SIZE`. Matches the default of LKH.
+pub const DEFAULT_GRID_SIZE: f64 = 1_000_000.0;
+
+/// The `TOR_2D` metric (LKH extension), see [`toroidal_distance_2d`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Toroidal2D {
+    /// Side length of the torus.
+    pub grid_size: f64,
+}
+
+impl Metric for Toroidal2D {
+    type Point = Point2D;
+
+    #[inline(always)]
+    fn distance(&self, point_a: &Point2D, point_b: &Point2D) -> Distance {
+        toroidal_distance_2d(point_a, point_b, self.grid_size)
+    }
+}
+
+/// The `TOR_3D` metric (LKH extension), see [`toroidal_distance_3d`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Toroidal3D {
+    /// Side length of the torus.
+    pub grid_size: f64,
+}
+
+impl Metric for Toroidal3D {
+    type Point = Point3D;
+
+    #[inline(always)]
+    fn distance(&self, point_a: &Point3D, point_b: &Point3D) -> Distance {
+        toroidal_distance_3d(point_a, point_b, self.grid_size)
+    }
+}
+
+/// The metrics defined on two-dimensional node coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric2D {
+    /// `EUC_2D`
+    Euclidean,
+    /// `MAX_2D`
+    Maximum,
+    /// `MAN_2D`
+    Manhattan,
+    /// `CEIL_2D`
+    Ceil,
+    /// `FLOOR_2D`
+    Floor,
+    /// `ATT`
+    Att,
+    /// `GEO`, the coordinates are converted with [`convert_to_geo_coordinates`].
+    Geographical,
+    /// `GEOM`, the coordinates are converted with [`convert_degrees_to_geo_coordinates`].
+    Geom,
+    /// `GEOM_MEEUS`, the coordinates are converted with [`convert_degrees_to_geo_coordinates`].
+    GeomMeeus,
+    /// `GEO_MEEUS`, the coordinates are converted with [`convert_to_geo_coordinates`].
+    GeoMeeus,
+    /// `TOR_2D`
+    Toroidal {
+        /// Side length of the torus.
+        grid_size: f64,
+    },
+}
+
+impl Metric2D {
+    /// Returns the metric for the given edge weight type, or `None` if the edge weight type is not
+    /// defined on two-dimensional coordinates.
+    ///
+    /// `TOR_2D` uses the [`DEFAULT_GRID_SIZE`], see [`Metric2D::from_metadata`] for respecting the
+    /// `GRID_SIZE` of an instance.
+    pub fn from_edge_weight_type(edge_weight_type: &EdgeWeightType) -> Option<Self> {
+        match edge_weight_type {
+            EdgeWeightType::EUC_2D => Some(Self::Euclidean),
+            EdgeWeightType::MAX_2D => Some(Self::Maximum),
+            EdgeWeightType::MAN_2D => Some(Self::Manhattan),
+            EdgeWeightType::CEIL_2D => Some(Self::Ceil),
+            EdgeWeightType::FLOOR_2D => Some(Self::Floor),
+            EdgeWeightType::ATT => Some(Self::Att),
+            EdgeWeightType::GEO => Some(Self::Geographical),
+            EdgeWeightType::GEOM => Some(Self::Geom),
+            EdgeWeightType::GEOM_MEEUS => Some(Self::GeomMeeus),
+            EdgeWeightType::GEO_MEEUS => Some(Self::GeoMeeus),
+            EdgeWeightType::TOR_2D => Some(Self::Toroidal {
+                grid_size: DEFAULT_GRID_SIZE,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the metric for the edge weight type of the instance, or `None` if the edge weight
+    /// type is not defined on two-dimensional coordinates.
+    pub fn from_metadata(metadata: &InstanceMetadata) -> Option<Self> {
+        match Self::from_edge_weight_type(&metadata.edge_weight_type)? {
+            Self::Toroidal { .. } => Some(Self::Toroidal {
+                grid_size: metadata.grid_size.unwrap_or(DEFAULT_GRID_SIZE),
+            }),
+            metric => Some(metric),
+        }
+    }
+
+    /// Returns the edge weight type corresponding to the metric.
+    pub fn edge_weight_type(self) -> EdgeWeightType {
+        match self {
+            Self::Euclidean => EdgeWeightType::EUC_2D,
+            Self::Maximum => EdgeWeightType::MAX_2D,
+            Self::Manhattan => EdgeWeightType::MAN_2D,
+            Self::Ceil => EdgeWeightType::CEIL_2D,
+            Self::Floor => EdgeWeightType::FLOOR_2D,
+            Self::Att => EdgeWeightType::ATT,
+            Self::Geographical => EdgeWeightType::GEO,
+            Self::Geom => EdgeWeightType::GEOM,
+            Self::GeomMeeus => EdgeWeightType::GEOM_MEEUS,
+            Self::GeoMeeus => EdgeWeightType::GEO_MEEUS,
+            Self::Toroidal { .. } => EdgeWeightType::TOR_2D,
+        }
+    }
+}
+
+impl Metric for Metric2D {
+    type Point = Point2D;
+
+    fn distance(&self, point_a: &Point2D, point_b: &Point2D) -> Distance {
+        match *self {
+            Self::Euclidean => euclidean_distance_2d(point_a, point_b),
+            Self::Maximum => max_distance_2d(point_a, point_b),
+            Self::Manhattan => manhattan_distance_2d(point_a, point_b),
+            Self::Ceil => ceil_distance_2d(point_a, point_b),
+            Self::Floor => floor_distance_2d(point_a, point_b),
+            Self::Att => att_distance_2d(point_a, point_b),
+            Self::Geographical => geographical_distance(
+                &convert_to_geo_coordinates(point_a),
+                &convert_to_geo_coordinates(point_b),
+            ),
+            Self::Geom => geom_distance(
+                &convert_degrees_to_geo_coordinates(point_a),
+                &convert_degrees_to_geo_coordinates(point_b),
+            ),
+            Self::GeomMeeus => geom_meeus_distance(
+                &convert_degrees_to_geo_coordinates(point_a),
+                &convert_degrees_to_geo_coordinates(point_b),
+            ),
+            Self::GeoMeeus => geo_meeus_distance(
+                &convert_to_geo_coordinates(point_a),
+                &convert_to_geo_coordinates(point_b),
+            ),
+            Self::Toroidal { grid_size } => toroidal_distance_2d(point_a, point_b, grid_size),
+        }
+    }
+}
+
+/// The metrics defined on three-dimensional node coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric3D {
+    /// `EUC_3D`
+    Euclidean,
+    /// `MAX_3D`
+    Maximum,
+    /// `MAN_3D`
+    Manhattan,
+    /// `CEIL_3D`
+    Ceil,
+    /// `FLOOR_3D`
+    Floor,
+    /// `TOR_3D`
+    Toroidal {
+        /// Side length of the torus.
+        grid_size: f64,
+    },
+}
+
+impl Metric3D {
+    /// Returns the metric for the given edge weight type, or `None` if the edge weight type is not
+    /// defined on three-dimensional coordinates.
+    ///
+    /// `TOR_3D` uses the [`DEFAULT_GRID_SIZE`], see [`Metric3D::from_metadata`] for respecting the
+    /// `GRID_SIZE` of an instance.
+    pub fn from_edge_weight_type(edge_weight_type: &EdgeWeightType) -> Option<Self> {
+        match edge_weight_type {
+            EdgeWeightType::EUC_3D => Some(Self::Euclidean),
+            EdgeWeightType::MAX_3D => Some(Self::Maximum),
+            EdgeWeightType::MAN_3D => Some(Self::Manhattan),
+            EdgeWeightType::CEIL_3D => Some(Self::Ceil),
+            EdgeWeightType::FLOOR_3D => Some(Self::Floor),
+            EdgeWeightType::TOR_3D => Some(Self::Toroidal {
+                grid_size: DEFAULT_GRID_SIZE,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the metric for the edge weight type of the instance, or `None` if the edge weight
+    /// type is not defined on three-dimensional coordinates.
+    pub fn from_metadata(metadata: &InstanceMetadata) -> Option<Self> {
+        match Self::from_edge_weight_type(&metadata.edge_weight_type)? {
+            Self::Toroidal { .. } => Some(Self::Toroidal {
+                grid_size: metadata.grid_size.unwrap_or(DEFAULT_GRID_SIZE),
+            }),
+            metric => Some(metric),
+        }
+    }
+
+    /// Returns the edge weight type corresponding to the metric.
+    pub fn edge_weight_type(self) -> EdgeWeightType {
+        match self {
+            Self::Euclidean => EdgeWeightType::EUC_3D,
+            Self::Maximum => EdgeWeightType::MAX_3D,
+            Self::Manhattan => EdgeWeightType::MAN_3D,
+            Self::Ceil => EdgeWeightType::CEIL_3D,
+            Self::Floor => EdgeWeightType::FLOOR_3D,
+            Self::Toroidal { .. } => EdgeWeightType::TOR_3D,
+        }
+    }
+}
+
+impl Metric for Metric3D {
+    type Point = Point3D;
+
+    fn distance(&self, point_a: &Point3D, point_b: &Point3D) -> Distance {
+        match *self {
+            Self::Euclidean => euclidean_distance_3d(point_a, point_b),
+            Self::Maximum => max_distance_3d(point_a, point_b),
+            Self::Manhattan => manhattan_distance_3d(point_a, point_b),
+            Self::Ceil => ceil_distance_3d(point_a, point_b),
+            Self::Floor => floor_distance_3d(point_a, point_b),
+            Self::Toroidal { grid_size } => toroidal_distance_3d(point_a, point_b, grid_size),
+        }
+    }
+}
+
+/// Computes the 2D Euclidean distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn euclidean_distance_2d(point_a: &Point2D, point_b: &Point2D) -> Distance {
+    Distance(nint(
+        ((point_a.x - point_b.x).powi(2) + (point_a.y - point_b.y).powi(2)).sqrt(),
+    ))
+}
+
+/// Computes the 3D Euclidean distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn euclidean_distance_3d(point_a: &Point3D, point_b: &Point3D) -> Distance {
+    Distance(nint(
+        ((point_a.x - point_b.x).powi(2)
+            + (point_a.y - point_b.y).powi(2)
+            + (point_a.z - point_b.z).powi(2))
+        .sqrt(),
+    ))
+}
+
+/// Computes the 2D Max distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn max_distance_2d(point_a: &Point2D, point_b: &Point2D) -> Distance {
+    Distance(nint(
+        (point_a.x - point_b.x)
+            .abs()
+            .max((point_a.y - point_b.y).abs()),
+    ))
+}
+
+/// Computes the 3D Max distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn max_distance_3d(point_a: &Point3D, point_b: &Point3D) -> Distance {
+    Distance(nint(
+        (point_a.x - point_b.x)
+            .abs()
+            .max((point_a.y - point_b.y).abs())
+            .max((point_a.z - point_b.z).abs()),
+    ))
+}
+
+/// Computes the 2D Manhattan distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn manhattan_distance_2d(point_a: &Point2D, point_b: &Point2D) -> Distance {
+    Distance(nint(
+        (point_a.x - point_b.x).abs() + (point_a.y - point_b.y).abs(),
+    ))
+}
+
+/// Computes the 3D Manhattan distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn manhattan_distance_3d(point_a: &Point3D, point_b: &Point3D) -> Distance {
+    Distance(nint(
+        (point_a.x - point_b.x).abs()
+            + (point_a.y - point_b.y).abs()
+            + (point_a.z - point_b.z).abs(),
+    ))
+}
+
+/// Computes the 2D Ceil distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn ceil_distance_2d(point_a: &Point2D, point_b: &Point2D) -> Distance {
+    Distance(
+        ((point_a.x - point_b.x).powi(2) + (point_a.y - point_b.y).powi(2))
+            .sqrt()
+            .ceil() as i32,
+    )
+}
+
+/// Computes the geographical distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn geographical_distance(point_a: &GeoPoint, point_b: &GeoPoint) -> Distance {
+    if point_a == point_b {
+        return Distance(0);
+    }
+    let rrr = 6378.388;
+    let q1 = (point_a.longitude - point_b.longitude).cos();
+    let q2 = (point_a.latitude - point_b.latitude).cos();
+    let q3 = (point_a.latitude + point_b.latitude).cos();
+
+    Distance(((rrr * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos()) + 1.0) as i32)
+}
+
+/// Converts a 2D point representing geographical coordinates as defined in TSPLIB95.
+pub fn convert_to_geo_coordinates(point: &Point2D) -> GeoPoint {
+    let deg_lat = point.x.trunc();
+    let min_lat = point.x - deg_lat;
+    let latitude = PI * (deg_lat + ((5.0 * min_lat) / 3.0)) / 180.0;
+
+    let deg_lon = point.y.trunc();
+    let min_lon = point.y - deg_lon;
+    let longitude = PI * (deg_lon + ((5.0 * min_lon) / 3.0)) / 180.0;
+
+    GeoPoint {
+        latitude,
+        longitude,
+    }
+}
+
+/// Computes the ATT distance between two points as defined in TSPLIB95.
+#[inline(always)]
+pub fn att_distance_2d(point_a: &Point2D, point_b: &Point2D) -> Distance {
+    let xd = point_a.x - point_b.x;
+    let yd = point_a.y - point_b.y;
+
+    let rij = ((xd.powi(2) + yd.powi(2)) / 10.0).sqrt();
+    let tij = nint(rij);
+    if (tij as f64) < rij {
+        Distance(tij + 1)
+    } else {
+        Distance(tij)
+    }
+}
+
+/// Computes the 3D Ceil distance between two points (LKH extension).
+#[inline(always)]
+pub fn ceil_distance_3d(point_a: &Point3D, point_b: &Point3D) -> Distance {
+    Distance(
+        ((point_a.x - point_b.x).powi(2)
+            + (point_a.y - point_b.y).powi(2)
+            + (point_a.z - point_b.z).powi(2))
+        .sqrt()
+        .ceil() as i32,
+    )
+}
+
+/// Computes the 2D Floor distance between two points (LKH extension).
+#[inline(always)]
+pub fn floor_distance_2d(point_a: &Point2D, point_b: &Point2D) -> Distance {
+    Distance(
+        ((point_a.x - point_b.x).powi(2) + (point_a.y - point_b.y).powi(2))
+            .sqrt()
+            .floor() as i32,
+    )
+}
+
+/// Computes the 3D Floor distance between two points (LKH extension).
+#[inline(always)]
+pub fn floor_distance_3d(point_a: &Point3D, point_b: &Point3D) -> Distance {
+    Distance(
+        ((point_a.x - point_b.x).powi(2)
+            + (point_a.y - point_b.y).powi(2)
+            + (point_a.z - point_b.z).powi(2))
+        .sqrt()
+        .floor() as i32,
+    )
+}
+
+/// Computes the 2D Euclidean distance on a torus with the given side length (LKH extension).
+///
+/// Along every axis, the shorter way around the torus is taken.
+#[inline(always)]
+pub fn toroidal_distance_2d(point_a: &Point2D, point_b: &Point2D, grid_size: f64) -> Distance {
+    let dx = toroidal_delta(point_a.x, point_b.x, grid_size);
+    let dy = toroidal_delta(point_a.y, point_b.y, grid_size);
+    Distance(nint((dx * dx + dy * dy).sqrt()))
+}
+
+/// Computes the 3D Euclidean distance on a torus with the given side length (LKH extension).
+///
+/// Along every axis, the shorter way around the torus is taken.
+#[inline(always)]
+pub fn toroidal_distance_3d(point_a: &Point3D, point_b: &Point3D, grid_size: f64) -> Distance {
+    let dx = toroidal_delta(point_a.x, point_b.x, grid_size);
+    let dy = toroidal_delta(point_a.y, point_b.y, grid_size);
+    let dz = toroidal_delta(point_a.z, point_b.z, grid_size);
+    Distance(nint((dx * dx + dy * dy + dz * dz).sqrt()))
+}
+
+#[inline(always)]
+pub(crate) fn toroidal_delta(a: f64, b: f64, grid_size: f64) -> f64 {
+    let delta = (a - b).abs();
+    if grid_size - delta < delta {
+        grid_size - delta
+    } else {
+        delta
+    }
+}
+
+/// Converts a 2D point of latitude (x) and longitude (y) in decimal degrees into radians, as
+/// used by `GEOM` and `GEOM_MEEUS`.
+pub fn convert_degrees_to_geo_coordinates(point: &Point2D) -> GeoPoint {
+    GeoPoint {
+        latitude: PI * point.x / 180.0,
+        longitude: PI * point.y / 180.0,
+    }
+}
+
+/// Computes the great circle distance in meters between two points as defined for `GEOM` by
+/// LKH and Concorde (used by the World TSP and the national instances).
+#[inline(always)]
+pub fn geom_distance(point_a: &GeoPoint, point_b: &GeoPoint) -> Distance {
+    let delta_longitude = point_a.longitude - point_b.longitude;
+    let q1 = point_b.latitude.cos() * delta_longitude.sin();
+    let q3 = (delta_longitude / 2.0).sin();
+    let q4 = (delta_longitude / 2.0).cos();
+    let q2 = (point_a.latitude + point_b.latitude).sin() * q3 * q3
+        - (point_a.latitude - point_b.latitude).sin() * q4 * q4;
+    let q5 = (point_a.latitude - point_b.latitude).cos() * q4 * q4
+        - (point_a.latitude + point_b.latitude).cos() * q3 * q3;
+
+    Distance((6_378_388.0 * (q1 * q1 + q2 * q2).sqrt().atan2(q5) + 1.0) as i32)
+}
+
+/// Computes the distance in meters between two points on the ellipsoid as defined for
+/// `GEOM_MEEUS` by LKH.
+#[inline(always)]
+pub fn geom_meeus_distance(point_a: &GeoPoint, point_b: &GeoPoint) -> Distance {
+    meeus_distance(point_a, point_b, 6_378_388.0)
+}
+
+/// Computes the distance in kilometers between two points on the ellipsoid as defined for
+/// `GEO_MEEUS` by LKH.
+#[inline(always)]
+pub fn geo_meeus_distance(point_a: &GeoPoint, point_b: &GeoPoint) -> Distance {
+    meeus_distance(point_a, point_b, 6378.388)
+}
+
+/// Distance on the reference ellipsoid with the given equatorial radius, following the
+/// approximation from Meeus, "Astronomical Algorithms", chapter 11.
+#[inline(always)]
+fn meeus_distance(point_a: &GeoPoint, point_b: &GeoPoint, radius: f64) -> Distance {
+    const FLATTENING: f64 = 1.0 / 298.257;
+
+    let f = (point_a.latitude + point_b.latitude) / 2.0;
+    let g = (point_a.latitude - point_b.latitude) / 2.0;
+    let l = (point_a.longitude - point_b.longitude) / 2.0;
+    let (sin_f2, cos_f2) = (f.sin().powi(2), f.cos().powi(2));
+    let (sin_g2, cos_g2) = (g.sin().powi(2), g.cos().powi(2));
+    let (sin_l2, cos_l2) = (l.sin().powi(2), l.cos().powi(2));
+
+    let s = sin_g2 * cos_l2 + cos_f2 * sin_l2;
+    let c = cos_g2 * cos_l2 + sin_f2 * sin_l2;
+    if s == 0.0 {
+        return Distance(0);
+    }
+    if c == 0.0 {
+        // Antipodal points, the correction terms are not defined
+        return Distance(nint(PI * radius));
+    }
+
+    let w = (s / c).sqrt().atan();
+    let r = (s * c).sqrt() / w;
+    let d = 2.0 * w * radius;
+    let h1 = (3.0 * r - 1.0) / (2.0 * c);
+    let h2 = (3.0 * r + 1.0) / (2.0 * s);
+
+    Distance(nint(
+        d * (1.0 + FLATTENING * h1 * sin_f2 * cos_g2 - FLATTENING * h2 * cos_f2 * sin_g2),
+    ))
+}
+
+/// Nearest integer function as defined in TSPLIB95.
+///
+/// Expects a non-negative float input.
+#[inline(always)]
+pub fn nint(x: f64) -> i32 {
+    (x + 0.5) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_2d_dispatch_matches_distance_functions() {
+        let point_a = Point2D { x: 16.47, y: 96.10 };
+        let point_b = Point2D { x: 20.09, y: 94.55 };
+
+        assert_eq!(
+            Metric2D::from_edge_weight_type(&EdgeWeightType::EUC_2D)
+                .unwrap()
+                .distance(&point_a, &point_b),
+            Euclidean2D.distance(&point_a, &point_b)
+        );
+        assert_eq!(
+            Metric2D::Geographical.distance(&point_a, &point_b),
+            geographical_distance(
+                &convert_to_geo_coordinates(&point_a),
+                &convert_to_geo_coordinates(&point_b)
+            )
+        );
+        assert!(Metric2D::from_edge_weight_type(&EdgeWeightType::EUC_3D).is_none());
+    }
+
+    #[test]
+    fn test_metric_round_trips_edge_weight_type() {
+        for metric in [
+            Metric2D::Euclidean,
+            Metric2D::Maximum,
+            Metric2D::Manhattan,
+            Metric2D::Ceil,
+            Metric2D::Floor,
+            Metric2D::Att,
+            Metric2D::Geographical,
+            Metric2D::Geom,
+            Metric2D::GeomMeeus,
+            Metric2D::GeoMeeus,
+            Metric2D::Toroidal {
+                grid_size: DEFAULT_GRID_SIZE,
+            },
+        ] {
+            assert_eq!(
+                Metric2D::from_edge_weight_type(&metric.edge_weight_type()),
+                Some(metric)
+            );
+        }
+        for metric in [
+            Metric3D::Euclidean,
+            Metric3D::Maximum,
+            Metric3D::Manhattan,
+            Metric3D::Ceil,
+            Metric3D::Floor,
+            Metric3D::Toroidal {
+                grid_size: DEFAULT_GRID_SIZE,
+            },
+        ] {
+            assert_eq!(
+                Metric3D::from_edge_weight_type(&metric.edge_weight_type()),
+                Some(metric)
+            );
+        }
+    }
+
+    #[test]
+    fn test_floor_and_ceil_3d() {
+        let origin = Point3D {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let point = Point3D {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        };
+
+        assert_eq!(Floor3D.distance(&origin, &point), Distance(1));
+        assert_eq!(Ceil3D.distance(&origin, &point), Distance(2));
+        assert_eq!(
+            Floor2D.distance(&Point2D { x: 0.0, y: 0.0 }, &Point2D { x: 1.0, y: 1.0 }),
+            Distance(1)
+        );
+    }
+
+    #[test]
+    fn test_toroidal_distance_wraps_around() {
+        let metric = Toroidal2D { grid_size: 100.0 };
+
+        assert_eq!(
+            metric.distance(&Point2D { x: 1.0, y: 50.0 }, &Point2D { x: 99.0, y: 50.0 }),
+            Distance(2)
+        );
+        assert_eq!(
+            metric.distance(&Point2D { x: 10.0, y: 10.0 }, &Point2D { x: 40.0, y: 50.0 }),
+            Distance(50)
+        );
+    }
+
+    #[test]
+    fn test_geom_distances_on_the_equator() {
+        // One degree of longitude on the equator is 6378388 * PI / 180 = 111323.87 meters
+        let point_a = Point2D { x: 0.0, y: 0.0 };
+        let point_b = Point2D { x: 0.0, y: 1.0 };
+
+        // GEOM adds one meter before truncating, GEOM_MEEUS rounds to the nearest integer
+        assert_eq!(
+            Metric2D::Geom.distance(&point_a, &point_b),
+            Distance(111_324)
+        );
+        assert_eq!(
+            Metric2D::GeomMeeus.distance(&point_a, &point_b),
+            Distance(111_324)
+        );
+        assert_eq!(Metric2D::Geom.distance(&point_a, &point_a), Distance(1));
+        assert_eq!(
+            Metric2D::GeomMeeus.distance(&point_a, &point_a),
+            Distance(0)
+        );
+    }
+
+    #[test]
+    fn test_geo_distances_of_burma14() {
+        // The minutes of the coordinates are truncated, not rounded, so 21.52 is 21 degrees and 52
+        // minutes. The expected distances are those of the explicit TSPLIB matrix of burma14.
+        let point_1 = Point2D { x: 16.47, y: 96.10 };
+        let point_12 = Point2D { x: 21.52, y: 95.59 };
+        let point_14 = Point2D { x: 20.09, y: 94.55 };
+
+        assert_eq!(
+            Metric2D::Geographical.distance(&point_1, &point_12),
+            Distance(567)
+        );
+        assert_eq!(
+            Metric2D::Geographical.distance(&point_1, &point_14),
+            Distance(398)
+        );
+    }
+
+    #[test]
+    fn test_geo_meeus_distance_from_paris_to_washington() {
+        // Example 11.c of Meeus, "Astronomical Algorithms": Paris at 48°50'11" N 2°20'14" E and
+        // Washington at 38°55'17" N 77°03'56" W are 6181.63 km apart with an equatorial radius of
+        // 6378.14 km. Scaled to the radius 6378.388 km of LKH, this is 6181.87 km.
+        let paris = Point2D {
+            x: 48.0 + 50.0 / 100.0 + 11.0 / 6000.0,
+            y: 2.0 + 20.0 / 100.0 + 14.0 / 6000.0,
+        };
+        let washington = Point2D {
+            x: 38.0 + 55.0 / 100.0 + 17.0 / 6000.0,
+            y: -(77.0 + 3.0 / 100.0 + 56.0 / 6000.0),
+        };
+
+        assert_eq!(
+            Metric2D::GeoMeeus.distance(&paris, &washington),
+            Distance(6182)
+        );
+    }
+
+    #[test]
+    fn test_geo_meeus_close_to_geo() {
+        let point_a = Point2D { x: 16.47, y: 96.10 };
+        let point_b = Point2D { x: 20.09, y: 94.55 };
+
+        let geo = Metric2D::Geographical.distance(&point_a, &point_b).0;
+        let geo_meeus = Metric2D::GeoMeeus.distance(&point_a, &point_b).0;
+        // The ellipsoid correction is well below one percent
+        assert!((geo - geo_meeus).abs() * 100 < geo, "{geo} vs {geo_meeus}");
+    }
+}
@@ -4,6 +4,7 @@
 ///
 /// The Keywords are according to the TSPLIB 95 specification.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TSPDataKeyword {
     NODE_COORD_SECTION,
     DEPOT_SECTION,
@@ -20,6 +21,7 @@ pub enum TSPDataKeyword {
 ///
 /// The Keywords are according to the TSPLIB 95 specification.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TSPSpecificationKeyword {
     NAME(String),
     TYPE(ProblemType),
@@ -31,10 +33,13 @@ pub enum TSPSpecificationKeyword {
     EDGE_DATA_FORMAT(EdgeDataFormat),
     NODE_COORD_TYPE(NodeCoordType),
     DISPLAY_DATA_TYPE(DisplayDataType),
+    /// Extension of LKH, the side length of the torus for `TOR_2D` and `TOR_3D`
+    GRID_SIZE(f64),
     EOF,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProblemType {
     TSP,
     ATSP,
@@ -45,6 +50,7 @@ pub enum ProblemType {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdgeWeightType {
     EXPLICIT,
     EUC_2D,
@@ -59,9 +65,27 @@ pub enum EdgeWeightType {
     XRAY1,
     XRAY2,
     SPECIAL,
+    // Extensions of LKH, used e.g. by the national and World TSP instances
+    /// LKH's `GEOM`: spherical distance in meters between coordinates in decimal degrees.
+    GEOM,
+    /// LKH's `GEOM_MEEUS`: ellipsoidal distance in meters between coordinates in decimal degrees.
+    GEOM_MEEUS,
+    /// LKH's `GEO_MEEUS`: ellipsoidal distance in kilometers between `GEO` coordinates.
+    GEO_MEEUS,
+    /// LKH's `TOR_2D`: 2D Euclidean distance on a torus with side length `GRID_SIZE`.
+    TOR_2D,
+    /// LKH's `TOR_3D`: 3D Euclidean distance on a torus with side length `GRID_SIZE`.
+    TOR_3D,
+    /// LKH's `FLOOR_2D`: 2D Euclidean distance rounded down.
+    FLOOR_2D,
+    /// LKH's `FLOOR_3D`: 3D Euclidean distance rounded down.
+    FLOOR_3D,
+    /// LKH's `CEIL_3D`: 3D Euclidean distance rounded up.
+    CEIL_3D,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdgeWeightFormat {
     FUNCTION,
     FULL_MATRIX,
@@ -76,12 +100,14 @@ pub enum EdgeWeightFormat {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdgeDataFormat {
     EDGE_LIST,
     ADJ_LIST,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeCoordType {
     TWOD_COORDS,
     THREED_COORDS,
@@ -89,6 +115,7 @@ pub enum NodeCoordType {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplayDataType {
     COORD_DISPLAY,
     TWOD_DISPLAY,
@@ -0,0 +1,678 @@
+//! Binary on-disk cache for distance matrices.
+//!
+//! Parsing big instances and computing their distance matrices takes seconds, while loading a
+//! cached matrix is instant: The cache file is memory mapped, and the matrix is read directly from
+//! the mapped file without copying it (see [`MappedMatrix`] and [`MappedMatrixSym`]).
+//!
+//! # Format
+//!
+//! A cache file consists of a header of [`HEADER_SIZE`] bytes, followed by the raw matrix data
+//! (in the layout of [`Matrix`] or [`MatrixSym`]) and the display data, if any. The header
+//! contains (all integers little endian):
+//!
+//! | Bytes    | Content                                                       |
+//! |----------|---------------------------------------------------------------|
+//! | `0..8`   | The magic bytes `TSPCACHE`                                    |
+//! | `8..12`  | The format version, see [`CACHE_FORMAT_VERSION`]              |
+//! | `12`     | The matrix layout, see [`MatrixLayout`]                       |
+//! | `13`     | The element type, see [`CacheElement::TYPE_TAG`]              |
+//! | `14`     | The size of an element in bytes                               |
+//! | `15`     | The byte order of the data, `1` for little and `2` for big endian |
+//! | `16..24` | The dimension                                                 |
+//! | `24..32` | The fingerprint of the instance file, see [`fingerprint`]     |
+//! | `32..40` | The number of display data points                             |
+//!
+//! The remaining header bytes are reserved and zero. The data is stored in the native byte order,
+//! so cache files are not portable between machines of different endianness (loading them fails
+//! instead).
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use log::{debug, info, warn};
+use memmap2::{Mmap, MmapMut};
+use thiserror::Error;
+use tsp_core::instance::{
+    TSPSymInstance,
+    distance::{Distance, Distance64, DistanceValue, FloatDistance},
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+    oracle::DistanceOracle,
+    point::Point2D,
+};
+
+use crate::{
+    FileContent, ParserError, distance_container::ParseFromTSPLib, metadata::parse_metadata,
+    parse_tsp_instance_from_content,
+};
+
+/// The size of the header, the data starts directly after it.
+pub const HEADER_SIZE: usize = 64;
+
+/// The version of the cache format. Cache files of other versions are rebuilt.
+pub const CACHE_FORMAT_VERSION: u32 = 1;
+
+const MAGIC: [u8; 8] = *b"TSPCACHE";
+
+#[cfg(target_endian = "little")]
+const NATIVE_BYTE_ORDER: u8 = 1;
+#[cfg(target_endian = "big")]
+const NATIVE_BYTE_ORDER: u8 = 2;
+
+/// Reasons why a cache file cannot be used.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CacheError {
+    /// The file does not start with the magic bytes.
+    #[error("The file is not a distance matrix cache")]
+    InvalidMagic,
+    /// The file was written with another version of the format.
+    #[error("Unsupported cache format version {0}, expected {CACHE_FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    /// The file contains a matrix of another layout or element type than requested.
+    #[error(
+        "The cache contains a {found_layout:?} matrix with element type {found_type}, expected a \
+         {expected_layout:?} matrix with element type {expected_type}"
+    )]
+    TypeMismatch {
+        /// The requested layout.
+        expected_layout: MatrixLayout,
+        /// The requested element type tag.
+        expected_type: u8,
+        /// The layout of the cached matrix, if it is valid.
+        found_layout: Option<MatrixLayout>,
+        /// The element type tag of the cached matrix.
+        found_type: u8,
+    },
+    /// The file was written on a machine with a different byte order.
+    #[error("The cache was written with a different byte order")]
+    ByteOrderMismatch,
+    /// The file is shorter than its header claims.
+    #[error("The cache is truncated, expected at least {expected} bytes but found {found}")]
+    Truncated {
+        /// The size given by the header.
+        expected: usize,
+        /// The actual size of the file.
+        found: usize,
+    },
+    /// The sizes in the header do not fit into the address space.
+    #[error(
+        "The cache header claims dimension {dimension} and {display_points} display points, which \
+         is too large"
+    )]
+    SizeOverflow {
+        /// The dimension given by the header.
+        dimension: u64,
+        /// The number of display data points given by the header.
+        display_points: u64,
+    },
+    /// The instance file changed since the cache was written.
+    #[error(
+        "The cache fingerprint {found:#x} does not match the instance fingerprint {expected:#x}"
+    )]
+    FingerprintMismatch {
+        /// The fingerprint of the instance file.
+        expected: u64,
+        /// The fingerprint stored in the cache.
+        found: u64,
+    },
+}
+
+/// The layout of a cached matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixLayout {
+    /// All `dimension^2` entries in row major order, as in [`Matrix`].
+    Full = 0,
+    /// The lower triangle including the diagonal in row major order, as in [`MatrixSym`].
+    Symmetric = 1,
+}
+
+impl MatrixLayout {
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(MatrixLayout::Full),
+            1 => Some(MatrixLayout::Symmetric),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of stored entries, or `None` if it overflows.
+    fn len(self, dimension: usize) -> Option<usize> {
+        match self {
+            MatrixLayout::Full => dimension.checked_mul(dimension),
+            MatrixLayout::Symmetric => dimension
+                .checked_add(1)
+                .and_then(|next| next.checked_mul(dimension))
+                .map(|size| size / 2),
+        }
+    }
+}
+
+/// Distance types that can be stored in cache files.
+///
+/// # Safety
+///
+/// Implementors have to be `repr(transparent)` wrappers of a primitive number type, such that
+/// they have no padding and every bit pattern is a valid value.
+#[expect(
+    unsafe_code,
+    reason = "Reinterpreting mapped bytes requires plain old data"
+)]
+pub unsafe trait CacheElement: DistanceValue {
+    /// Identifies the element type in the header.
+    const TYPE_TAG: u8;
+    /// Names the element type in cache file names.
+    const NAME: &'static str;
+}
+
+#[expect(
+    unsafe_code,
+    reason = "Reinterpreting mapped bytes requires plain old data"
+)]
+// SAFETY: `Distance` is a `repr(transparent)` wrapper of an `i32`.
+unsafe impl CacheElement for Distance {
+    const NAME: &'static str = "i32";
+    const TYPE_TAG: u8 = 1;
+}
+
+#[expect(
+    unsafe_code,
+    reason = "Reinterpreting mapped bytes requires plain old data"
+)]
+// SAFETY: `Distance64` is a `repr(transparent)` wrapper of an `i64`.
+unsafe impl CacheElement for Distance64 {
+    const NAME: &'static str = "i64";
+    const TYPE_TAG: u8 = 2;
+}
+
+#[expect(
+    unsafe_code,
+    reason = "Reinterpreting mapped bytes requires plain old data"
+)]
+// SAFETY: `FloatDistance` is a `repr(transparent)` wrapper of an `f64`.
+unsafe impl CacheElement for FloatDistance {
+    const NAME: &'static str = "f64";
+    const TYPE_TAG: u8 = 3;
+}
+
+/// Distance containers that are backed by a cache file.
+pub trait CachedContainer: DistanceOracle<Value = Self::Distance> + Sized {
+    /// The type of the stored distances.
+    type Distance: CacheElement;
+    /// The container that is parsed when the cache has to be (re)built.
+    type Source: ParseFromTSPLib<Distance = Self::Distance>;
+    /// The layout of the cached data.
+    const LAYOUT: MatrixLayout;
+
+    /// Returns the raw data of the parsed container, which is written to the cache.
+    fn source_data(source: &Self::Source) -> &[Self::Distance];
+
+    /// Creates the container from a mapped cache file with a valid header.
+    ///
+    /// Panics if the mapped file is too short for a matrix of the given dimension.
+    fn from_mapped(map: Mmap, dimension: usize) -> Self;
+}
+
+/// Matrix data read directly from a memory mapped cache file.
+#[derive(Debug)]
+struct MappedData<D> {
+    map: Mmap,
+    len: usize,
+    _distance: PhantomData<D>,
+}
+
+impl<D: CacheElement> MappedData<D> {
+    fn new(map: Mmap, len: usize) -> Self {
+        assert!(map.len() >= HEADER_SIZE + len * size_of::<D>());
+        // Mappings are page aligned, and the header size is a multiple of every element alignment
+        assert!(map.as_ptr().cast::<D>().is_aligned());
+        Self {
+            map,
+            len,
+            _distance: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    fn data(&self) -> &[D] {
+        // SAFETY: The length and alignment are checked in `new`, `CacheElement` guarantees that
+        // any bytes are a valid `D`, and the mapping lives as long as `self`.
+        #[expect(unsafe_code, reason = "Zero copy access to the mapped data")]
+        unsafe {
+            std::slice::from_raw_parts(self.map.as_ptr().add(HEADER_SIZE).cast::<D>(), self.len)
+        }
+    }
+}
+
+/// A full distance matrix backed by a memory mapped cache file, the counterpart of [`Matrix`].
+#[derive(Debug)]
+pub struct MappedMatrix<D> {
+    data: MappedData<D>,
+    dimension: usize,
+}
+
+impl<D: CacheElement> MappedMatrix<D> {
+    /// Returns the dimension of the matrix.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the mapped data, in the layout of [`Matrix::data`].
+    pub fn data(&self) -> &[D] {
+        self.data.data()
+    }
+
+    /// Access the data at (from, to).
+    #[inline(always)]
+    pub fn get_data(&self, from: Node, to: Node) -> D {
+        self.data.data()[from.0 * self.dimension + to.0]
+    }
+
+    /// Copies the data into an owned matrix.
+    pub fn to_matrix(&self) -> Matrix<D> {
+        Matrix::new(self.data().to_vec(), self.dimension)
+    }
+}
+
+impl<D: CacheElement> DistanceOracle for MappedMatrix<D> {
+    type Value = D;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> D {
+        self.get_data(from, to)
+    }
+
+    #[inline(always)]
+    fn row(&self, from: Node) -> Option<&[D]> {
+        let start = from.0 * self.dimension;
+        Some(&self.data.data()[start..start + self.dimension])
+    }
+}
+
+impl<D: CacheElement> CachedContainer for MappedMatrix<D> {
+    type Distance = D;
+    type Source = Matrix<D>;
+
+    const LAYOUT: MatrixLayout = MatrixLayout::Full;
+
+    fn source_data(source: &Matrix<D>) -> &[D] {
+        source.data()
+    }
+
+    fn from_mapped(map: Mmap, dimension: usize) -> Self {
+        Self {
+            data: MappedData::new(map, matrix_len::<Self>(dimension)),
+            dimension,
+        }
+    }
+}
+
+/// A symmetric distance matrix backed by a memory mapped cache file, the counterpart of
+/// [`MatrixSym`].
+#[derive(Debug)]
+pub struct MappedMatrixSym<D> {
+    data: MappedData<D>,
+    dimension: usize,
+}
+
+impl<D: CacheElement> MappedMatrixSym<D> {
+    /// Returns the dimension of the matrix.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Returns the mapped data, in the layout of [`MatrixSym::data`].
+    pub fn data(&self) -> &[D] {
+        self.data.data()
+    }
+
+    /// Access the data at (from, to).
+    #[inline(always)]
+    pub fn get_data(&self, from: Node, to: Node) -> D {
+        let index = tsp_core::instance::matrix::get_lower_triangle_matrix_entry(from.0, to.0);
+        self.data.data()[index]
+    }
+
+    /// Copies the data into an owned matrix.
+    pub fn to_matrix_sym(&self) -> MatrixSym<D> {
+        MatrixSym::new(self.data().to_vec(), self.dimension)
+    }
+}
+
+impl<D: CacheElement> DistanceOracle for MappedMatrixSym<D> {
+    type Value = D;
+
+    #[inline(always)]
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    #[inline(always)]
+    fn get(&self, from: Node, to: Node) -> D {
+        self.get_data(from, to)
+    }
+}
+
+impl<D: CacheElement> CachedContainer for MappedMatrixSym<D> {
+    type Distance = D;
+    type Source = MatrixSym<D>;
+
+    const LAYOUT: MatrixLayout = MatrixLayout::Symmetric;
+
+    fn source_data(source: &MatrixSym<D>) -> &[D] {
+        source.data()
+    }
+
+    fn from_mapped(map: Mmap, dimension: usize) -> Self {
+        Self {
+            data: MappedData::new(map, matrix_len::<Self>(dimension)),
+            dimension,
+        }
+    }
+}
+
+/// Computes the fingerprint of an instance file (64 bit FNV-1a of its content).
+pub fn fingerprint(content: &[u8]) -> u64 {
+    content.iter().fold(0xCBF2_9CE4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+/// Returns the path of the cache file for the instance and container, which is next to the
+/// instance file, e.g. `brd14051.tsp.sym-i32.cache`.
+pub fn cache_path<C: CachedContainer>(instance_path: impl AsRef<Path>) -> PathBuf {
+    let layout = match C::LAYOUT {
+        MatrixLayout::Full => "full",
+        MatrixLayout::Symmetric => "sym",
+    };
+    let mut file_name = instance_path
+        .as_ref()
+        .file_name()
+        .unwrap_or_default()
+        .to_owned();
+    file_name.push(format!(".{layout}-{}.cache", C::Distance::NAME));
+    instance_path.as_ref().with_file_name(file_name)
+}
+
+/// Writes the distances and display data of an instance to a cache file.
+///
+/// The file is written to a temporary file first and renamed afterwards, so concurrent readers
+/// never see a partially written cache.
+pub fn write_cache<C: CachedContainer>(
+    cache_path: impl AsRef<Path>,
+    distances: &C::Source,
+    dimension: usize,
+    fingerprint: u64,
+    display_data: Option<&[Point2D]>,
+) -> Result<(), ParserError> {
+    let cache_path = cache_path.as_ref();
+    // The process id separates processes and the counter separates threads, so concurrent writers
+    // never share a temporary file
+    static TEMPORARY_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let mut temporary_path = cache_path.as_os_str().to_owned();
+    temporary_path.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = write_temporary_file::<C>(
+        temporary_path.as_ref(),
+        distances,
+        dimension,
+        fingerprint,
+        display_data,
+    )
+    .and_then(|()| std::fs::rename(&temporary_path, cache_path));
+    if result.is_err() {
+        // The temporary file may not exist, in which case there is nothing to clean up
+        let _ = std::fs::remove_file(&temporary_path);
+    }
+    Ok(result?)
+}
+
+fn write_temporary_file<C: CachedContainer>(
+    temporary_path: &Path,
+    distances: &C::Source,
+    dimension: usize,
+    fingerprint: u64,
+    display_data: Option<&[Point2D]>,
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(temporary_path)?);
+    encode_cache::<C>(&mut writer, distances, dimension, fingerprint, display_data)?;
+    writer
+        .into_inner()
+        .map_err(std::io::IntoInnerError::into_error)?
+        .sync_all()
+}
+
+/// Encodes the distances in the cache format into an anonymous mapping, which is used in place of
+/// the cache file if it cannot be written.
+fn map_anonymous<C: CachedContainer>(
+    distances: &C::Source,
+    dimension: usize,
+    display_data: Option<&[Point2D]>,
+) -> Result<Mmap, ParserError> {
+    let data_size = size_of_val(C::source_data(distances));
+    let size =
+        HEADER_SIZE + data_size + padding(data_size) + display_data.unwrap_or_default().len() * 16;
+    let mut map = MmapMut::map_anon(size)?;
+    encode_cache::<C>(&mut &mut map[..], distances, dimension, 0, display_data)?;
+    Ok(map.make_read_only()?)
+}
+
+/// Writes the header, the distances and the display data in the cache format.
+fn encode_cache<C: CachedContainer>(
+    writer: &mut impl Write,
+    distances: &C::Source,
+    dimension: usize,
+    fingerprint: u64,
+    display_data: Option<&[Point2D]>,
+) -> std::io::Result<()> {
+    let data = C::source_data(distances);
+    assert_eq!(data.len(), matrix_len::<C>(dimension));
+    let display_data = display_data.unwrap_or_default();
+
+    let mut header = [0u8; HEADER_SIZE];
+    header[0..8].copy_from_slice(&MAGIC);
+    header[8..12].copy_from_slice(&CACHE_FORMAT_VERSION.to_le_bytes());
+    header[12] = C::LAYOUT as u8;
+    header[13] = C::Distance::TYPE_TAG;
+    header[14] = size_of::<C::Distance>() as u8;
+    header[15] = NATIVE_BYTE_ORDER;
+    header[16..24].copy_from_slice(&(dimension as u64).to_le_bytes());
+    header[24..32].copy_from_slice(&fingerprint.to_le_bytes());
+    header[32..40].copy_from_slice(&(display_data.len() as u64).to_le_bytes());
+
+    // SAFETY: `CacheElement` guarantees that the elements are plain numbers without padding.
+    #[expect(unsafe_code, reason = "Writing the raw matrix data")]
+    let bytes =
+        unsafe { std::slice::from_raw_parts(data.as_ptr().cast::<u8>(), size_of_val(data)) };
+
+    writer.write_all(&header)?;
+    writer.write_all(bytes)?;
+    writer.write_all(&vec![0; padding(bytes.len())])?;
+    for point in display_data {
+        writer.write_all(&point.x.to_ne_bytes())?;
+        writer.write_all(&point.y.to_ne_bytes())?;
+    }
+    Ok(())
+}
+
+/// Loads a cache file written by [`write_cache`], checking that it matches the fingerprint of the
+/// instance file.
+///
+/// The distances are mapped without copying them. Returns the container and the display data.
+pub fn load_cache<C: CachedContainer>(
+    cache_path: impl AsRef<Path>,
+    fingerprint: u64,
+) -> Result<(C, Option<Vec<Point2D>>), ParserError> {
+    let file = File::open(cache_path)?;
+    // SAFETY: Cache files are only replaced by renaming, never modified in place.
+    #[expect(unsafe_code, reason = "Memory mapping a file is inherently unsafe")]
+    let map = unsafe { Mmap::map(&file)? };
+
+    let header = map.get(..HEADER_SIZE).ok_or(CacheError::Truncated {
+        expected: HEADER_SIZE,
+        found: map.len(),
+    })?;
+    let read_u64 = |range: std::ops::Range<usize>| {
+        u64::from_le_bytes(header[range].try_into().expect("Ranges have length 8"))
+    };
+
+    if header[0..8] != MAGIC {
+        return Err(CacheError::InvalidMagic.into());
+    }
+    let version = u32::from_le_bytes(header[8..12].try_into().expect("Range has length 4"));
+    if version != CACHE_FORMAT_VERSION {
+        return Err(CacheError::UnsupportedVersion(version).into());
+    }
+    let layout = MatrixLayout::from_tag(header[12]);
+    if layout != Some(C::LAYOUT)
+        || header[13] != C::Distance::TYPE_TAG
+        || header[14] as usize != size_of::<C::Distance>()
+    {
+        return Err(CacheError::TypeMismatch {
+            expected_layout: C::LAYOUT,
+            expected_type: C::Distance::TYPE_TAG,
+            found_layout: layout,
+            found_type: header[13],
+        }
+        .into());
+    }
+    if header[15] != NATIVE_BYTE_ORDER {
+        return Err(CacheError::ByteOrderMismatch.into());
+    }
+    let found_fingerprint = read_u64(24..32);
+    if found_fingerprint != fingerprint {
+        return Err(CacheError::FingerprintMismatch {
+            expected: fingerprint,
+            found: found_fingerprint,
+        }
+        .into());
+    }
+
+    let (raw_dimension, raw_display_points) = (read_u64(16..24), read_u64(32..40));
+    let size_overflow = CacheError::SizeOverflow {
+        dimension: raw_dimension,
+        display_points: raw_display_points,
+    };
+    let dimension = usize::try_from(raw_dimension).map_err(|_| size_overflow.clone())?;
+    let (display_start, expected_size) = C::LAYOUT
+        .len(dimension)
+        .and_then(|len| len.checked_mul(size_of::<C::Distance>()))
+        .and_then(|data_size| data_size.checked_next_multiple_of(8))
+        .and_then(|data_size| data_size.checked_add(HEADER_SIZE))
+        .and_then(|display_start| {
+            usize::try_from(raw_display_points)
+                .ok()
+                .and_then(|points| points.checked_mul(16))
+                .and_then(|display_size| display_start.checked_add(display_size))
+                .map(|expected_size| (display_start, expected_size))
+        })
+        .ok_or(size_overflow)?;
+    if map.len() < expected_size {
+        return Err(CacheError::Truncated {
+            expected: expected_size,
+            found: map.len(),
+        }
+        .into());
+    }
+
+    let display_data = (raw_display_points > 0).then(|| {
+        map[display_start..expected_size]
+            .as_chunks::<16>()
+            .0
+            .iter()
+            .map(|point| {
+                let (x, y) = point.split_at(8);
+                Point2D {
+                    x: f64::from_ne_bytes(x.try_into().expect("Chunk halves have length 8")),
+                    y: f64::from_ne_bytes(y.try_into().expect("Chunk halves have length 8")),
+                }
+            })
+            .collect()
+    });
+
+    Ok((C::from_mapped(map, dimension), display_data))
+}
+
+/// Parses a TSPLIB instance, reusing the cached distances if the instance file did not change.
+///
+/// The cache file is stored next to the instance file (see [`cache_path`]) and (re)built if it is
+/// missing, stale or unreadable. Only the specification part of the instance file is parsed if the
+/// cache can be used.
+pub fn parse_tsp_instance_cached<C: CachedContainer>(
+    instance_path: impl AsRef<Path>,
+) -> Result<TSPSymInstance<C>, ParserError> {
+    let instance_path = instance_path.as_ref();
+    let file_content = FileContent::new(instance_path)?;
+    let fingerprint = fingerprint(&file_content);
+    let cache_path = cache_path::<C>(instance_path);
+
+    let (distances, display_data) = match load_cache::<C>(&cache_path, fingerprint) {
+        Ok(cached) => {
+            debug!("Using cached distances from {}", cache_path.display());
+            cached
+        }
+        Err(error) => {
+            info!("Building distance cache {} ({error})", cache_path.display());
+            let instance = parse_tsp_instance_from_content::<C::Source>(&file_content)?;
+            let dimension = instance.metadata().dimension;
+            match write_cache::<C>(
+                &cache_path,
+                instance.distances(),
+                dimension,
+                fingerprint,
+                instance.display_data(),
+            ) {
+                Ok(()) => load_cache::<C>(&cache_path, fingerprint)?,
+                Err(error) => {
+                    // The cache is only an optimization, so the parsed instance is used without it
+                    warn!(
+                        "Could not write distance cache {} ({error})",
+                        cache_path.display()
+                    );
+                    let map = map_anonymous::<C>(
+                        instance.distances(),
+                        dimension,
+                        instance.display_data(),
+                    )?;
+                    (
+                        C::from_mapped(map, dimension),
+                        instance.display_data().map(<[Point2D]>::to_vec),
+                    )
+                }
+            }
+        }
+    };
+
+    let (metadata, _) = parse_metadata(&file_content, &mut 0)?;
+
+    let instance = TSPSymInstance::new(distances, metadata);
+    Ok(match display_data {
+        Some(display_data) => instance.with_display_data(display_data),
+        None => instance,
+    })
+}
+
+/// Returns the number of entries of a matrix of a dimension that is known to fit into memory.
+fn matrix_len<C: CachedContainer>(dimension: usize) -> usize {
+    C::LAYOUT
+        .len(dimension)
+        .expect("The matrix size was checked when it was read or allocated")
+}
+
+/// Returns the number of zero bytes after the matrix data, such that the display data is aligned.
+fn padding(data_size: usize) -> usize {
+    data_size.next_multiple_of(8) - data_size
+}
@@ -0,0 +1,89 @@
+//! Parsing of explicitly given edge weights, i.e. the `EDGE_WEIGHT_SECTION` of instances with
+//! `EDGE_WEIGHT_TYPE: EXPLICIT`.
+//!
+//! All formats are converted into the row-major lower-triangular layout (including the diagonal)
+//! that is also used by [`MatrixSym`][tsp_core::instance::matrix::MatrixSym].
+
+use tsp_core::{
+    instance::{
+        InstanceMetadata, distance::DistanceValue, matrix::get_lower_triangle_matrix_entry,
+    },
+    tsp_lib_spec::EdgeWeightFormat,
+};
+
+use crate::{
+    FileContent,
+    data_section::{DataSectionParseError, next_line, parse_number, split_line},
+    metadata::decode_text_field,
+};
+
+/// Parses the `EDGE_WEIGHT_SECTION` into a row-major lower-triangular matrix (including the
+/// diagonal).
+///
+/// The section may spread its entries arbitrarily over lines. Parsing stops as soon as the number
+/// of entries required by the edge weight format has been read.
+pub(crate) fn parse_edge_weight_section<D: DistanceValue>(
+    file_content: &FileContent,
+    index_in_map: &mut usize,
+    metadata: &InstanceMetadata,
+) -> Result<Vec<D>, DataSectionParseError> {
+    let dimension = metadata.dimension;
+    let edge_weight_format = metadata
+        .edge_weight_format
+        .clone()
+        .ok_or(DataSectionParseError::MissingEdgeWeightFormat)?;
+
+    // Iterator over the (row, column) entries in the order in which they appear in the file
+    let entries: Box<dyn Iterator<Item = (usize, usize)>> = match edge_weight_format {
+        EdgeWeightFormat::FULL_MATRIX => Box::new(
+            (0..dimension).flat_map(move |row| (0..dimension).map(move |column| (row, column))),
+        ),
+        EdgeWeightFormat::LOWER_DIAG_ROW | EdgeWeightFormat::UPPER_DIAG_COL => {
+            Box::new((0..dimension).flat_map(|row| (0..=row).map(move |column| (row, column))))
+        }
+        EdgeWeightFormat::LOWER_ROW | EdgeWeightFormat::UPPER_COL => {
+            Box::new((0..dimension).flat_map(|row| (0..row).map(move |column| (row, column))))
+        }
+        EdgeWeightFormat::UPPER_DIAG_ROW | EdgeWeightFormat::LOWER_DIAG_COL => Box::new(
+            (0..dimension).flat_map(move |row| (row..dimension).map(move |column| (row, column))),
+        ),
+        EdgeWeightFormat::UPPER_ROW | EdgeWeightFormat::LOWER_COL => Box::new(
+            (0..dimension)
+                .flat_map(move |row| ((row + 1)..dimension).map(move |column| (row, column))),
+        ),
+        EdgeWeightFormat::FUNCTION => {
+            return Err(DataSectionParseError::UnsupportedEdgeWeightFormat(
+                edge_weight_format,
+            ));
+        }
+    };
+    let mut entries = entries.peekable();
+    let is_full_matrix = matches!(edge_weight_format, EdgeWeightFormat::FULL_MATRIX);
+
+    let mut distance_data = vec![D::ZERO; dimension * (dimension + 1) / 2];
+
+    while entries.peek().is_some() {
+        let line = next_line(file_content, index_in_map)
+            .ok_or(DataSectionParseError::UnexpectedEndOfFile)?;
+
+        for part in split_line(line) {
+            let Some((row, column)) = entries.next() else {
+                return Err(DataSectionParseError::InvalidEdgeWeight(decode_text_field(
+                    line,
+                )));
+            };
+            let distance = parse_number::<D>(part)
+                .ok_or_else(|| DataSectionParseError::InvalidEdgeWeight(decode_text_field(part)))?;
+
+            // Full matrices contain every entry twice, the entry below the diagonal has to match
+            // the one above it, which has already been read
+            let index = get_lower_triangle_matrix_entry(row, column);
+            if is_full_matrix && row > column && distance_data[index] != distance {
+                return Err(DataSectionParseError::AsymmetricEdgeWeight(row, column));
+            }
+            distance_data[index] = distance;
+        }
+    }
+
+    Ok(distance_data)
+}
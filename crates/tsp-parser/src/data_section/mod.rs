@@ -6,108 +6,161 @@
 /// Distance values are required to be non-negative integers. Computations are expected to be
 /// carried out in double precision arithmetic, i.e. `f64` in Rust.
 use memchr::memchr;
-use memmap2::Mmap;
+use thiserror::Error;
+pub use tsp_core::instance::point::{GeoPoint, Point2D, Point3D};
 use tsp_core::{
     instance::InstanceMetadata,
-    tsp_lib_spec::{EdgeWeightType, TSPDataKeyword},
+    metric::{Metric2D, Metric3D},
+    tsp_lib_spec::{
+        DisplayDataType, EdgeWeightFormat, EdgeWeightType, NodeCoordType, TSPDataKeyword,
+    },
 };
 
-pub(crate) mod distance_function;
+mod explicit;
 
 use crate::{
-    FileContent,
-    data_section::distance_function::{euclidean_distance_2d, geographical_distance},
+    FileContent, ParserError,
     distance_container::ParseFromTSPLib,
+    metadata::{decode_text_field, parse_data_keyword},
 };
 
-/// A point in 2D space.
-#[derive(Debug, Clone, Copy)]
-pub struct Point2D {
-    pub x: f64,
-    pub y: f64,
+#[derive(Error, Debug)]
+pub enum DataSectionParseError {
+    #[error("Unexpected end of file in data section")]
+    UnexpectedEndOfFile,
+    #[error("Missing coordinate {0} in this line: {1}")]
+    MissingCoordinate(usize, String),
+    #[error("Invalid coordinate value: {0}")]
+    InvalidCoordinate(String),
+    #[error("Invalid edge weight value: {0}")]
+    InvalidEdgeWeight(String),
+    #[error(
+        "Edge weight ({0}, {1}) differs from ({1}, {0}), asymmetric instances are not supported"
+    )]
+    AsymmetricEdgeWeight(usize, usize),
+    #[error("EDGE_WEIGHT_FORMAT is required for explicit edge weights")]
+    MissingEdgeWeightFormat,
+    #[error("EDGE_WEIGHT_FORMAT {0:?} is not supported for explicit edge weights")]
+    UnsupportedEdgeWeightFormat(EdgeWeightFormat),
+    #[error("Missing data section: {0}")]
+    MissingSection(&'static str),
+    #[error("Expected {expected} entries in {section}, found {found}")]
+    DimensionMismatch {
+        section: &'static str,
+        expected: usize,
+        found: usize,
+    },
 }
 
-/// A point in 3D space.
-#[derive(Debug, Clone, Copy)]
-pub struct Point3D {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
+/// Parses all data sections of a TSP instance file, starting with the section introduced by
+/// `data_keyword`.
+///
+/// Returns the distance container and the 2D display coordinates of the nodes, if the instance
+/// provides them. Following TSPLIB95, the display coordinates are the node coordinates in case of
+/// `DISPLAY_DATA_TYPE: COORD_DISPLAY`, which is also the default for instances with 2D node
+/// coordinates.
+pub fn parse_data_sections<DistanceContainer: ParseFromTSPLib>(
+    file_content: &FileContent,
+    index_in_map: &mut usize,
+    data_keyword: TSPDataKeyword,
+    metadata: &InstanceMetadata,
+) -> Result<(DistanceContainer, Option<Vec<Point2D>>), ParserError> {
+    let mut distance_container = None;
+    let mut node_coords = None;
+    let mut display_data = None;
+
+    let mut next_data_keyword = Some(data_keyword);
+    while let Some(data_keyword) = next_data_keyword {
+        match data_keyword {
+            TSPDataKeyword::NODE_COORD_SECTION => {
+                let (distances, coords) =
+                    parse_node_coord_section(file_content, index_in_map, metadata)?;
+                distance_container = distances;
+                node_coords = coords;
+            }
+            TSPDataKeyword::EDGE_WEIGHT_SECTION => {
+                let distance_data =
+                    explicit::parse_edge_weight_section(file_content, index_in_map, metadata)?;
+                distance_container = Some(DistanceContainer::from_edge_weight_section(
+                    distance_data,
+                    metadata,
+                ));
+            }
+            TSPDataKeyword::DISPLAY_DATA_SECTION => {
+                let points = parse_2d_node_coord_section(file_content, index_in_map, metadata)?;
+                check_dimension("DISPLAY_DATA_SECTION", &points, metadata)?;
+                display_data = Some(points);
+            }
+            _ => skip_section(file_content, index_in_map),
+        }
+
+        next_data_keyword = parse_next_data_keyword(file_content, index_in_map)?;
+    }
 
-/// A point in geographical latitude and longitude radiance coordinates.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct GeoPoint {
-    pub latitude: f64,
-    pub longitude: f64,
+    let distance_container = distance_container.ok_or(DataSectionParseError::MissingSection(
+        "NODE_COORD_SECTION or EDGE_WEIGHT_SECTION",
+    ))?;
+
+    let display_data = match metadata.display_data_type {
+        Some(DisplayDataType::COORD_DISPLAY) | None => node_coords,
+        Some(DisplayDataType::TWOD_DISPLAY) => Some(display_data.ok_or(
+            DataSectionParseError::MissingSection("DISPLAY_DATA_SECTION"),
+        )?),
+        Some(DisplayDataType::NO_DISPLAY) => None,
+    };
+
+    Ok((distance_container, display_data))
 }
 
-pub fn parse_data_sections<DistanceContainer: ParseFromTSPLib>(
+/// Parses the node coordinate section and computes the distances according to the edge weight
+/// type.
+///
+/// Returns the distance container (if the distances are given by the node coordinates) and the
+/// node coordinates if they are two-dimensional.
+fn parse_node_coord_section<DistanceContainer: ParseFromTSPLib>(
     file_content: &FileContent,
     index_in_map: &mut usize,
-    data_keyword: TSPDataKeyword,
     metadata: &InstanceMetadata,
-) -> DistanceContainer {
+) -> Result<(Option<DistanceContainer>, Option<Vec<Point2D>>), DataSectionParseError> {
+    if let Some(metric) = Metric2D::from_metadata(metadata) {
+        let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata)?;
+        check_dimension("NODE_COORD_SECTION", &node_data, metadata)?;
+        return Ok((
+            Some(DistanceContainer::from_points_2d(&node_data, metric)),
+            Some(node_data),
+        ));
+    }
+
+    if let Some(metric) = Metric3D::from_metadata(metadata) {
+        let node_data = parse_3d_node_coord_section(file_content, index_in_map, metadata)?;
+        check_dimension("NODE_COORD_SECTION", &node_data, metadata)?;
+        return Ok((
+            Some(DistanceContainer::from_points_3d(&node_data, metric)),
+            None,
+        ));
+    }
+
     match metadata.edge_weight_type {
-        // The distance function is not chosen via a match statement here because the compiler
-        // does not seem to inline the distance function properly in that case
-        // (leading to a big performance hit)
-        EdgeWeightType::EUC_2D => {
-            let distance_function = euclidean_distance_2d;
-            let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::MAX_2D => {
-            let distance_function = distance_function::max_distance_2d;
-            let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::MAN_2D => {
-            let distance_function = distance_function::manhattan_distance_2d;
-            let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::CEIL_2D => {
-            let distance_function = distance_function::ceil_distance_2d;
-            let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::ATT => {
-            let distance_function = distance_function::att_distance_2d;
-            let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::GEO => {
-            let distance_function = geographical_distance;
-            // TODO(perf): Possibly parallelize the conversion to geo coordinates
-            let node_data = parse_2d_node_coord_section(file_content, index_in_map, metadata)
-                .into_iter()
-                .map(|point| distance_function::convert_to_geo_coordinates(&point))
-                .collect::<Vec<GeoPoint>>();
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::EUC_3D => {
-            let distance_function = distance_function::euclidean_distance_3d;
-            let node_data = parse_3d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::MAX_3D => {
-            let distance_function = distance_function::max_distance_3d;
-            let node_data = parse_3d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
-        EdgeWeightType::MAN_3D => {
-            let distance_function = distance_function::manhattan_distance_3d;
-            let node_data = parse_3d_node_coord_section(file_content, index_in_map, metadata);
-            DistanceContainer::from_node_coord_section(&node_data, metadata, distance_function)
-        }
         EdgeWeightType::EXPLICIT => {
-            // TODO: Implement explicit distance matrix parsing
-            todo!("Explicit distance matrix parsing is not supported yet");
+            // The distances are given in the EDGE_WEIGHT_SECTION, the node coordinates can only
+            // be used for displaying the instance.
+            let node_data = match metadata.node_coord_type {
+                NodeCoordType::TWOD_COORDS => {
+                    let node_data =
+                        parse_2d_node_coord_section(file_content, index_in_map, metadata)?;
+                    check_dimension("NODE_COORD_SECTION", &node_data, metadata)?;
+                    Some(node_data)
+                }
+                _ => {
+                    skip_section(file_content, index_in_map);
+                    None
+                }
+            };
+            Ok((None, node_data))
         }
         _ => unimplemented!(
-            "Node coordinate type {:?} is not yet implemented",
-            metadata.node_coord_type
+            "Edge weight type {:?} is not yet implemented",
+            metadata.edge_weight_type
         ),
     }
 }
@@ -116,156 +169,179 @@ fn parse_2d_node_coord_section(
     file_content: &FileContent,
     index_in_map: &mut usize,
     metadata: &InstanceMetadata,
-) -> Vec<Point2D> {
+) -> Result<Vec<Point2D>, DataSectionParseError> {
     let mut point_data: Vec<Point2D> = Vec::with_capacity(metadata.dimension);
 
     // Read a line to test if the point data is floating point or integer
-    let is_float_data = is_float_data(file_content, index_in_map);
+    let is_float_data = is_float_data(file_content, *index_in_map)?;
 
-    while let Some(index_newline) = memchr(b'\n', &file_content[*index_in_map..]) {
-        let line = &file_content[*index_in_map..*index_in_map + index_newline];
-        // SAFETY: The TSP instance file is expected to be valid UTF-8
-        let line_str = unsafe { std::str::from_utf8_unchecked(line) };
-        let line_str = line_str.trim();
+    while let Some(line) = next_line_in_section(file_content, index_in_map) {
+        let [x, y] = parse_line_to_coordinates(line, is_float_data)?;
 
-        // Move the index to the start of the next line (+1 for the newline character)
-        *index_in_map += index_newline + 1;
-
-        // Check if end of file is reached
-        if line_str == "EOF" || line_str.is_empty() {
-            break;
-        }
-
-        let point = parse_line_to_2d_point(line_str, is_float_data);
-
-        point_data.push(point);
+        point_data.push(Point2D { x, y });
     }
 
-    point_data
+    Ok(point_data)
 }
 
 fn parse_3d_node_coord_section(
     file_content: &FileContent,
     index_in_map: &mut usize,
     metadata: &InstanceMetadata,
-) -> Vec<Point3D> {
+) -> Result<Vec<Point3D>, DataSectionParseError> {
     let mut point_data: Vec<Point3D> = Vec::with_capacity(metadata.dimension);
 
     // Read a line to test if the point data is floating point or integer
-    let is_float_data = is_float_data(file_content, index_in_map);
+    let is_float_data = is_float_data(file_content, *index_in_map)?;
 
-    while let Some(index_newline) = memchr(b'\n', &file_content[*index_in_map..]) {
-        let line = &file_content[*index_in_map..*index_in_map + index_newline];
-        // SAFETY: The TSP instance file is expected to be valid UTF-8
-        let line_str = unsafe { std::str::from_utf8_unchecked(line) };
+    while let Some(line) = next_line_in_section(file_content, index_in_map) {
+        let [x, y, z] = parse_line_to_coordinates(line, is_float_data)?;
 
-        // Move the index to the start of the next line (+1 for the newline character)
-        *index_in_map += index_newline + 1;
+        point_data.push(Point3D { x, y, z });
+    }
 
-        // Check if end of file is reached
-        if line_str == "EOF" {
-            break;
-        }
+    Ok(point_data)
+}
 
-        let point = parse_line_to_3d_point(line_str, is_float_data);
+/// Returns the next line (without the newline character) and moves the index to the start of the
+/// line after it.
+///
+/// Returns `None` if the end of the file has been reached.
+pub(crate) fn next_line<'a>(
+    file_content: &'a FileContent,
+    index_in_map: &mut usize,
+) -> Option<&'a [u8]> {
+    let rest = &file_content[*index_in_map..];
+    if rest.is_empty() {
+        return None;
+    }
+    let line_length = memchr(b'\n', rest).unwrap_or(rest.len());
+
+    // Move the index to the start of the next line (+1 for the newline character)
+    *index_in_map = (*index_in_map + line_length + 1).min(file_content.len());
 
-        point_data.push(point);
+    Some(rest[..line_length].trim_ascii())
+}
+
+/// Returns the next non-empty line of the current data section.
+///
+/// Returns `None` if the end of the file or the start of another section (or the `EOF` keyword)
+/// has been reached. In the latter case, the index is not moved, so that the keyword can be parsed
+/// by [`parse_next_data_keyword`].
+fn next_line_in_section<'a>(
+    file_content: &'a FileContent,
+    index_in_map: &mut usize,
+) -> Option<&'a [u8]> {
+    loop {
+        let index_line_start = *index_in_map;
+        let line = next_line(file_content, index_in_map)?;
+
+        if line.first().is_some_and(u8::is_ascii_alphabetic) {
+            // Reached the next keyword
+            *index_in_map = index_line_start;
+            return None;
+        }
+        if !line.is_empty() {
+            return Some(line);
+        }
     }
+}
 
-    point_data
+/// Skips the remaining lines of the current data section.
+fn skip_section(file_content: &FileContent, index_in_map: &mut usize) {
+    while next_line_in_section(file_content, index_in_map).is_some() {}
 }
 
-#[inline(always)]
-fn parse_line_to_2d_point(line_str: &str, is_float_data: bool) -> Point2D {
-    // We assume the input to be split by ascii whitespace
-    let mut parts = line_str.split_ascii_whitespace();
-    let _node_index = parts.next();
+/// Parses the keyword of the next data section.
+///
+/// Returns `None` if the `EOF` keyword or the end of the file has been reached.
+fn parse_next_data_keyword(
+    file_content: &FileContent,
+    index_in_map: &mut usize,
+) -> Result<Option<TSPDataKeyword>, ParserError> {
+    while let Some(line) = next_line(file_content, index_in_map) {
+        match line {
+            b"" => continue,
+            b"EOF" => return Ok(None),
+            keyword => return parse_data_keyword(keyword).map(Some),
+        }
+    }
+    Ok(None)
+}
 
-    let x_str = parts
-        .next()
-        .expect(format!("Missing x coordinate: {}", line_str).as_str());
-    let y_str = parts
-        .next()
-        .expect(format!("Missing y coordinate: {}", line_str).as_str());
-    let (x, y) = if is_float_data {
-        (
-            x_str
-                .parse::<f64>()
-                .expect("x coordinate should always be a valid f64 floating point number"),
-            y_str
-                .parse::<f64>()
-                .expect("y coordinate should always be a valid f64 floating point number"),
-        )
+/// Checks that a data section contains exactly one entry per node.
+fn check_dimension<T>(
+    section: &'static str,
+    data: &[T],
+    metadata: &InstanceMetadata,
+) -> Result<(), DataSectionParseError> {
+    if data.len() == metadata.dimension {
+        Ok(())
     } else {
-        (
-            x_str
-                .parse::<i32>()
-                .expect("x coordinate should be a valid i32 integer by sampling first line")
-                as f64,
-            y_str
-                .parse::<i32>()
-                .expect("y coordinate should be a valid i32 integer by sampling first line")
-                as f64,
-        )
-    };
+        Err(DataSectionParseError::DimensionMismatch {
+            section,
+            expected: metadata.dimension,
+            found: data.len(),
+        })
+    }
+}
 
-    Point2D { x, y }
+/// Splits a line into its ascii whitespace separated parts.
+#[inline(always)]
+pub(crate) fn split_line(line: &[u8]) -> impl Iterator<Item = &[u8]> {
+    line.split(u8::is_ascii_whitespace)
+        .filter(|part| !part.is_empty())
 }
 
+/// Parses the `N` coordinates following the node index in a line of a node coordinate section.
 #[inline(always)]
-fn parse_line_to_3d_point(line_str: &str, is_float_data: bool) -> Point3D {
+fn parse_line_to_coordinates<const N: usize>(
+    line: &[u8],
+    is_float_data: bool,
+) -> Result<[f64; N], DataSectionParseError> {
     // We assume the input to be split by ascii whitespace
-    let mut parts = line_str.split_ascii_whitespace();
+    let mut parts = split_line(line);
     let _node_index = parts.next();
 
-    let x_str = parts.next().expect("Missing x coordinate");
-    let y_str = parts.next().expect("Missing y coordinate");
-    let z_str = parts.next().expect("Missing z coordinate");
-    let (x, y, z) = if is_float_data {
-        (
-            x_str
-                .parse::<f64>()
-                .expect("x coordinate should always be a valid f64 floating point number"),
-            y_str
-                .parse::<f64>()
-                .expect("y coordinate should always be a valid f64 floating point number"),
-            z_str
-                .parse::<f64>()
-                .expect("z coordinate should always be a valid f64 floating point number"),
-        )
-    } else {
-        (
-            x_str
-                .parse::<i32>()
-                .expect("x coordinate should be a valid i32 integer by sampling first line")
-                as f64,
-            y_str
-                .parse::<i32>()
-                .expect("y coordinate should be a valid i32 integer by sampling first line")
-                as f64,
-            z_str
-                .parse::<i32>()
-                .expect("z coordinate should be a valid i32 integer by sampling first line")
-                as f64,
-        )
-    };
+    let mut coordinates = [0.0; N];
+    for (axis, coordinate) in coordinates.iter_mut().enumerate() {
+        let part = parts.next().ok_or_else(|| {
+            DataSectionParseError::MissingCoordinate(axis, decode_text_field(line))
+        })?;
+        *coordinate = if is_float_data {
+            parse_number::<f64>(part)
+        } else {
+            // Integer data has been determined by sampling the first line
+            parse_number::<i32>(part).map(f64::from)
+        }
+        .ok_or_else(|| DataSectionParseError::InvalidCoordinate(decode_text_field(part)))?;
+    }
 
-    Point3D { x, y, z }
+    Ok(coordinates)
 }
 
 #[inline(always)]
-fn is_float_data(file_content: &FileContent, index_in_map: &usize) -> bool {
-    let index_newline = memchr(b'\n', &file_content[*index_in_map..])
-        .expect("The data section should not be empty");
-    let line = &file_content[*index_in_map..*index_in_map + index_newline];
-
-    // SAFETY: The TSP instance file is expected to be valid UTF-8
-    let line_str = unsafe { std::str::from_utf8_unchecked(line) };
+fn is_float_data(
+    file_content: &FileContent,
+    index_in_map: usize,
+) -> Result<bool, DataSectionParseError> {
+    // Copy the index, such that the sampled line is not consumed
+    let mut index_in_map = index_in_map;
+    let line = next_line_in_section(file_content, &mut index_in_map)
+        .ok_or(DataSectionParseError::UnexpectedEndOfFile)?;
 
     // We assume the input to be split by ascii whitespace
-    let mut parts = line_str.split_ascii_whitespace();
+    let mut parts = split_line(line);
     let _node_index = parts.next();
 
-    let x_str = parts.next().expect("Missing x coordinate");
-    x_str.contains('.')
+    let x_str = parts
+        .next()
+        .ok_or_else(|| DataSectionParseError::MissingCoordinate(0, decode_text_field(line)))?;
+    Ok(x_str.contains(&b'.'))
+}
+
+/// Parses an ASCII number. Returns `None` if the bytes are not a valid number of type `T`.
+#[inline(always)]
+pub(crate) fn parse_number<T: std::str::FromStr>(value: &[u8]) -> Option<T> {
+    std::str::from_utf8(value).ok()?.parse().ok()
 }
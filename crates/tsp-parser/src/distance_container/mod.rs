@@ -1,18 +1,60 @@
-use tsp_core::instance::{InstanceMetadata, distance::Distance};
+use tsp_core::instance::{
+    InstanceMetadata,
+    distance::{Distance, DistanceValue},
+    matrix::{AnyCompactMatrix, AnyCompactMatrixSym, FromPoints, Matrix, MatrixSym},
+};
 
-mod matrix;
-mod matrix_sym;
+/// Distance containers that can be parsed from TSPLIB instances.
+///
+/// Distances given by node coordinates are computed via [`FromPoints`].
+pub trait ParseFromTSPLib: FromPoints {
+    /// The type of the stored distances.
+    type Distance: DistanceValue;
 
-pub trait ParseFromTSPLib {
-    fn from_node_coord_section<PointType: Sync + Send>(
-        node_data: &Vec<PointType>,
+    /// Creates the distance container from explicitly given edge weights.
+    ///
+    /// The edge weights are given as a row-major lower-triangular matrix including the diagonal,
+    /// i.e. in the layout of [`MatrixSym`].
+    fn from_edge_weight_section(
+        lower_triangle: Vec<Self::Distance>,
         metadata: &InstanceMetadata,
-        distance_function: impl Fn(&PointType, &PointType) -> Distance + Sync + Send + Copy,
     ) -> Self;
 }
 
-fn find_row_column_from_lower_triangle_index(index: usize) -> (usize, usize) {
-    let row = (-0.5 + ((0.25 + 2.0 * index as f64).sqrt())).floor() as usize;
-    let column = index - (row * (row + 1)) / 2;
-    (row, column)
+impl<D: DistanceValue> ParseFromTSPLib for Matrix<D> {
+    type Distance = D;
+
+    fn from_edge_weight_section(lower_triangle: Vec<D>, metadata: &InstanceMetadata) -> Self {
+        MatrixSym::new(lower_triangle, metadata.dimension).to_edge_data_matrix()
+    }
+}
+
+impl<D: DistanceValue> ParseFromTSPLib for MatrixSym<D> {
+    type Distance = D;
+
+    fn from_edge_weight_section(lower_triangle: Vec<D>, metadata: &InstanceMetadata) -> Self {
+        MatrixSym::new(lower_triangle, metadata.dimension)
+    }
+}
+
+impl ParseFromTSPLib for AnyCompactMatrix {
+    type Distance = Distance;
+
+    fn from_edge_weight_section(
+        lower_triangle: Vec<Distance>,
+        metadata: &InstanceMetadata,
+    ) -> Self {
+        AnyCompactMatrix::new(&Matrix::from_edge_weight_section(lower_triangle, metadata))
+    }
+}
+
+impl ParseFromTSPLib for AnyCompactMatrixSym {
+    type Distance = Distance;
+
+    fn from_edge_weight_section(
+        lower_triangle: Vec<Distance>,
+        metadata: &InstanceMetadata,
+    ) -> Self {
+        AnyCompactMatrixSym::new(&MatrixSym::new(lower_triangle, metadata.dimension))
+    }
 }
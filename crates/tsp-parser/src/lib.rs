@@ -5,14 +5,18 @@ use thiserror::Error;
 use tsp_core::instance::TSPSymInstance;
 
 use crate::{
-    data_section::parse_data_sections,
+    cache::CacheError,
+    data_section::{DataSectionParseError, parse_data_sections},
     distance_container::ParseFromTSPLib,
     metadata::{MetaDataParseError, parse_metadata},
+    solutions::SolutionsParseError,
 };
 
+pub mod cache;
 pub mod data_section;
 pub mod distance_container;
 pub mod metadata;
+pub mod solutions;
 
 #[derive(Error, Debug)]
 pub enum ParserError {
@@ -20,6 +24,12 @@ pub enum ParserError {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     MetaDataParsing(#[from] MetaDataParseError),
+    #[error(transparent)]
+    DataSectionParsing(#[from] DataSectionParseError),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Solutions(#[from] SolutionsParseError),
 }
 
 pub struct FileContent {
@@ -30,22 +40,34 @@ pub struct FileContent {
     data: Vec<u8>,
 }
 
+pub use cache::parse_tsp_instance_cached;
+
 pub fn parse_tsp_instance<DistanceContainer: ParseFromTSPLib>(
     instance_path: impl AsRef<Path>,
 ) -> Result<TSPSymInstance<DistanceContainer>, ParserError> {
     let file_content = FileContent::new(instance_path)?;
+    parse_tsp_instance_from_content(&file_content)
+}
+
+fn parse_tsp_instance_from_content<DistanceContainer: ParseFromTSPLib>(
+    file_content: &FileContent,
+) -> Result<TSPSymInstance<DistanceContainer>, ParserError> {
     let mut index_in_map = 0;
 
-    let (metadata, data_keyword) = parse_metadata(&file_content, &mut index_in_map)?;
+    let (metadata, data_keyword) = parse_metadata(file_content, &mut index_in_map)?;
 
-    let data = parse_data_sections::<DistanceContainer>(
-        &file_content,
+    let (data, display_data) = parse_data_sections::<DistanceContainer>(
+        file_content,
         &mut index_in_map,
         data_keyword,
         &metadata,
-    );
+    )?;
 
-    Ok(TSPSymInstance::new(data, metadata))
+    let instance = TSPSymInstance::new(data, metadata);
+    Ok(match display_data {
+        Some(display_data) => instance.with_display_data(display_data),
+        None => instance,
+    })
 }
 
 impl FileContent {
@@ -57,8 +79,9 @@ impl FileContent {
         }
         #[cfg(not(feature = "_miri"))]
         {
-            // Safety: This is the only point at which we access the file, so the file should
+            // SAFETY: This is the only point at which we access the file, so the file should
             // not be modified otherwise.
+            #[expect(unsafe_code, reason = "Memory mapping a file is inherently unsafe")]
             let mmap = unsafe { Mmap::map(&File::open(instance_path)?)? };
             mmap.advise(Advice::Sequential)?;
             Ok(FileContent { data: mmap })
@@ -29,6 +29,7 @@ pub struct InstanceMetadataBuilder {
     edge_data_format: Option<EdgeDataFormat>,
     node_coord_type: Option<NodeCoordType>,
     display_data_type: Option<DisplayDataType>,
+    grid_size: Option<f64>,
 }
 
 impl Default for InstanceMetadataBuilder {
@@ -50,6 +51,7 @@ impl InstanceMetadataBuilder {
             edge_data_format: None,
             node_coord_type: None,
             display_data_type: None,
+            grid_size: None,
         }
     }
 
@@ -73,6 +75,7 @@ impl InstanceMetadataBuilder {
             edge_data_format: self.edge_data_format,
             node_coord_type: self.node_coord_type.unwrap_or(NodeCoordType::NO_COORDS),
             display_data_type: self.display_data_type,
+            grid_size: self.grid_size,
         })
     }
 
@@ -175,4 +178,14 @@ impl InstanceMetadataBuilder {
         self.display_data_type = Some(display_data_type);
         self
     }
+
+    pub fn grid_size(mut self, grid_size: f64) -> Self {
+        self.grid_size = Some(grid_size);
+        self
+    }
+
+    pub fn grid_size_mut(&mut self, grid_size: f64) -> &mut Self {
+        self.grid_size = Some(grid_size);
+        self
+    }
 }
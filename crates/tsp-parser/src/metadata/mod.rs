@@ -1,5 +1,4 @@
 use memchr::memchr;
-use memmap2::Mmap;
 use thiserror::Error;
 use tsp_core::{
     instance::InstanceMetadata,
@@ -11,6 +10,7 @@ use tsp_core::{
 
 use crate::{
     FileContent, ParserError,
+    data_section::parse_number,
     metadata::metadata_builder::{InstanceMetadataBuilder, InstanceMetadataBuilderError},
 };
 
@@ -38,6 +38,8 @@ pub enum MetaDataParseError {
     InvalidNodeCoordType(String),
     #[error("Invalid DISPLAY_DATA_TYPE value: {0}")]
     InvalidDisplayDataType(String),
+    #[error("Invalid GRID_SIZE value: {0}")]
+    InvalidGridSize(String),
     #[error(transparent)]
     InstanceMetadataBuilderError(#[from] InstanceMetadataBuilderError),
 }
@@ -47,6 +49,9 @@ pub enum MetaDataParseError {
 /// Returns a tuple containing the parsed `InstanceMetadata`, the first encountered
 /// `TSPDataKeyword`, and a reference to the remaining lines iterator starting from the data section
 /// (the line after the first data keyword).
+///
+/// The file is parsed on the byte level, so it is not required to be valid UTF-8. Only the free
+/// text fields `NAME` and `COMMENT` are decoded, see [`decode_text_field`].
 pub fn parse_metadata(
     file_content: &FileContent,
     index_in_map: &mut usize,
@@ -59,12 +64,7 @@ pub fn parse_metadata(
             );
         };
 
-        let line = unsafe {
-            std::str::from_utf8_unchecked(
-                &file_content[*index_in_map..*index_in_map + index_newline],
-            )
-        };
-        // println!("Parsing line: {}", line);
+        let line = &file_content[*index_in_map..*index_in_map + index_newline];
 
         // Move the index to the start of the next line (+1 for the newline character)
         *index_in_map += index_newline + 1;
@@ -86,162 +86,187 @@ pub fn parse_metadata(
 }
 
 fn parse_specification_or_data_keyword(
-    line: &str,
+    line: &[u8],
     metadata_builder: &mut InstanceMetadataBuilder,
 ) -> Result<Option<TSPDataKeyword>, ParserError> {
-    let mut parts = line.splitn(2, ':');
-    match (parts.next(), parts.next()) {
+    match memchr(b':', line) {
         // Hot path
-        (Some(k), Some(v)) => {
-            parse_specification(k.trim(), v.trim(), metadata_builder)?;
+        Some(index_colon) => {
+            let (keyword, value) = (&line[..index_colon], &line[index_colon + 1..]);
+            parse_specification(keyword.trim_ascii(), value.trim_ascii(), metadata_builder)?;
             Ok(None)
         }
-        // Cold path(s)
-        (Some(k), None) => Ok(Some(parse_data_keyword(k.trim())?)),
-        _ => Err(MetaDataParseError::InvalidKeyword(line.to_string()).into()),
+        // Cold path
+        None => Ok(Some(parse_data_keyword(line.trim_ascii())?)),
     }
 }
 
 fn parse_specification(
-    keyword: &str,
-    value: &str,
+    keyword: &[u8],
+    value: &[u8],
     metadata_builder: &mut InstanceMetadataBuilder,
 ) -> Result<(), ParserError> {
     match keyword {
-        "NAME" => {
-            metadata_builder.name_mut(value.to_string());
+        b"NAME" => {
+            metadata_builder.name_mut(decode_text_field(value));
             Ok(())
         }
-        "TYPE" => {
+        b"TYPE" => {
             metadata_builder.problem_type_mut(parse_problem_type(value)?);
             Ok(())
         }
-        "COMMENT" => {
-            metadata_builder.comment_mut(value.to_string());
+        b"COMMENT" => {
+            metadata_builder.comment_mut(decode_text_field(value));
             Ok(())
         }
-        "DIMENSION" => {
+        b"DIMENSION" => {
             metadata_builder.dimension_mut(
-                value
-                    .parse()
-                    .map_err(|_| MetaDataParseError::InvalidDimension(value.to_string()))?,
+                parse_number(value).ok_or_else(|| {
+                    MetaDataParseError::InvalidDimension(decode_text_field(value))
+                })?,
             );
             Ok(())
         }
-        "CAPACITY" => {
-            metadata_builder.capacity_mut(
-                value
-                    .parse()
-                    .map_err(|_| MetaDataParseError::InvalidCapacity(value.to_string()))?,
-            );
+        b"CAPACITY" => {
+            metadata_builder
+                .capacity_mut(parse_number(value).ok_or_else(|| {
+                    MetaDataParseError::InvalidCapacity(decode_text_field(value))
+                })?);
             Ok(())
         }
-        "EDGE_WEIGHT_TYPE" => {
+        b"EDGE_WEIGHT_TYPE" => {
             metadata_builder.edge_weight_type_mut(parse_edge_weight_type(value)?);
             Ok(())
         }
-        "EDGE_WEIGHT_FORMAT" => {
+        b"EDGE_WEIGHT_FORMAT" => {
             metadata_builder.edge_weight_format_mut(parse_edge_weight_format(value)?);
             Ok(())
         }
-        "EDGE_DATA_FORMAT" => {
+        b"EDGE_DATA_FORMAT" => {
             metadata_builder.edge_data_format_mut(parse_edge_data_format(value)?);
             Ok(())
         }
-        "NODE_COORD_TYPE" => {
+        b"NODE_COORD_TYPE" => {
             metadata_builder.node_coord_type_mut(parse_node_coord_type(value)?);
             Ok(())
         }
-        "DISPLAY_DATA_TYPE" => {
+        b"DISPLAY_DATA_TYPE" => {
             metadata_builder.display_data_type_mut(parse_display_data_type(value)?);
             Ok(())
         }
-        _ => Err(MetaDataParseError::InvalidKeyword(keyword.to_string()).into()),
+        b"GRID_SIZE" => {
+            metadata_builder
+                .grid_size_mut(parse_number(value).ok_or_else(|| {
+                    MetaDataParseError::InvalidGridSize(decode_text_field(value))
+                })?);
+            Ok(())
+        }
+        _ => Err(MetaDataParseError::InvalidKeyword(decode_text_field(keyword)).into()),
     }
 }
 
-fn parse_data_keyword(input: &str) -> Result<TSPDataKeyword, ParserError> {
+/// Decodes a free text field (such as `NAME` or `COMMENT`) into a `String`.
+///
+/// TSPLIB files predate UTF-8 and some of them contain comments in other encodings (e.g.
+/// Latin-1). Invalid UTF-8 sequences are therefore replaced by `U+FFFD REPLACEMENT CHARACTER`
+/// instead of rejecting the whole file.
+pub fn decode_text_field(value: &[u8]) -> String {
+    String::from_utf8_lossy(value).into_owned()
+}
+
+pub(crate) fn parse_data_keyword(input: &[u8]) -> Result<TSPDataKeyword, ParserError> {
     match input {
-        "NODE_COORD_SECTION" => Ok(TSPDataKeyword::NODE_COORD_SECTION),
-        "DEPOT_SECTION" => Ok(TSPDataKeyword::DEPOT_SECTION),
-        "DEMAND_SECTION" => Ok(TSPDataKeyword::DEMAND_SECTION),
-        "EDGE_DATA_SECTION" => Ok(TSPDataKeyword::EDGE_DATA_SECTION),
-        "FIXED_EDGES_SECTION" => todo!("Fixed edges sections are not supported yet"),
-        "DISPLAY_DATA_SECTION" => Ok(TSPDataKeyword::DISPLAY_DATA_SECTION),
-        "TOUR_SECTION" => Ok(TSPDataKeyword::TOUR_SECTION),
-        "EDGE_WEIGHT_SECTION" => Ok(TSPDataKeyword::EDGE_WEIGHT_SECTION),
-        _ => Err(MetaDataParseError::InvalidKeyword(input.to_string()).into()),
+        b"NODE_COORD_SECTION" => Ok(TSPDataKeyword::NODE_COORD_SECTION),
+        b"DEPOT_SECTION" => Ok(TSPDataKeyword::DEPOT_SECTION),
+        b"DEMAND_SECTION" => Ok(TSPDataKeyword::DEMAND_SECTION),
+        b"EDGE_DATA_SECTION" => Ok(TSPDataKeyword::EDGE_DATA_SECTION),
+        b"FIXED_EDGES_SECTION" => todo!("Fixed edges sections are not supported yet"),
+        b"DISPLAY_DATA_SECTION" => Ok(TSPDataKeyword::DISPLAY_DATA_SECTION),
+        b"TOUR_SECTION" => Ok(TSPDataKeyword::TOUR_SECTION),
+        b"EDGE_WEIGHT_SECTION" => Ok(TSPDataKeyword::EDGE_WEIGHT_SECTION),
+        _ => Err(MetaDataParseError::InvalidKeyword(decode_text_field(input)).into()),
     }
 }
 
-fn parse_problem_type(input: &str) -> Result<ProblemType, ParserError> {
-    match input {
-        "TSP" => Ok(ProblemType::TSP),
-        "ATSP" => Ok(ProblemType::ATSP),
-        "SOP" => Ok(ProblemType::SOP),
-        "HCP" => Ok(ProblemType::HCP),
-        "TOUR" => Ok(ProblemType::TOUR),
-        _ => Err(MetaDataParseError::InvalidProblemType(input.to_string()).into()),
+fn parse_problem_type(input: &[u8]) -> Result<ProblemType, ParserError> {
+    // Some files annotate the type, e.g. `TYPE: TSP (M.~Hofmeister)`, so only the first word is
+    // considered
+    let problem_type = input.split(u8::is_ascii_whitespace).next().unwrap_or(input);
+    match problem_type {
+        b"TSP" => Ok(ProblemType::TSP),
+        b"ATSP" => Ok(ProblemType::ATSP),
+        b"SOP" => Ok(ProblemType::SOP),
+        b"HCP" => Ok(ProblemType::HCP),
+        b"TOUR" => Ok(ProblemType::TOUR),
+        _ => Err(MetaDataParseError::InvalidProblemType(decode_text_field(input)).into()),
     }
 }
 
-fn parse_edge_weight_type(input: &str) -> Result<EdgeWeightType, ParserError> {
+fn parse_edge_weight_type(input: &[u8]) -> Result<EdgeWeightType, ParserError> {
     match input {
-        "EXPLICIT" => Ok(EdgeWeightType::EXPLICIT),
-        "EUC_2D" => Ok(EdgeWeightType::EUC_2D),
-        "EUC_3D" => Ok(EdgeWeightType::EUC_3D),
-        "MAX_2D" => Ok(EdgeWeightType::MAX_2D),
-        "MAX_3D" => Ok(EdgeWeightType::MAX_3D),
-        "MAN_2D" => Ok(EdgeWeightType::MAN_2D),
-        "MAN_3D" => Ok(EdgeWeightType::MAN_3D),
-        "CEIL_2D" => Ok(EdgeWeightType::CEIL_2D),
-        "GEO" => Ok(EdgeWeightType::GEO),
-        "ATT" => Ok(EdgeWeightType::ATT),
-        "XRAY1" => Ok(EdgeWeightType::XRAY1),
-        "XRAY2" => Ok(EdgeWeightType::XRAY2),
-        "SPECIAL" => Ok(EdgeWeightType::SPECIAL),
-        _ => Err(MetaDataParseError::InvalidEdgeWeightType(input.to_string()).into()),
+        b"EXPLICIT" => Ok(EdgeWeightType::EXPLICIT),
+        b"EUC_2D" => Ok(EdgeWeightType::EUC_2D),
+        b"EUC_3D" => Ok(EdgeWeightType::EUC_3D),
+        b"MAX_2D" => Ok(EdgeWeightType::MAX_2D),
+        b"MAX_3D" => Ok(EdgeWeightType::MAX_3D),
+        b"MAN_2D" => Ok(EdgeWeightType::MAN_2D),
+        b"MAN_3D" => Ok(EdgeWeightType::MAN_3D),
+        b"CEIL_2D" => Ok(EdgeWeightType::CEIL_2D),
+        b"GEO" => Ok(EdgeWeightType::GEO),
+        b"ATT" => Ok(EdgeWeightType::ATT),
+        b"XRAY1" => Ok(EdgeWeightType::XRAY1),
+        b"XRAY2" => Ok(EdgeWeightType::XRAY2),
+        b"SPECIAL" => Ok(EdgeWeightType::SPECIAL),
+        b"GEOM" => Ok(EdgeWeightType::GEOM),
+        b"GEOM_MEEUS" => Ok(EdgeWeightType::GEOM_MEEUS),
+        b"GEO_MEEUS" => Ok(EdgeWeightType::GEO_MEEUS),
+        b"TOR_2D" => Ok(EdgeWeightType::TOR_2D),
+        b"TOR_3D" => Ok(EdgeWeightType::TOR_3D),
+        b"FLOOR_2D" => Ok(EdgeWeightType::FLOOR_2D),
+        b"FLOOR_3D" => Ok(EdgeWeightType::FLOOR_3D),
+        b"CEIL_3D" => Ok(EdgeWeightType::CEIL_3D),
+        _ => Err(MetaDataParseError::InvalidEdgeWeightType(decode_text_field(input)).into()),
     }
 }
 
-fn parse_edge_weight_format(input: &str) -> Result<EdgeWeightFormat, ParserError> {
+fn parse_edge_weight_format(input: &[u8]) -> Result<EdgeWeightFormat, ParserError> {
     match input {
-        "FUNCTION" => Ok(EdgeWeightFormat::FUNCTION),
-        "FULL_MATRIX" => Ok(EdgeWeightFormat::FULL_MATRIX),
-        "UPPER_ROW" => Ok(EdgeWeightFormat::UPPER_ROW),
-        "LOWER_ROW" => Ok(EdgeWeightFormat::LOWER_ROW),
-        "UPPER_DIAG_ROW" => Ok(EdgeWeightFormat::UPPER_DIAG_ROW),
-        "LOWER_DIAG_ROW" => Ok(EdgeWeightFormat::LOWER_DIAG_ROW),
-        "UPPER_COL" => Ok(EdgeWeightFormat::UPPER_COL),
-        "LOWER_COL" => Ok(EdgeWeightFormat::LOWER_COL),
-        "UPPER_DIAG_COL" => Ok(EdgeWeightFormat::UPPER_DIAG_COL),
-        "LOWER_DIAG_COL" => Ok(EdgeWeightFormat::LOWER_DIAG_COL),
-        _ => Err(MetaDataParseError::InvalidEdgeWeightFormat(input.to_string()).into()),
+        b"FUNCTION" => Ok(EdgeWeightFormat::FUNCTION),
+        b"FULL_MATRIX" => Ok(EdgeWeightFormat::FULL_MATRIX),
+        b"UPPER_ROW" => Ok(EdgeWeightFormat::UPPER_ROW),
+        b"LOWER_ROW" => Ok(EdgeWeightFormat::LOWER_ROW),
+        b"UPPER_DIAG_ROW" => Ok(EdgeWeightFormat::UPPER_DIAG_ROW),
+        b"LOWER_DIAG_ROW" => Ok(EdgeWeightFormat::LOWER_DIAG_ROW),
+        b"UPPER_COL" => Ok(EdgeWeightFormat::UPPER_COL),
+        b"LOWER_COL" => Ok(EdgeWeightFormat::LOWER_COL),
+        b"UPPER_DIAG_COL" => Ok(EdgeWeightFormat::UPPER_DIAG_COL),
+        b"LOWER_DIAG_COL" => Ok(EdgeWeightFormat::LOWER_DIAG_COL),
+        _ => Err(MetaDataParseError::InvalidEdgeWeightFormat(decode_text_field(input)).into()),
     }
 }
 
-fn parse_edge_data_format(input: &str) -> Result<EdgeDataFormat, ParserError> {
+fn parse_edge_data_format(input: &[u8]) -> Result<EdgeDataFormat, ParserError> {
     match input {
-        "EDGE_LIST" => Ok(EdgeDataFormat::EDGE_LIST),
-        "ADJ_LIST" => Ok(EdgeDataFormat::ADJ_LIST),
-        _ => Err(MetaDataParseError::InvalidEdgeDataFormat(input.to_string()).into()),
+        b"EDGE_LIST" => Ok(EdgeDataFormat::EDGE_LIST),
+        b"ADJ_LIST" => Ok(EdgeDataFormat::ADJ_LIST),
+        _ => Err(MetaDataParseError::InvalidEdgeDataFormat(decode_text_field(input)).into()),
     }
 }
 
-fn parse_node_coord_type(input: &str) -> Result<NodeCoordType, ParserError> {
+fn parse_node_coord_type(input: &[u8]) -> Result<NodeCoordType, ParserError> {
     match input {
-        "TWOD_COORDS" => Ok(NodeCoordType::TWOD_COORDS),
-        "THREED_COORDS" => Ok(NodeCoordType::THREED_COORDS),
-        "NO_COORDS" => Ok(NodeCoordType::NO_COORDS),
-        _ => Err(MetaDataParseError::InvalidNodeCoordType(input.to_string()).into()),
+        b"TWOD_COORDS" => Ok(NodeCoordType::TWOD_COORDS),
+        b"THREED_COORDS" => Ok(NodeCoordType::THREED_COORDS),
+        b"NO_COORDS" => Ok(NodeCoordType::NO_COORDS),
+        _ => Err(MetaDataParseError::InvalidNodeCoordType(decode_text_field(input)).into()),
     }
 }
 
-fn parse_display_data_type(input: &str) -> Result<DisplayDataType, ParserError> {
+fn parse_display_data_type(input: &[u8]) -> Result<DisplayDataType, ParserError> {
     match input {
-        "COORD_DISPLAY" => Ok(DisplayDataType::COORD_DISPLAY),
-        "TWOD_DISPLAY" => Ok(DisplayDataType::TWOD_DISPLAY),
-        "NO_DISPLAY" => Ok(DisplayDataType::NO_DISPLAY),
-        _ => Err(MetaDataParseError::InvalidDisplayDataType(input.to_string()).into()),
+        b"COORD_DISPLAY" => Ok(DisplayDataType::COORD_DISPLAY),
+        b"TWOD_DISPLAY" => Ok(DisplayDataType::TWOD_DISPLAY),
+        b"NO_DISPLAY" => Ok(DisplayDataType::NO_DISPLAY),
+        _ => Err(MetaDataParseError::InvalidDisplayDataType(decode_text_field(input)).into()),
     }
 }
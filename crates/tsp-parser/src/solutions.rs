@@ -0,0 +1,83 @@
+//! Parsing of files with the optimal (or best known) tour lengths of instances.
+//!
+//! Every non-empty line has the form `name : cost`, optionally followed by a comment, e.g.
+//! `dsj1000 : 18660188 (CEIL_2D)`. This is the format of the `solutions` file shipped with TSPLIB.
+
+use std::{collections::HashMap, path::Path};
+
+use thiserror::Error;
+use tsp_core::instance::{
+    comparison::relative_gap,
+    distance::{Distance64, DistanceValue},
+};
+
+use crate::ParserError;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SolutionsParseError {
+    #[error("Line {line} is not of the form `name : cost`: {content}")]
+    InvalidLine { line: usize, content: String },
+    #[error("Invalid cost on line {line}: {content}")]
+    InvalidCost { line: usize, content: String },
+}
+
+/// The known optimal tour lengths of instances, by instance name.
+#[derive(Debug, Clone, Default)]
+pub struct KnownSolutions {
+    costs: HashMap<String, Distance64>,
+}
+
+impl KnownSolutions {
+    /// Reads a solutions file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ParserError> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Parses the content of a solutions file.
+    pub fn parse(content: &str) -> Result<Self, SolutionsParseError> {
+        let mut costs = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (name, cost) =
+                line.split_once(':')
+                    .ok_or_else(|| SolutionsParseError::InvalidLine {
+                        line: index + 1,
+                        content: line.to_string(),
+                    })?;
+            let cost = cost
+                .split_whitespace()
+                .next()
+                .and_then(|cost| cost.parse().ok())
+                .ok_or_else(|| SolutionsParseError::InvalidCost {
+                    line: index + 1,
+                    content: line.to_string(),
+                })?;
+            costs.insert(name.trim().to_string(), Distance64(cost));
+        }
+        Ok(Self { costs })
+    }
+
+    /// Returns the optimal tour length of the instance, if it is known.
+    pub fn get(&self, name: &str) -> Option<Distance64> {
+        self.costs.get(name).copied()
+    }
+
+    /// Returns the gap of the cost to the optimal tour length of the instance in percent, if the
+    /// optimal tour length is known. See [`relative_gap`].
+    pub fn gap(&self, name: &str, cost: impl DistanceValue) -> Option<f64> {
+        self.get(name)
+            .map(|optimum| relative_gap(cost.to_f64(), optimum.to_f64()))
+    }
+
+    /// Returns the number of instances.
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Returns whether no instance is known.
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+
+use tsp_core::instance::{
+    distance::{Distance, FloatDistance},
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+    oracle::DistanceOracle,
+};
+use tsp_parser::{
+    ParserError,
+    cache::{
+        CacheError, MappedMatrix, MappedMatrixSym, cache_path, fingerprint, load_cache,
+        parse_tsp_instance_cached,
+    },
+};
+
+/// Copies the instance into a fresh directory, so the cache files of parallel tests do not
+/// interfere.
+fn copy_instance(name: &str, test: &str) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("tsp-parser-cache-{test}-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    std::fs::copy(format!("../../instances/tsplib_symmetric/{name}"), &path).unwrap();
+    path
+}
+
+#[test]
+fn test_cached_matrix_sym_equals_parsed() {
+    let path = copy_instance("bayg29.tsp", "sym");
+    let parsed = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(&path).unwrap();
+
+    // The first call builds the cache, the second one maps it
+    for _ in 0..2 {
+        let cached = parse_tsp_instance_cached::<MappedMatrixSym<Distance>>(&path).unwrap();
+        assert!(cache_path::<MappedMatrixSym<Distance>>(&path).exists());
+        assert_eq!(cached.distances().data(), parsed.raw_distances());
+        assert_eq!(cached.display_data(), parsed.display_data());
+        assert_eq!(cached.metadata().name, parsed.metadata().name);
+        assert_eq!(cached.dimension(), 29);
+    }
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_cached_matrix_equals_parsed() {
+    let path = copy_instance("berlin52.tsp", "full");
+    let parsed = tsp_parser::parse_tsp_instance::<Matrix<FloatDistance>>(&path).unwrap();
+
+    for _ in 0..2 {
+        let cached = parse_tsp_instance_cached::<MappedMatrix<FloatDistance>>(&path).unwrap();
+        assert_eq!(cached.distances().data(), parsed.raw_distances());
+        assert_eq!(cached.display_data(), parsed.display_data());
+        assert_eq!(
+            cached.distances().to_matrix().data(),
+            parsed.distance_matrix().data()
+        );
+    }
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_stale_cache_is_rebuilt() {
+    let path = copy_instance("bayg29.tsp", "stale");
+    let cache = cache_path::<MappedMatrixSym<Distance>>(&path);
+    let old_fingerprint = fingerprint(&std::fs::read(&path).unwrap());
+    parse_tsp_instance_cached::<MappedMatrixSym<Distance>>(&path).unwrap();
+
+    let content = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("NAME: bayg29", "NAME: changed");
+    std::fs::write(&path, content).unwrap();
+    let new_fingerprint = fingerprint(&std::fs::read(&path).unwrap());
+
+    let cached = parse_tsp_instance_cached::<MappedMatrixSym<Distance>>(&path).unwrap();
+    assert_eq!(cached.metadata().name, "changed");
+    assert!(load_cache::<MappedMatrixSym<Distance>>(&cache, new_fingerprint).is_ok());
+    assert!(matches!(
+        load_cache::<MappedMatrixSym<Distance>>(&cache, old_fingerprint),
+        Err(ParserError::Cache(CacheError::FingerprintMismatch { .. }))
+    ));
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_invalid_cache_is_rejected() {
+    let path = copy_instance("bayg29.tsp", "invalid");
+    let cache = cache_path::<MappedMatrixSym<Distance>>(&path);
+    let fingerprint = fingerprint(&std::fs::read(&path).unwrap());
+    parse_tsp_instance_cached::<MappedMatrixSym<Distance>>(&path).unwrap();
+
+    // Loading the cache with another element type or layout fails
+    let wrong_path = cache_path::<MappedMatrix<Distance>>(&path);
+    std::fs::copy(&cache, &wrong_path).unwrap();
+    assert!(matches!(
+        load_cache::<MappedMatrix<Distance>>(&wrong_path, fingerprint),
+        Err(ParserError::Cache(CacheError::TypeMismatch { .. }))
+    ));
+
+    // A truncated cache is detected and rebuilt
+    let bytes = std::fs::read(&cache).unwrap();
+    std::fs::write(&cache, &bytes[..bytes.len() / 2]).unwrap();
+    assert!(matches!(
+        load_cache::<MappedMatrixSym<Distance>>(&cache, fingerprint),
+        Err(ParserError::Cache(CacheError::Truncated { .. }))
+    ));
+    parse_tsp_instance_cached::<MappedMatrixSym<Distance>>(&path).unwrap();
+    assert_eq!(std::fs::read(&cache).unwrap(), bytes);
+
+    // Sizes that overflow are rejected instead of wrapping around
+    let mut header = bytes.clone();
+    header[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+    std::fs::write(&cache, &header).unwrap();
+    assert!(matches!(
+        load_cache::<MappedMatrixSym<Distance>>(&cache, fingerprint),
+        Err(ParserError::Cache(CacheError::SizeOverflow { .. }))
+    ));
+
+    std::fs::write(
+        &cache,
+        b"not a cache file, but long enough to contain a full header......",
+    )
+    .unwrap();
+    assert!(matches!(
+        load_cache::<MappedMatrixSym<Distance>>(&cache, fingerprint),
+        Err(ParserError::Cache(CacheError::InvalidMagic))
+    ));
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_unwritable_cache_is_skipped() {
+    let path = copy_instance("bayg29.tsp", "unwritable");
+    // A non-empty directory in place of the cache file makes the final rename fail
+    let cache = cache_path::<MappedMatrixSym<Distance>>(&path);
+    std::fs::create_dir(&cache).unwrap();
+    std::fs::write(cache.join("occupied"), b"").unwrap();
+
+    let parsed = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(&path).unwrap();
+    let cached = parse_tsp_instance_cached::<MappedMatrixSym<Distance>>(&path).unwrap();
+    assert_eq!(cached.distances().data(), parsed.raw_distances());
+    assert_eq!(cached.display_data(), parsed.display_data());
+    assert_eq!(
+        cached.distances().get(Node(3), Node(7)),
+        parsed.distances().get(Node(3), Node(7))
+    );
+
+    // The temporary file is removed again
+    let files = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+    assert_eq!(files, 2);
+
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -7,7 +7,7 @@ use std::{
 use tsp_core::instance::{
     TSPSymInstance,
     distance::Distance,
-    matrix::{Matrix, MatrixSym},
+    matrix::{AnyCompactMatrix, AnyCompactMatrixSym, Matrix, MatrixSym},
     node::Node,
 };
 
@@ -79,6 +79,21 @@ fn check_input_file_against_golden_file(file_name: &str) {
             );
         }
     }
+
+    let compact_sym: TSPSymInstance<AnyCompactMatrixSym> =
+        tsp_parser::parse_tsp_instance("../../instances/".to_owned() + file_name + ".tsp")
+            .expect("Compact symmetric parsing should succeed");
+    assert_eq!(
+        compact_sym.distances().to_matrix_sym().data(),
+        &golden_distance_data
+    );
+    let compact_matrix: TSPSymInstance<AnyCompactMatrix> =
+        tsp_parser::parse_tsp_instance("../../instances/".to_owned() + file_name + ".tsp")
+            .expect("Compact matrix parsing should succeed");
+    assert_eq!(
+        compact_matrix.distances().to_matrix().data(),
+        input_instance_matrix.distance_matrix().data()
+    );
 }
 
 #[test]
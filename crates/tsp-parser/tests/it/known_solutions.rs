@@ -0,0 +1,42 @@
+use tsp_core::instance::{distance::Distance, matrix::MatrixSym, node::Node, tour::Tour};
+use tsp_parser::solutions::{KnownSolutions, SolutionsParseError};
+
+#[test]
+fn test_tsplib_solutions() {
+    let solutions =
+        KnownSolutions::from_file("../../instances/tsplib_symmetric/solutions").unwrap();
+    assert_eq!(solutions.len(), 111);
+    assert_eq!(solutions.get("a280").map(|cost| cost.0), Some(2579));
+    assert_eq!(solutions.get("dsj1000").map(|cost| cost.0), Some(18660188));
+    assert_eq!(solutions.get("missing"), None);
+
+    // The identity tour of ulysses16 compared with its optimum
+    let instance = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(
+        "../../instances/tsplib_symmetric/ulysses16.tsp",
+    )
+    .unwrap();
+    let tour = Tour::from_nodes((0..16).map(Node).collect(), |from, to| {
+        instance.distance_matrix().get_data(from, to)
+    })
+    .unwrap();
+    let optimum = solutions.get("ulysses16").unwrap().0 as f64;
+    let gap = solutions.gap("ulysses16", tour.cost()).unwrap();
+    assert!((gap - 100.0 * (tour.cost().0 as f64 - optimum) / optimum).abs() < 1e-9);
+    assert!(gap > 0.0);
+}
+
+#[test]
+fn test_invalid_solutions() {
+    assert_eq!(
+        KnownSolutions::parse("a280 : 2579\nberlin52 7542\n").unwrap_err(),
+        SolutionsParseError::InvalidLine {
+            line: 2,
+            content: "berlin52 7542".to_string()
+        }
+    );
+    assert!(matches!(
+        KnownSolutions::parse("a280 : many"),
+        Err(SolutionsParseError::InvalidCost { line: 1, .. })
+    ));
+    assert!(KnownSolutions::parse("\n").unwrap().is_empty());
+}
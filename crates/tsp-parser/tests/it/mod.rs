@@ -1,3 +1,8 @@
+mod cache;
 mod check_canonical_tour_length;
 mod check_matrix_parsing;
+mod known_solutions;
+mod parse_display_data;
+mod parse_lkh_edge_weight_types;
+mod parse_non_utf8;
 mod parse_without_error;
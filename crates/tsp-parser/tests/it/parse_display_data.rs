@@ -0,0 +1,99 @@
+use tsp_core::instance::{
+    distance::Distance,
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+    point::Point2D,
+};
+use tsp_parser::{ParserError, data_section::DataSectionParseError};
+
+#[test]
+fn test_twod_display_bayg29() {
+    let instance = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(
+        "../../instances/tsplib_symmetric/bayg29.tsp",
+    )
+    .expect("Parsing bayg29 should succeed");
+
+    let display_data = instance
+        .display_data()
+        .expect("bayg29 has a DISPLAY_DATA_SECTION");
+    assert_eq!(display_data.len(), 29);
+    assert_eq!(
+        display_data[0],
+        Point2D {
+            x: 1150.0,
+            y: 1760.0
+        }
+    );
+    assert_eq!(
+        display_data[1],
+        Point2D {
+            x: 630.0,
+            y: 1660.0
+        }
+    );
+
+    // UPPER_ROW: The first line contains the distances from node 0 to nodes 1, 2, ...
+    let distances = instance.distance_matrix();
+    assert_eq!(distances.get_data(Node(0), Node(1)), Distance(97));
+    assert_eq!(distances.get_data(Node(2), Node(0)), Distance(205));
+}
+
+#[test]
+fn test_coord_display_berlin52() {
+    let instance = tsp_parser::parse_tsp_instance::<Matrix<Distance>>(
+        "../../instances/tsplib_symmetric/berlin52.tsp",
+    )
+    .expect("Parsing berlin52 should succeed");
+
+    // No DISPLAY_DATA_TYPE defaults to COORD_DISPLAY for instances with node coordinates
+    let display_data = instance
+        .display_data()
+        .expect("berlin52 is displayed using its node coordinates");
+    assert_eq!(display_data.len(), 52);
+    assert_eq!(display_data[0], Point2D { x: 565.0, y: 575.0 });
+    assert_eq!(display_data[1], Point2D { x: 25.0, y: 185.0 });
+}
+
+#[test]
+fn test_no_display_explicit_gr17_short() {
+    let instance = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(
+        "../../instances/tsplib_symmetric/gr17.tsp",
+    )
+    .expect("Parsing gr17 should succeed");
+
+    assert!(instance.display_data().is_none());
+
+    // LOWER_DIAG_ROW: The entries are given row by row up to and including the diagonal
+    let distances = instance.distance_matrix();
+    assert_eq!(distances.get_data(Node(1), Node(0)), Distance(633));
+    assert_eq!(distances.get_data(Node(2), Node(1)), Distance(390));
+    assert_eq!(distances.get_data(Node(3), Node(3)), Distance(0));
+}
+
+#[test]
+fn test_full_matrix_swiss42() {
+    let instance = tsp_parser::parse_tsp_instance::<Matrix<Distance>>(
+        "../../instances/tsplib_symmetric/swiss42.tsp",
+    )
+    .expect("Parsing swiss42 should succeed");
+
+    let distances = instance.distance_matrix();
+    assert_eq!(distances.get_data(Node(0), Node(1)), Distance(15));
+    assert_eq!(distances.get_data(Node(41), Node(0)), Distance(124));
+}
+
+#[test]
+fn test_asymmetric_full_matrix_short() {
+    let path = "tests/test_assets/explicit/asymmetric_full_matrix.tsp";
+    for result in [
+        tsp_parser::parse_tsp_instance::<Matrix<Distance>>(path).map(|_| ()),
+        tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(path).map(|_| ()),
+    ] {
+        assert!(matches!(
+            result,
+            Err(ParserError::DataSectionParsing(
+                DataSectionParseError::AsymmetricEdgeWeight(2, 0)
+            ))
+        ));
+    }
+}
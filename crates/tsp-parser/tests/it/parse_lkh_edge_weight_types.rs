@@ -0,0 +1,78 @@
+use tsp_core::instance::{
+    distance::Distance,
+    matrix::{Matrix, MatrixSym},
+    node::Node,
+};
+
+const LKH_ASSETS: &str = "tests/test_assets/lkh/";
+
+fn parse(file_name: &str) -> MatrixSym<Distance> {
+    tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(LKH_ASSETS.to_owned() + file_name)
+        .unwrap()
+        .distance_matrix()
+        .clone()
+}
+
+#[test]
+fn test_geom_short() {
+    let distances = parse("geom4.tsp");
+
+    // One degree of longitude on the equator is 111323.87 meters, GEOM adds one before truncating
+    assert_eq!(distances.get_data(Node(0), Node(1)), Distance(111_324));
+    assert_eq!(distances.get_data(Node(0), Node(3)), Distance(333_972));
+}
+
+#[test]
+fn test_geom_meeus_short() {
+    let distances = parse("geom_meeus4.tsp");
+
+    assert_eq!(distances.get_data(Node(0), Node(1)), Distance(111_324));
+    assert_eq!(distances.get_data(Node(1), Node(3)), Distance(222_648));
+}
+
+#[test]
+fn test_tor_2d_uses_grid_size_short() {
+    let distances = parse("tor2d4.tsp");
+
+    // Going around the torus of side length 100 is shorter than crossing it
+    assert_eq!(distances.get_data(Node(0), Node(1)), Distance(2));
+    assert_eq!(distances.get_data(Node(0), Node(2)), Distance(3));
+}
+
+#[test]
+fn test_floor_and_ceil_3d_short() {
+    let floor = parse("floor3d3.tsp");
+    let ceil = parse("ceil3d3.tsp");
+
+    assert_eq!(floor.get_data(Node(0), Node(1)), Distance(1));
+    assert_eq!(ceil.get_data(Node(0), Node(1)), Distance(2));
+    assert_eq!(floor.get_data(Node(0), Node(2)), Distance(5));
+    assert_eq!(ceil.get_data(Node(0), Node(2)), Distance(5));
+}
+
+#[test]
+fn test_matrix_and_matrix_sym_agree_short() {
+    for file_name in [
+        "geom4.tsp",
+        "geom_meeus4.tsp",
+        "tor2d4.tsp",
+        "floor3d3.tsp",
+        "ceil3d3.tsp",
+    ] {
+        let matrix_sym = parse(file_name);
+        let instance =
+            tsp_parser::parse_tsp_instance::<Matrix<Distance>>(LKH_ASSETS.to_owned() + file_name)
+                .unwrap();
+        let dimension = instance.metadata().dimension;
+
+        for from in 0..dimension {
+            for to in 0..from {
+                assert_eq!(
+                    instance.distance_matrix().get_data(Node(from), Node(to)),
+                    matrix_sym.get_data(Node(from), Node(to)),
+                    "{file_name}: ({from}, {to})"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use tsp_core::instance::{distance::Distance, matrix::MatrixSym, node::Node};
+use tsp_parser::{ParserError, data_section::DataSectionParseError, metadata::MetaDataParseError};
+
+const ENCODING_ASSETS: &str = "tests/test_assets/encoding/";
+
+#[test]
+fn test_latin1_comment_short() {
+    let instance = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(
+        ENCODING_ASSETS.to_owned() + "latin1_comment.tsp",
+    )
+    .expect("Latin-1 comments should be decoded lossily");
+
+    assert_eq!(
+        instance.metadata().comment.as_deref(),
+        Some("Gr\u{FFFD}\u{FFFD}te St\u{FFFD}dte (Latin-1 encoded)")
+    );
+    assert_eq!(
+        instance.distance_matrix().get_data(Node(0), Node(1)),
+        Distance(5)
+    );
+}
+
+#[test]
+fn test_invalid_coordinate_short() {
+    let result = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(
+        ENCODING_ASSETS.to_owned() + "invalid_coordinate.tsp",
+    );
+
+    assert!(matches!(
+        result,
+        Err(ParserError::DataSectionParsing(
+            DataSectionParseError::InvalidCoordinate(_)
+        ))
+    ));
+}
+
+#[test]
+fn test_invalid_keyword_short() {
+    let result = tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(
+        ENCODING_ASSETS.to_owned() + "invalid_keyword.tsp",
+    );
+
+    assert!(matches!(
+        result,
+        Err(ParserError::MetaDataParsing(
+            MetaDataParseError::InvalidKeyword(_)
+        ))
+    ));
+}
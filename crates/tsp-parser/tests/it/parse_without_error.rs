@@ -1,4 +1,4 @@
-use std::any::Any;
+use std::{any::Any, fmt::Display};
 
 use tsp_core::instance::{
     distance::Distance,
@@ -10,21 +10,21 @@ fn parse_instance_symmetric(path: &str) {
     let parsing_result = std::panic::catch_unwind(|| {
         tsp_parser::parse_tsp_instance::<MatrixSym<Distance>>(path.to_owned())
     });
-    if let Err(err) = parsing_result {
-        handle_error(err);
-    } else {
-        assert!(parsing_result.is_ok());
-    }
+    check_parsing_result(parsing_result);
 }
 
 fn parse_instance_non_symmetric(path: &str) {
     let parsing_result = std::panic::catch_unwind(|| {
         tsp_parser::parse_tsp_instance::<Matrix<Distance>>(path.to_owned())
     });
-    if let Err(err) = parsing_result {
-        handle_error(err);
-    } else {
-        assert!(parsing_result.is_ok());
+    check_parsing_result(parsing_result);
+}
+
+fn check_parsing_result<T, E: Display>(parsing_result: std::thread::Result<Result<T, E>>) {
+    match parsing_result {
+        Ok(Ok(_)) => {}
+        Ok(Err(err)) => panic!("Parsing failed with error: {err}"),
+        Err(err) => handle_error(err),
     }
 }
 
@@ -38,9 +38,8 @@ fn handle_error(err: Box<dyn Any + Send>) {
     };
 
     match err_msg.as_str() {
-        "not yet implemented: Explicit distance matrix parsing is not supported yet" => {}
         "not yet implemented: Fixed edges sections are not supported yet" => {}
-        _ => assert!(false, "Parsing failed with unexpected error: {}", err_msg),
+        _ => panic!("Parsing failed with unexpected error: {err_msg}"),
     }
 }
 
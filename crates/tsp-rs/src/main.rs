@@ -1,22 +1,25 @@
-use std::ops::Add;
-
-use tsp_core::instance::{
-    distance::Distance,
-    matrix::{Matrix, MatrixSym},
-};
-use tsp_solvers::{held_karp, held_karp_mod::held_karp_parallel};
+use tsp_core::instance::{distance::Distance, matrix::Matrix};
+use tsp_solvers::solver::{SOLVER_NAMES, SolverOptions, solver_by_name};
 
 fn main() {
     env_logger::init();
 
+    let solver_name = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "held-karp".to_string());
+    let Some(solver) = solver_by_name(&solver_name) else {
+        eprintln!("Unknown solver {solver_name}, expected one of {SOLVER_NAMES:?}");
+        std::process::exit(1);
+    };
+
     let tsp_instance =
-        tsp_parser::parse_tsp_instance::<Matrix<Distance>>(
-            "instances/tsplib_symmetric/eil76.tsp",
-        )
+        tsp_parser::parse_tsp_instance::<Matrix<Distance>>("instances/tsplib_symmetric/eil76.tsp")
+            .unwrap();
+    let solution = solver
+        .solve(tsp_instance.distance_matrix(), &SolverOptions::default())
         .unwrap();
-    let best_tour = held_karp(tsp_instance.distance_matrix());
-    if let Some(best_tour) = &best_tour {
-        println!("Best tour found: {:?}", best_tour.cost.0);
+    if let Some(cost) = solution.cost() {
+        println!("Best tour found: {:?}", cost.0);
     }
 }
 
@@ -1,8 +1,12 @@
 use concorde_rs::solver::tsp_hk;
-use criterion::{Criterion, criterion_group, criterion_main};
-use tsp_core::instance::{distance::Distance, matrix::MatrixSym};
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use tsp_core::instance::{distance::Distance, matrix::MatrixSym, node::Node, tour::Tour};
 use tsp_parser::parse_tsp_instance;
-use tsp_solvers::held_karp;
+use tsp_solvers::{
+    held_karp,
+    held_karp_mod::HeldKarp,
+    solver::{Solver, SolverOptions},
+};
 
 macro_rules! create_held_karp_benchmarks {
     ($file_path:expr, $name_concorde:ident, $name_own:ident) => {
@@ -45,6 +49,58 @@ create_held_karp_benchmarks!(
     held_karp_own_berlin52
 );
 
+/// Instances of the golden set in `tests/test_assets/tour_lengths` on which the starting tours are
+/// compared. The tour `0 -> 1 -> ... -> n-1` happens to be optimal for dantzig42.
+const INITIAL_TOUR_INSTANCES: &[&str] = &[
+    "ulysses16",
+    "ulysses22",
+    "gr24",
+    "fri26",
+    "bayg29",
+    "bays29",
+    "dantzig42",
+    "swiss42",
+];
+
+/// Compares starting the search from the tour `0 -> 1 -> ... -> n-1` with starting it from the
+/// heuristic tour. The number of explored nodes of each start is part of its benchmark id.
+fn held_karp_initial_tour(c: &mut Criterion) {
+    let mut group = c.benchmark_group("held_karp_initial_tour");
+    group.sample_size(10);
+
+    for &name in INITIAL_TOUR_INSTANCES {
+        let tsp_instance = parse_tsp_instance::<MatrixSym<Distance>>(format!(
+            "../../instances/tsplib_symmetric/{name}.tsp"
+        ))
+        .unwrap();
+        let non_symmetric_matrix = tsp_instance.distance_matrix().to_edge_data_matrix();
+        let dimension = tsp_instance.metadata().dimension;
+
+        let identity_tour = Tour::new((0..dimension).map(Node).collect(), Distance(0)).unwrap();
+        let identity_options = SolverOptions {
+            initial_tour: Some(identity_tour),
+            ..SolverOptions::default()
+        };
+        let heuristic_options = SolverOptions::default();
+
+        for (start, options) in [
+            ("identity tour", &identity_options),
+            ("heuristic tour", &heuristic_options),
+        ] {
+            let nodes = HeldKarp::default()
+                .solve(&non_symmetric_matrix, options)
+                .unwrap()
+                .stats
+                .nodes;
+            let id = BenchmarkId::new(start, format!("{name} ({nodes} nodes)"));
+            group.bench_with_input(id, options, |b, options| {
+                b.iter(|| HeldKarp::default().solve(&non_symmetric_matrix, options))
+            });
+        }
+    }
+    group.finish();
+}
+
 criterion_group!(held_karp_bench_12, held_karp_concorde_12, held_karp_own_12);
 criterion_group!(
     name = held_karp_bench_berlin52;
@@ -52,4 +108,10 @@ criterion_group!(
     targets = held_karp_concorde_berlin52, held_karp_own_berlin52
 );
 
-criterion_main!(held_karp_bench_12, held_karp_bench_berlin52);
+criterion_group!(held_karp_bench_initial_tour, held_karp_initial_tour);
+
+criterion_main!(
+    held_karp_bench_12,
+    held_karp_bench_berlin52,
+    held_karp_bench_initial_tour
+);
@@ -0,0 +1,117 @@
+use log::{debug, info};
+use tsp_core::instance::{
+    UnTour, distance::DistanceValue, edge::UnEdge, matrix::MatrixSym, node::Node,
+    oracle::DistanceOracle,
+};
+
+use crate::{
+    held_karp_mod::{
+        EdgeState, HeldKarpConfig, LowerBoundOutput, SearchContext, held_karp_lower_bound,
+        initial_penalties, limits::SearchLimits, progress::SearchProgress,
+    },
+    observer::NoObserver,
+    solver::SolverOptions,
+};
+
+/// The result of [`held_karp_bound`].
+#[derive(Debug, Clone)]
+pub struct BoundResult<D: DistanceValue> {
+    /// The best lower bound on the cost of a tour, rounded up.
+    pub lower_bound: D,
+    /// The upper bound used for the step sizes.
+    pub upper_bound: D,
+    /// The node penalties at the end of the ascent.
+    pub node_penalties: Vec<D::Scaled>,
+    /// The minimum 1-tree with the final node penalties.
+    pub one_tree: Vec<UnEdge>,
+    /// The tour, if the final 1-tree is a tour. The lower bound is optimal in this case.
+    pub tour: Option<UnTour<D>>,
+    /// The progress of every iteration.
+    pub trace: Vec<BoundIteration<D>>,
+}
+
+/// The state of one iteration of the subgradient ascent.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundIteration<D: DistanceValue> {
+    /// The cost of the 1-tree with the penalties of this iteration, rounded up.
+    pub bound: D,
+    /// The sum of the squared degree deviations from 2 of the 1-tree, 0 if it is a tour.
+    pub degree_deviation: u32,
+    /// The step size used to update the penalties, 0 in the last iteration.
+    pub step_size: D::Scaled,
+}
+
+/// Computes the Held-Karp lower bound with a subgradient ascent on the node penalties of 1-trees,
+/// without branching.
+///
+/// This is the bound computed at the root of [`held_karp`](crate::held_karp), with the root
+/// parameters of the config (`initial_max_iterations`, `initial_alpha`, `initial_beta` and
+/// `min_step_factor`). It takes only `O(iterations * dimension^2)` time, so it is also useful on
+/// instances that are too big to be solved, e.g. to assess heuristic tours. The ascent stops after
+/// `config.initial_max_iterations` iterations (but performs at least one), when the step size
+/// becomes negligible, when the bound reaches the upper bound or when a 1-tree is a tour.
+///
+/// The upper bound steers the step sizes, a tighter one (e.g. the cost of a heuristic tour)
+/// converges faster. Without one, the cost of the tour visiting the nodes in order is used.
+///
+/// Returns `None` for instances with less than 3 nodes.
+pub fn held_karp_bound<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    upper_bound: Option<D>,
+    config: &HeldKarpConfig,
+) -> Option<BoundResult<D>> {
+    let dimension = distances.dimension();
+    if dimension < 3 {
+        return None;
+    }
+    info!("Computing the Held-Karp bound");
+
+    let upper_bound = upper_bound.unwrap_or_else(|| {
+        (0..dimension)
+            .map(|node| distances.get(Node(node), Node((node + 1) % dimension)))
+            .sum()
+    });
+    let edge_states = MatrixSym::new_from_dimension_with_value(dimension, EdgeState::Available);
+    let mut node_penalties = initial_penalties(distances);
+    let mut trace = Vec::new();
+    let limits = SearchLimits::new(&SolverOptions::default());
+    let progress = SearchProgress::new(&NoObserver);
+    let context = SearchContext {
+        config,
+        limits: &limits,
+        progress: &progress,
+        upper_bound: None,
+    };
+
+    let (lower_bound, one_tree, tour) = match held_karp_lower_bound(
+        distances,
+        &edge_states,
+        &mut node_penalties,
+        upper_bound,
+        0,
+        &context,
+        Some(&mut trace),
+    )
+    .expect("Without excluded edges, every instance with 3 nodes has a 1-tree")
+    {
+        LowerBoundOutput::LowerBound(lower_bound, one_tree) => (lower_bound, one_tree, None),
+        LowerBoundOutput::Tour(tour) => {
+            debug!("The 1-tree is a tour after {} iterations", trace.len());
+            (tour.cost, tour.edges.clone(), Some(tour))
+        }
+    };
+
+    info!(
+        "Held-Karp bound {} after {} iterations",
+        lower_bound,
+        trace.len()
+    );
+    Some(BoundResult {
+        lower_bound,
+        upper_bound,
+        node_penalties,
+        one_tree,
+        tour,
+        trace,
+    })
+}
@@ -0,0 +1,193 @@
+use thiserror::Error;
+
+use crate::held_karp_mod::{
+    BETA, INITIAL_ALPHA, INITIAL_BETA, INITIAL_MAX_ITERATIONS, MAX_ITERATIONS, MAX_THREADS,
+};
+
+/// Tuning parameters of the Held-Karp solvers.
+///
+/// At every branch-and-bound node, the lower bound is computed with a subgradient ascent on the
+/// node penalties. The step size of iteration `k` is `initial_alpha * beta^k * (upper_bound -
+/// bound) / |g|^2`, where `g` is the vector of the degree deviations of the 1-tree. The root of the
+/// search usually gets many more iterations than the other nodes, since its penalties are the
+/// starting point of all other nodes.
+///
+/// Use [`HeldKarpConfig::validate`] (or the constructors of the solvers, which call it) to check
+/// the parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldKarpConfig {
+    /// The maximum number of subgradient iterations at the root node.
+    pub initial_max_iterations: usize,
+    /// The maximum number of subgradient iterations at all other nodes.
+    pub max_iterations: usize,
+    /// The initial step size factor `alpha`.
+    pub initial_alpha: f64,
+    /// The factor `beta` by which the step size factor decreases every iteration at the root node.
+    pub initial_beta: f64,
+    /// The factor `beta` by which the step size factor decreases every iteration at all other
+    /// nodes.
+    pub beta: f64,
+    /// The ascent stops once a step is at most this multiple of
+    /// [`ScaledValue::MIN_STEP`](tsp_core::instance::distance::ScaledValue::MIN_STEP).
+    pub min_step_factor: f64,
+    /// The maximum number of threads of the parallel solver, including the calling thread.
+    /// Defaults to 9, the calling thread and up to 8 spawned threads, as before the limit was
+    /// configurable.
+    pub max_threads: usize,
+}
+
+impl Default for HeldKarpConfig {
+    fn default() -> Self {
+        Self {
+            initial_max_iterations: INITIAL_MAX_ITERATIONS,
+            max_iterations: MAX_ITERATIONS,
+            initial_alpha: INITIAL_ALPHA,
+            initial_beta: INITIAL_BETA,
+            beta: BETA,
+            min_step_factor: 1.0,
+            max_threads: MAX_THREADS,
+        }
+    }
+}
+
+impl HeldKarpConfig {
+    /// Fewer and bigger subgradient steps. The bounds are weaker, so more nodes are explored, but
+    /// every node is cheaper. Suited for small instances.
+    pub fn fast() -> Self {
+        Self {
+            initial_max_iterations: 200,
+            max_iterations: 5,
+            initial_beta: 0.97,
+            beta: 0.8,
+            min_step_factor: 4.0,
+            ..Self::default()
+        }
+    }
+
+    /// More and smaller subgradient steps. Every node is more expensive, but the tighter bounds
+    /// prune more of the search tree. Suited for big or hard instances.
+    pub fn thorough() -> Self {
+        Self {
+            initial_max_iterations: 5_000,
+            max_iterations: 30,
+            initial_beta: 0.995,
+            beta: 0.95,
+            ..Self::default()
+        }
+    }
+
+    /// Checks that all parameters are in their valid ranges.
+    pub fn validate(&self) -> Result<(), HeldKarpConfigError> {
+        if self.initial_max_iterations == 0 {
+            return Err(HeldKarpConfigError::ZeroIterations(
+                "initial_max_iterations",
+            ));
+        }
+        if self.max_iterations == 0 {
+            return Err(HeldKarpConfigError::ZeroIterations("max_iterations"));
+        }
+        if !(self.initial_alpha.is_finite() && self.initial_alpha > 0.0) {
+            return Err(HeldKarpConfigError::InvalidAlpha(self.initial_alpha));
+        }
+        for (field, beta) in [("initial_beta", self.initial_beta), ("beta", self.beta)] {
+            if !(beta > 0.0 && beta <= 1.0) {
+                return Err(HeldKarpConfigError::InvalidBeta { field, value: beta });
+            }
+        }
+        if !(self.min_step_factor.is_finite() && self.min_step_factor >= 0.0) {
+            return Err(HeldKarpConfigError::InvalidMinStepFactor(
+                self.min_step_factor,
+            ));
+        }
+        if self.max_threads == 0 {
+            return Err(HeldKarpConfigError::ZeroThreads);
+        }
+        Ok(())
+    }
+
+    /// Returns the maximum number of iterations and the factor `beta` at the given depth.
+    pub(crate) fn iteration_parameters(&self, depth: usize) -> (usize, f64) {
+        if depth == 0 {
+            (self.initial_max_iterations, self.initial_beta)
+        } else {
+            (self.max_iterations, self.beta)
+        }
+    }
+}
+
+/// Reasons why a [`HeldKarpConfig`] is invalid.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum HeldKarpConfigError {
+    /// A maximum number of iterations is 0.
+    #[error("{0} has to be at least 1")]
+    ZeroIterations(&'static str),
+    /// The initial step size factor is not positive and finite.
+    #[error("initial_alpha has to be positive and finite, but is {0}")]
+    InvalidAlpha(f64),
+    /// A step size decrease factor is not in `(0, 1]`.
+    #[error("{field} has to be in (0, 1], but is {value}")]
+    InvalidBeta {
+        /// The name of the offending field.
+        field: &'static str,
+        /// The value of the offending field.
+        value: f64,
+    },
+    /// The minimum step size factor is negative or not finite.
+    #[error("min_step_factor has to be non-negative and finite, but is {0}")]
+    InvalidMinStepFactor(f64),
+    /// The maximum number of threads is 0.
+    #[error("max_threads has to be at least 1")]
+    ZeroThreads,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_config() {
+        for config in [
+            HeldKarpConfig::default(),
+            HeldKarpConfig::fast(),
+            HeldKarpConfig::thorough(),
+        ] {
+            assert_eq!(config.validate(), Ok(()));
+        }
+
+        let config = HeldKarpConfig {
+            max_iterations: 0,
+            ..HeldKarpConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(HeldKarpConfigError::ZeroIterations("max_iterations"))
+        );
+
+        let config = HeldKarpConfig {
+            beta: 1.5,
+            ..HeldKarpConfig::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(HeldKarpConfigError::InvalidBeta {
+                field: "beta",
+                value: 1.5
+            })
+        );
+
+        let config = HeldKarpConfig {
+            initial_alpha: f64::NAN,
+            ..HeldKarpConfig::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(HeldKarpConfigError::InvalidAlpha(_))
+        ));
+
+        let config = HeldKarpConfig {
+            max_threads: 0,
+            ..HeldKarpConfig::default()
+        };
+        assert_eq!(config.validate(), Err(HeldKarpConfigError::ZeroThreads));
+    }
+}
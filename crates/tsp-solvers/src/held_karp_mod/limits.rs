@@ -0,0 +1,167 @@
+use std::{
+    sync::{
+        Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use tsp_core::instance::{UnTour, distance::DistanceValue};
+
+use crate::solver::{CancellationToken, Solution, SolverOptions, SolverStats, TerminationReason};
+
+/// Counts the explored branch-and-bound nodes and decides when the search stops early.
+///
+/// One instance is shared by all threads of a search. Besides the node count, it keeps the
+/// smallest lower bound of the nodes that were skipped because the search stopped, since these are
+/// what the proven lower bound of an interrupted search is made of.
+pub(crate) struct SearchLimits<D> {
+    deadline: Option<Instant>,
+    node_limit: Option<usize>,
+    cancellation: Option<CancellationToken>,
+    upper_bound: Option<D>,
+    nodes: AtomicUsize,
+    stopped: OnceLock<TerminationReason>,
+    /// The smallest lower bound of the parents of all skipped nodes.
+    skipped_bound: Mutex<Option<D>>,
+    /// Whether the root was skipped, in which case no lower bound is known.
+    skipped_root: AtomicBool,
+}
+
+impl<D: DistanceValue> SearchLimits<D> {
+    pub(crate) fn new(options: &SolverOptions<D>) -> Self {
+        Self {
+            deadline: options.deadline,
+            node_limit: options.node_limit,
+            cancellation: options.cancellation.clone(),
+            upper_bound: options.upper_bound,
+            nodes: AtomicUsize::new(0),
+            stopped: OnceLock::new(),
+            skipped_bound: Mutex::new(None),
+            skipped_root: AtomicBool::new(false),
+        }
+    }
+
+    /// Counts a new node, or returns `false` if the search has stopped and the node has to be
+    /// skipped. `parent_bound` is the lower bound of the parent node, `None` for the root.
+    pub(crate) fn enter_node(&self, parent_bound: Option<D>) -> bool {
+        let within_node_limit = || {
+            let nodes = self.nodes.fetch_add(1, Ordering::Relaxed);
+            if self.node_limit.is_some_and(|limit| nodes >= limit) {
+                self.nodes.fetch_sub(1, Ordering::Relaxed);
+                self.stop(TerminationReason::NodeLimit);
+                false
+            } else {
+                true
+            }
+        };
+
+        if !self.is_stopped() && within_node_limit() {
+            return true;
+        }
+        match parent_bound {
+            Some(bound) => {
+                let mut skipped_bound = self.skipped_bound.lock().unwrap();
+                if skipped_bound.is_none_or(|skipped_bound| bound < skipped_bound) {
+                    *skipped_bound = Some(bound);
+                }
+            }
+            None => self.skipped_root.store(true, Ordering::Relaxed),
+        }
+        false
+    }
+
+    /// Returns whether the search has to stop, checking the deadline and the cancellation token.
+    pub(crate) fn is_stopped(&self) -> bool {
+        if self.stopped.get().is_some() {
+            return true;
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.stop(TerminationReason::TimeLimit);
+        } else if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            self.stop(TerminationReason::Cancelled);
+        }
+        self.stopped.get().is_some()
+    }
+
+    /// Records the reason the search stopped, unless it already stopped for another reason.
+    fn stop(&self, reason: TerminationReason) {
+        let _ = self.stopped.set(reason);
+    }
+
+    /// Returns the number of explored nodes.
+    pub(crate) fn nodes(&self) -> usize {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Creates the solution from the best tour found by the search.
+    ///
+    /// If no node was skipped, the whole search space was explored, even if the search stopped
+    /// during the last lower bound computations. The tour is optimal in this case, unless it costs
+    /// more than the given upper bound. Then the search only proved that no tour is that cheap.
+    pub(crate) fn into_solution(self, tour: Option<UnTour<D>>, elapsed: Duration) -> Solution<D> {
+        let stats = SolverStats {
+            nodes: self.nodes(),
+            elapsed,
+            ..SolverStats::default()
+        };
+        let skipped_bound = self.skipped_bound.into_inner().unwrap();
+        let skipped_root = self.skipped_root.into_inner();
+        let cost = tour.as_ref().map(|tour| tour.cost);
+
+        let (lower_bound, termination) = match self.stopped.into_inner() {
+            Some(reason) if skipped_root => (None, reason),
+            Some(reason) if skipped_bound.is_some() => {
+                // Nodes pruned by the given upper bound only prove that their tours cost more than
+                // the upper bound, not more than the bounds of the skipped nodes
+                let lower_bound = [skipped_bound, cost, self.upper_bound]
+                    .into_iter()
+                    .flatten()
+                    .min();
+                (lower_bound, reason)
+            }
+            _ if cost.is_some_and(|cost| self.upper_bound.is_some_and(|bound| cost > bound)) => {
+                (self.upper_bound, TerminationReason::UpperBoundTooLow)
+            }
+            _ if cost.is_some() => (cost, TerminationReason::Optimal),
+            _ => (None, TerminationReason::Infeasible),
+        };
+
+        Solution {
+            tour,
+            lower_bound,
+            termination,
+            stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tsp_core::instance::distance::Distance;
+
+    use super::*;
+
+    #[test]
+    fn test_interrupted_lower_bound_respects_upper_bound() {
+        let options = SolverOptions {
+            node_limit: Some(1),
+            upper_bound: Some(Distance(10)),
+            ..SolverOptions::default()
+        };
+        let limits = SearchLimits::new(&options);
+        assert!(limits.enter_node(None));
+        assert!(!limits.enter_node(Some(Distance(12))));
+
+        let solution = limits.into_solution(None, Duration::ZERO);
+        assert_eq!(solution.termination, TerminationReason::NodeLimit);
+        assert_eq!(solution.lower_bound, Some(Distance(10)));
+    }
+}
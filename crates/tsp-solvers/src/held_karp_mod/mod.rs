@@ -38,6 +38,26 @@ that they are called by the function above them.
         - `explore_node`:   Is called twice (recursively) to explore the branches including or excluding
                             the selected edge.
 
+The lower bound at the root of the search is also available on its own via `held_karp_bound`, which
+returns the final node penalties, the 1-tree and the progress of every iteration.
+
+The parameters of the subgradient ascent (iteration counts, step sizes) and the number of threads of
+the parallel variant can be tuned with a `HeldKarpConfig`, which comes with `fast` and `thorough`
+presets. It is passed to the solvers `HeldKarp` and `HeldKarpParallel` and to `held_karp_bound`.
+
+Through the `Solver` trait, a search can be stopped early by a deadline, a node limit or a
+cancellation token (see `SolverOptions`). It then returns the best tour found so far and the
+smallest lower bound of the nodes it did not explore.
+
+The search starts from a tour built by the cheap heuristics of the
+[`heuristics`](crate::heuristics) module (nearest neighbor followed by 2-opt), so that its cost
+prunes the search right from the root. A known tour or upper bound can be passed in the
+`SolverOptions` instead.
+
+The progress of a search (new best tours, improvements of the root bound and every entered node
+with the prune counts) can be followed with an observer, see the [`observer`](crate::observer)
+module.
+
 ## 1-trees
 
 1-trees are minimum spanning trees that span nodes 2 to n, plus two minimum cost edges
@@ -78,76 +98,243 @@ by forcibly including or excluding edges.
 
 */
 
-use std::u32;
+use std::{time::Instant, u32};
 
 use log::{debug, info, trace};
 use tsp_core::instance::{
     UnTour,
-    distance::{Distance, ScaledDistance},
+    distance::{DistanceValue, ScaledValue},
     edge::UnEdge,
-    matrix::Matrix,
+    matrix::MatrixSym,
     node::Node,
+    oracle::DistanceOracle,
 };
 
-pub use crate::held_karp_mod::{parallel::held_karp_parallel, trees::min_one_tree};
+pub use crate::held_karp_mod::{
+    bound::{BoundIteration, BoundResult, held_karp_bound},
+    config::{HeldKarpConfig, HeldKarpConfigError},
+    parallel::{HeldKarpParallel, held_karp_parallel},
+    trees::min_one_tree,
+};
+use crate::{
+    held_karp_mod::{limits::SearchLimits, progress::SearchProgress},
+    heuristics::nearest_neighbor_two_opt,
+    observer::{NoObserver, SolverObserver},
+    solver::{Solution, Solver, SolverOptions, SolverOptionsError},
+};
 
+mod bound;
+mod config;
+mod limits;
 mod parallel;
+mod progress;
 mod trees;
 
 /// Solve the Traveling Salesman Problem using the Held-Karp algorithm.
 ///
 /// For a detailed explanation of the algorithm, see the [module-level
 /// documentation][crate::held_karp_mod].
-pub fn held_karp(distances: &Matrix<Distance>) -> Option<UnTour> {
-    info!("Starting Held-Karp solver");
-    let mut edge_states = Matrix::new(
-        vec![EdgeState::Available; distances.data().len()],
-        distances.dimension(),
-    );
+///
+/// The solver is generic over the distance type, see
+/// [`DistanceValue`]. `Distance` (`i32`) is the
+/// fastest choice, use `Distance64` or `FloatDistance` if the tour lengths overflow `i32`.
+///
+/// The distances can be given in any [`DistanceOracle`], e.g. a `Matrix`, a `MatrixSym` (half the
+/// memory for symmetric instances) or a whole `TSPSymInstance`. They are read through the oracle
+/// and scaled on the fly, so no copy of the distances is made, and the edge states of the search
+/// are kept in a lower triangular matrix.
+pub fn held_karp<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+) -> Option<UnTour<D>> {
+    run_search(
+        distances,
+        &HeldKarpConfig::default(),
+        &SolverOptions::default(),
+        &NoObserver,
+        |initial_tour, context| branch_and_bound(distances, initial_tour, context),
+    )
+    .tour
+}
 
-    let scaled_distances = Matrix::new(
-        distances
-            .data()
-            .iter()
-            .map(|&d| ScaledDistance::from_distance(d))
-            .collect(),
-        distances.dimension(),
-    );
+/// The Held-Karp algorithm as a [`Solver`], see [`held_karp`].
+#[derive(Debug, Clone, Default)]
+pub struct HeldKarp {
+    config: HeldKarpConfig,
+}
 
-    let mut node_penalties = initial_penalties(&scaled_distances, distances.dimension());
-    let mut fixed_degrees = vec![0u32; distances.dimension()];
-    let mut bb_counter = 0;
-
-    let mut initial_upper_bound = Distance(0);
-    let mut initial_tour = Vec::with_capacity(distances.dimension());
-    for i in 0..distances.dimension() {
-        initial_tour.push(UnEdge {
-            from: Node(i),
-            to: Node((i + 1) % distances.dimension()),
-        });
-        initial_upper_bound += distances.get_data(Node(i), Node((i + 1) % distances.dimension()));
+impl HeldKarp {
+    /// The name of the solver.
+    pub const NAME: &str = "held-karp";
+
+    /// Creates the solver with the given parameters, or returns an error if they are invalid.
+    pub fn new(config: HeldKarpConfig) -> Result<Self, HeldKarpConfigError> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+
+    /// Returns the parameters of the solver.
+    pub fn config(&self) -> &HeldKarpConfig {
+        &self.config
+    }
+}
+
+impl<D: DistanceValue, O: DistanceOracle<Value = D>> Solver<O> for HeldKarp {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn solve_with_observer(
+        &self,
+        distances: &O,
+        options: &SolverOptions<D>,
+        observer: &dyn SolverObserver<D>,
+    ) -> Result<Solution<D>, SolverOptionsError> {
+        options.validate(distances.dimension())?;
+        Ok(run_search(
+            distances,
+            &self.config,
+            options,
+            observer,
+            |initial_tour, context| branch_and_bound(distances, initial_tour, context),
+        ))
     }
-    let mut best_tour = Some(UnTour {
-        edges: initial_tour,
-        cost: initial_upper_bound,
-    });
+}
+
+/// Runs the branch-and-bound search of [`held_karp`] starting from the given tour and returns the
+/// best tour found before the search finished or stopped.
+fn branch_and_bound<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    initial_tour: UnTour<D>,
+    context: &SearchContext<D>,
+) -> Option<UnTour<D>> {
+    info!("Starting Held-Karp solver");
+    let mut edge_states =
+        MatrixSym::new_from_dimension_with_value(distances.dimension(), EdgeState::Available);
+
+    let mut node_penalties = initial_penalties(distances);
+    let mut fixed_degrees = vec![0u32; distances.dimension()];
+
+    let mut initial_upper_bound = initial_tour.cost;
+    let mut best_tour = Some(initial_tour);
 
     explore_node(
         distances,
-        &scaled_distances,
         &mut edge_states,
         node_penalties.as_mut_slice(),
         fixed_degrees.as_mut_slice(),
         &mut initial_upper_bound,
         &mut best_tour,
-        &mut bb_counter,
         None,
         0,
+        context,
     );
+    debug!("Explored {} branch-and-bound nodes", context.limits.nodes());
 
     best_tour
 }
 
+/// The parameters, limits and progress shared by all nodes (and threads) of a search.
+struct SearchContext<'a, D> {
+    config: &'a HeldKarpConfig,
+    limits: &'a SearchLimits<D>,
+    progress: &'a SearchProgress<'a, D>,
+    /// The upper bound on the optimum given in the [`SolverOptions`], if any.
+    upper_bound: Option<D>,
+}
+
+impl<D: DistanceValue> SearchContext<'_, D> {
+    /// Returns whether a node with the given lower bound can be pruned, given the cost of the best
+    /// tour found so far.
+    fn can_prune(&self, lower_bound: D, best_cost: D) -> bool {
+        lower_bound >= best_cost || self.upper_bound.is_some_and(|bound| lower_bound > bound)
+    }
+
+    /// Returns the value the subgradient ascent steps towards, which is the cost of the best tour
+    /// or the given upper bound if that is smaller.
+    fn ascent_target(&self, best_cost: D) -> D::Scaled {
+        self.upper_bound
+            .map_or(best_cost, |bound| bound.min(best_cost))
+            .to_scaled()
+    }
+}
+
+/// Runs a branch-and-bound search and creates the solution from the tour it returns.
+///
+/// The search starts from the initial tour of the options, the tour of
+/// [`nearest_neighbor_two_opt`] if the options give neither a tour nor an upper bound, and the
+/// tour `0 -> 1 -> ... -> n-1` otherwise. The options have to be validated against the instance,
+/// see [`SolverOptions::validate`].
+fn run_search<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    config: &HeldKarpConfig,
+    options: &SolverOptions<D>,
+    observer: &dyn SolverObserver<D>,
+    search: impl FnOnce(UnTour<D>, &SearchContext<D>) -> Option<UnTour<D>>,
+) -> Solution<D> {
+    let start = Instant::now();
+    let limits = SearchLimits::new(options);
+    let progress = SearchProgress::new(observer);
+
+    let initial_tour = match &options.initial_tour {
+        Some(tour) => {
+            let mut tour = tour.clone();
+            tour.recompute_cost(|from, to| distances.get(from, to));
+            tour.into()
+        }
+        // Instances with fewer than 3 nodes have only one tour
+        None if options.upper_bound.is_none() && distances.dimension() >= 3 => {
+            nearest_neighbor_two_opt(distances).into()
+        }
+        None => identity_tour(distances),
+    };
+    debug!(
+        "Starting the search from a tour with cost {}",
+        initial_tour.cost
+    );
+    if progress.is_observed() {
+        progress.new_incumbent(initial_tour.clone());
+    }
+
+    let tour = search(
+        initial_tour,
+        &SearchContext {
+            config,
+            limits: &limits,
+            progress: &progress,
+            upper_bound: options.upper_bound,
+        },
+    );
+
+    let mut solution = limits.into_solution(tour, start.elapsed());
+    solution.stats.pruned = progress.pruned_count();
+    solution.stats.infeasible = progress.infeasible_count();
+    if let (Some(lower_bound), Some(root_bound)) =
+        (solution.lower_bound, progress.best_lower_bound())
+    {
+        // The bound of the root holds for all tours, even if the search stopped before the bounds
+        // of its descendants exceeded it
+        solution.lower_bound = Some(lower_bound.max(root_bound));
+    }
+    if let Some(lower_bound) = solution.lower_bound {
+        progress.lower_bound(lower_bound);
+    }
+    solution
+}
+
+/// Returns the tour visiting the nodes in the order of their indices.
+fn identity_tour<D: DistanceValue, O: DistanceOracle<Value = D>>(distances: &O) -> UnTour<D> {
+    let dimension = distances.dimension();
+    let mut cost = D::ZERO;
+    let mut edges = Vec::with_capacity(dimension);
+    for i in 0..dimension {
+        let (from, to) = (Node(i), Node((i + 1) % dimension));
+        edges.push(UnEdge { from, to });
+        cost += distances.get(from, to);
+    }
+    UnTour { edges, cost }
+}
+
+// The default parameters, see `HeldKarpConfig`
 const INITIAL_MAX_ITERATIONS: usize = 1_000;
 const MAX_ITERATIONS: usize = 10;
 
@@ -156,6 +343,9 @@ const INITIAL_ALPHA: f64 = 2.0;
 const INITIAL_BETA: f64 = 0.99;
 const BETA: f64 = 0.9;
 
+// The calling thread plus the 8 threads the parallel solver has always been allowed to spawn
+const MAX_THREADS: usize = 9;
+
 #[repr(i8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// State of an edge in the branch-and-bound search.
@@ -174,70 +364,64 @@ pub enum EdgeState {
 ///
 /// TODO: Summarize arguments in Held-Karp State Struct or Smth
 /// TODO: Possibly remove upper_bound as best_tour.cost already contains that information
-fn explore_node(
-    distances: &Matrix<Distance>,
-    scaled_distances: &Matrix<ScaledDistance>,
-    edge_states: &mut Matrix<EdgeState>,
-    node_penalties: &mut [ScaledDistance],
+fn explore_node<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    edge_states: &mut MatrixSym<EdgeState>,
+    node_penalties: &mut [D::Scaled],
     fixed_degrees: &mut [u32],
-    upper_bound: &mut Distance,
-    best_tour: &mut Option<UnTour>,
-    bb_counter: &mut usize,
-    bb_limit: Option<usize>,
+    upper_bound: &mut D,
+    best_tour: &mut Option<UnTour<D>>,
+    parent_bound: Option<D>,
     depth: usize,
+    context: &SearchContext<D>,
 ) {
-    // Increment the branch count
-    *bb_counter += 1;
-
-    if let Some(limit) = bb_limit {
-        if *bb_counter >= limit {
-            return;
-        }
+    // Count the node, or skip it if the search has stopped
+    if !context.limits.enter_node(parent_bound) {
+        return;
     }
+    context.progress.node_entered(context.limits.nodes(), depth);
 
-    let (max_iterations, beta) = if depth == 0 {
-        (INITIAL_MAX_ITERATIONS, INITIAL_BETA)
-    } else {
-        (MAX_ITERATIONS, BETA)
-    };
-
-    let one_tree = match held_karp_lower_bound(
+    let (lower_bound, one_tree) = match held_karp_lower_bound(
         distances,
-        scaled_distances,
         edge_states,
         node_penalties,
         *upper_bound,
-        max_iterations,
-        beta,
+        depth,
+        context,
+        None,
     ) {
         Some(LowerBoundOutput::Tour(tour)) => {
             // Found a new tour, that is, an upper bound
-            debug!("Found a new best tour with cost {}", tour.cost.0);
+            debug!("Found a new best tour with cost {}", tour.cost);
+            if context.progress.is_observed() {
+                context.progress.new_incumbent(tour.clone());
+            }
             *upper_bound = tour.cost;
             *best_tour = Some(tour);
             return;
         }
         Some(LowerBoundOutput::LowerBound(lower_bound, one_tree)) => {
             // Check if the lower bound is better than the current best cost
-            if lower_bound >= *upper_bound {
+            if context.can_prune(lower_bound, *upper_bound) {
                 // Prune this node, as we have already found a better tour than the lower bound
                 trace!(
                     "Pruning node with lower bound {} >= upper bound {}",
-                    lower_bound.0, upper_bound.0
+                    lower_bound, upper_bound
                 );
+                context.progress.pruned();
                 return;
             } else {
-                one_tree
+                (lower_bound, one_tree)
             }
         }
         None => {
             // Infeasible node, prune
+            context.progress.infeasible();
             return;
         }
     };
 
-    let Some(branching_edge) =
-        edge_to_branch_on(scaled_distances, edge_states, node_penalties, &one_tree)
+    let Some(branching_edge) = edge_to_branch_on(distances, edge_states, node_penalties, &one_tree)
     else {
         // No edge to branch on, so we prune
         return;
@@ -245,97 +429,91 @@ fn explore_node(
 
     // Explore the branch excluding the edge
     {
-        edge_states.set_data_symmetric(branching_edge.from, branching_edge.to, EdgeState::Excluded);
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Excluded);
 
         explore_node(
             distances,
-            scaled_distances,
             edge_states,
             node_penalties,
             fixed_degrees,
             upper_bound,
             best_tour,
-            bb_counter,
-            bb_limit,
+            Some(lower_bound),
             depth + 1,
+            context,
         );
 
-        edge_states.set_data_symmetric(
-            branching_edge.from,
-            branching_edge.to,
-            EdgeState::Available,
-        );
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
     }
 
     // Try exploring the branch including the edge.
     // That is, we might not be able to explore this branch, if we the edge inclusion would violate
     // the already fixed degrees / edges.
     if (fixed_degrees[branching_edge.from.0] < 2) && (fixed_degrees[branching_edge.to.0] < 2) {
-        edge_states.set_data_symmetric(branching_edge.from, branching_edge.to, EdgeState::Fixed);
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Fixed);
         fixed_degrees[branching_edge.from.0] += 1;
         fixed_degrees[branching_edge.to.0] += 1;
 
         explore_node(
             distances,
-            scaled_distances,
             edge_states,
             node_penalties,
             fixed_degrees,
             upper_bound,
             best_tour,
-            bb_counter,
-            bb_limit,
+            Some(lower_bound),
             depth + 1,
+            context,
         );
 
         // Backtrack
-        edge_states.set_data_symmetric(
-            branching_edge.from,
-            branching_edge.to,
-            EdgeState::Available,
-        );
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
         fixed_degrees[branching_edge.from.0] -= 1;
         fixed_degrees[branching_edge.to.0] -= 1;
     }
 }
 
-enum LowerBoundOutput {
-    LowerBound(Distance, Vec<UnEdge>),
-    Tour(UnTour),
+enum LowerBoundOutput<D> {
+    LowerBound(D, Vec<UnEdge>),
+    Tour(UnTour<D>),
 }
 
 /// Compute Held-Karp lower bound using 1-trees and Lagrangian relaxation
-fn held_karp_lower_bound(
-    distances: &Matrix<Distance>,
-    scaled_distances: &Matrix<ScaledDistance>,
-    edge_states: &Matrix<EdgeState>,
-    node_penalties: &mut [ScaledDistance],
-    upper_bound: Distance,
-    max_iterations: usize,
-    beta: f64,
-) -> Option<LowerBoundOutput> {
-    let scaled_upper_bound = ScaledDistance::from_distance(upper_bound);
+fn held_karp_lower_bound<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    edge_states: &MatrixSym<EdgeState>,
+    node_penalties: &mut [D::Scaled],
+    upper_bound: D,
+    depth: usize,
+    context: &SearchContext<D>,
+    mut trace: Option<&mut Vec<BoundIteration<D>>>,
+) -> Option<LowerBoundOutput<D>> {
+    let config = context.config;
+    let (max_iterations, beta) = config.iteration_parameters(depth);
+    let scaled_upper_bound = upper_bound.to_scaled();
+    let scaled_target = context.ascent_target(upper_bound);
+    let min_step = min_step::<D>(config);
 
     // Tracks the current best lower bound found
-    let mut scaled_best_lower_bound = ScaledDistance::MIN;
+    let mut scaled_best_lower_bound = D::Scaled::MIN;
 
     let mut iter_count = 0;
 
-    let mut alpha = INITIAL_ALPHA;
+    let mut alpha = config.initial_alpha;
 
-    let node_penalty_sum: ScaledDistance = node_penalties.iter().sum();
+    let node_penalty_sum: D::Scaled = node_penalties.iter().sum();
 
     let one_tree = loop {
-        let one_tree = min_one_tree(scaled_distances, edge_states, node_penalties)?;
+        let one_tree = min_one_tree(distances, edge_states, node_penalties)?;
 
         // Compute the cost of the 1-tree with penalties. This is simultaneously the value of
         // the lagrangian relaxation and thus a lower bound (possibly an upper bound too, if it is a
         // tour).
         let one_tree_cost = {
-            let mut base_cost = 2 * node_penalty_sum;
+            let mut base_cost = node_penalty_sum.times(2);
 
             for edge in &one_tree {
-                base_cost += scaled_distances.get_data(edge.from, edge.to);
+                base_cost += distances.get(edge.from, edge.to).to_scaled();
                 base_cost -= node_penalties[edge.from.0];
                 base_cost -= node_penalties[edge.to.0];
             }
@@ -345,15 +523,12 @@ fn held_karp_lower_bound(
 
         if one_tree_cost > scaled_best_lower_bound {
             scaled_best_lower_bound = one_tree_cost;
-        }
-
-        if one_tree_cost >= scaled_upper_bound {
-            // Lower bound exceeds current upper bound, prune
-            trace!(
-                "Pruning in held_karp_lower_bound due to lower bound {} >= upper bound {}",
-                one_tree_cost.0, scaled_upper_bound.0
-            );
-            break one_tree;
+            if depth == 0 {
+                // Without fixed or excluded edges, the bound of the root holds for all tours
+                context
+                    .progress
+                    .lower_bound(D::from_scaled_rounded_up(one_tree_cost));
+            }
         }
 
         // Next we check the degrees of the nodes in the 1-tree
@@ -372,11 +547,28 @@ fn held_karp_lower_bound(
 
         let square_sum = deg.iter().map(|&d| d * d).sum::<i32>();
 
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.push(BoundIteration {
+                bound: D::from_scaled_rounded_up(one_tree_cost),
+                degree_deviation: square_sum as u32,
+                step_size: D::Scaled::ZERO,
+            });
+        }
+
+        if one_tree_cost >= scaled_upper_bound {
+            // Lower bound exceeds current upper bound, prune
+            trace!(
+                "Pruning in held_karp_lower_bound due to lower bound {:?} >= upper bound {:?}",
+                one_tree_cost, scaled_upper_bound
+            );
+            break one_tree;
+        }
+
         if square_sum == 0 {
             // Found a tour
-            let cost: Distance = one_tree
+            let cost: D = one_tree
                 .iter()
-                .map(|edge| distances.get_data(edge.from, edge.to))
+                .map(|edge| distances.get(edge.from, edge.to))
                 .sum();
 
             return Some(LowerBoundOutput::Tour(UnTour {
@@ -385,6 +577,11 @@ fn held_karp_lower_bound(
             }));
         }
 
+        if one_tree_cost >= scaled_target {
+            // The bound reached the given upper bound, so the ascent has nothing to step towards
+            break one_tree;
+        }
+
         // We have not found a tour yet, so we want to update the penalties
         iter_count += 1;
 
@@ -393,48 +590,63 @@ fn held_karp_lower_bound(
             break one_tree;
         }
 
+        if context.limits.is_stopped() {
+            // The search stops, but the best bound so far is still valid
+            break one_tree;
+        }
+
         // TODO: Research on subgradient method for non-smooth optimization to find out more about
         // this
-        let step_size = (alpha
-            * ((scaled_upper_bound.0 - one_tree_cost.0) as f64 / (square_sum as f64)))
-            as i32;
+        let step_size = D::Scaled::from_raw_f64(
+            alpha * ((scaled_target - one_tree_cost).to_raw_f64() / (square_sum as f64)),
+        );
 
-        if step_size <= 3 {
-            // Step size is very small (<= 3 in scaled), we probably won't be making much progress
+        if step_size <= min_step {
+            // Step size is very small, we probably won't be making much progress
             break one_tree;
         }
 
+        if let Some(iteration) = trace.as_deref_mut().and_then(|trace| trace.last_mut()) {
+            iteration.step_size = step_size;
+        }
+
         alpha *= beta;
 
         // Update penalties based on degree deviations and step size
-        // TODO: Handle overflows
+        // Overflows panic in debug builds and saturate in release builds, see `ScaledValue`
         for (node_penalty, &d) in node_penalties.iter_mut().zip(deg.iter()) {
-            let adjustment = ScaledDistance(step_size * d);
+            let adjustment = step_size.times(d);
             *node_penalty += adjustment;
         }
     };
 
-    let best_lower_bound = scaled_best_lower_bound.to_distance_rounded_up();
+    let best_lower_bound = D::from_scaled_rounded_up(scaled_best_lower_bound);
 
     Some(LowerBoundOutput::LowerBound(best_lower_bound, one_tree))
 }
 
+/// Returns the step size at which the subgradient ascent stops, see
+/// [`HeldKarpConfig::min_step_factor`].
+fn min_step<D: DistanceValue>(config: &HeldKarpConfig) -> D::Scaled {
+    D::Scaled::from_raw_f64(D::Scaled::MIN_STEP.to_raw_f64() * config.min_step_factor)
+}
+
 /// Select an edge from the 1-tree to branch on.
 ///
 /// The edge with the minimum reduced cost (edge_cost - node_penalties[from] - node_penalties[to])
 /// among available edges is selected for branching.
-fn edge_to_branch_on(
-    scaled_distances: &Matrix<ScaledDistance>,
-    edge_states: &Matrix<EdgeState>,
-    node_penalties: &[ScaledDistance],
+fn edge_to_branch_on<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    edge_states: &MatrixSym<EdgeState>,
+    node_penalties: &[D::Scaled],
     one_tree: &[UnEdge],
 ) -> Option<UnEdge> {
     let mut minimum_edge = None;
-    let mut minimum_edge_distance = ScaledDistance::MAX;
+    let mut minimum_edge_distance = D::Scaled::MAX;
 
     for edge in one_tree {
         if edge_states.get_data(edge.from, edge.to) == EdgeState::Available {
-            let reduced_distance = scaled_distances.get_data(edge.from, edge.to)
+            let reduced_distance = distances.get(edge.from, edge.to).to_scaled()
                 - node_penalties[edge.from.0]
                 - node_penalties[edge.to.0];
             if reduced_distance < minimum_edge_distance {
@@ -450,15 +662,15 @@ fn edge_to_branch_on(
 /// Initializes node penalties for Lagrangian relaxation.
 ///
 /// Node penalties are set to half the minimum distances to other nodes.
-fn initial_penalties(
-    scaled_distances: &Matrix<ScaledDistance>,
-    dimension: usize,
-) -> Vec<ScaledDistance> {
-    let mut penalties = vec![ScaledDistance::MAX; dimension];
+fn initial_penalties<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+) -> Vec<D::Scaled> {
+    let dimension = distances.dimension();
+    let mut penalties = vec![D::Scaled::MAX; dimension];
 
     for from in 0..dimension {
         for to in 0..from {
-            let distance = scaled_distances.get_data_to_seq(Node(from), Node(to));
+            let distance = distances.get(Node(from), Node(to)).to_scaled();
             if distance < penalties[from] {
                 penalties[from] = distance;
             }
@@ -469,7 +681,7 @@ fn initial_penalties(
     }
 
     for penalty in penalties.iter_mut() {
-        *penalty = *penalty / 2;
+        *penalty = penalty.half();
     }
 
     penalties
@@ -6,15 +6,19 @@ use std::{
 use log::{debug, info, trace};
 use tsp_core::instance::{
     UnTour,
-    distance::{Distance, ScaledDistance},
+    distance::{DistanceValue, ScaledValue},
     edge::UnEdge,
-    matrix::Matrix,
-    node::Node,
+    matrix::MatrixSym,
+    oracle::DistanceOracle,
 };
 
-use crate::held_karp_mod::{
-    BETA, EdgeState, INITIAL_ALPHA, INITIAL_BETA, INITIAL_MAX_ITERATIONS, MAX_ITERATIONS,
-    edge_to_branch_on, initial_penalties, min_one_tree,
+use crate::{
+    held_karp_mod::{
+        EdgeState, HeldKarpConfig, HeldKarpConfigError, SearchContext, edge_to_branch_on,
+        initial_penalties, min_one_tree, min_step, run_search,
+    },
+    observer::{NoObserver, SolverObserver},
+    solver::{Solution, Solver, SolverOptions, SolverOptionsError},
 };
 
 ///  TODO: Adapt documentation
@@ -23,54 +27,93 @@ use crate::held_karp_mod::{
 ///
 /// For a detailed explanation of the algorithm, see the [module-level
 /// documentation][crate::held_karp_mod].
-pub fn held_karp_parallel(distances: &Matrix<Distance>) -> Option<UnTour> {
-    info!("Starting Held-Karp parallel solver for instance");
-    let mut edge_states = Matrix::new(
-        vec![EdgeState::Available; distances.data().len()],
-        distances.dimension(),
-    );
+pub fn held_karp_parallel<D: DistanceValue, O: DistanceOracle<Value = D> + Sync>(
+    distances: &O,
+) -> Option<UnTour<D>> {
+    run_search(
+        distances,
+        &HeldKarpConfig::default(),
+        &SolverOptions::default(),
+        &NoObserver,
+        |initial_tour, context| branch_and_bound_parallel(distances, initial_tour, context),
+    )
+    .tour
+}
 
-    let scaled_distances = Matrix::new(
-        distances
-            .data()
-            .iter()
-            .map(|&d| ScaledDistance::from_distance(d))
-            .collect(),
-        distances.dimension(),
-    );
+/// The parallel Held-Karp algorithm as a [`Solver`], see [`held_karp_parallel`].
+#[derive(Debug, Clone, Default)]
+pub struct HeldKarpParallel {
+    config: HeldKarpConfig,
+}
 
-    let mut node_penalties = initial_penalties(&scaled_distances, distances.dimension());
-    let mut fixed_degrees = vec![0u32; distances.dimension()];
-    let mut bb_counter = 0;
-
-    let mut initial_upper_bound = Distance(0);
-    let mut initial_tour = Vec::with_capacity(distances.dimension());
-    for i in 0..distances.dimension() {
-        initial_tour.push(UnEdge {
-            from: Node(i),
-            to: Node((i + 1) % distances.dimension()),
-        });
-        initial_upper_bound += distances.get_data(Node(i), Node((i + 1) % distances.dimension()));
+impl HeldKarpParallel {
+    /// The name of the solver.
+    pub const NAME: &str = "held-karp-parallel";
+
+    /// Creates the solver with the given parameters, or returns an error if they are invalid.
+    pub fn new(config: HeldKarpConfig) -> Result<Self, HeldKarpConfigError> {
+        config.validate()?;
+        Ok(Self { config })
     }
-    let best_tour = Arc::new(Mutex::new(UnTour {
-        edges: initial_tour,
-        cost: initial_upper_bound,
-    }));
+
+    /// Returns the parameters of the solver.
+    pub fn config(&self) -> &HeldKarpConfig {
+        &self.config
+    }
+}
+
+impl<D: DistanceValue, O: DistanceOracle<Value = D> + Sync> Solver<O> for HeldKarpParallel {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn solve_with_observer(
+        &self,
+        distances: &O,
+        options: &SolverOptions<D>,
+        observer: &dyn SolverObserver<D>,
+    ) -> Result<Solution<D>, SolverOptionsError> {
+        options.validate(distances.dimension())?;
+        Ok(run_search(
+            distances,
+            &self.config,
+            options,
+            observer,
+            |initial_tour, context| branch_and_bound_parallel(distances, initial_tour, context),
+        ))
+    }
+}
+
+/// Runs the branch-and-bound search of [`held_karp_parallel`] starting from the given tour and
+/// returns the best tour found before the search finished or stopped.
+fn branch_and_bound_parallel<D: DistanceValue, O: DistanceOracle<Value = D> + Sync>(
+    distances: &O,
+    initial_tour: UnTour<D>,
+    context: &SearchContext<D>,
+) -> Option<UnTour<D>> {
+    info!("Starting Held-Karp parallel solver for instance");
+    let mut edge_states =
+        MatrixSym::new_from_dimension_with_value(distances.dimension(), EdgeState::Available);
+
+    let mut node_penalties = initial_penalties(distances);
+    let mut fixed_degrees = vec![0u32; distances.dimension()];
+
+    let best_tour = Arc::new(Mutex::new(initial_tour));
 
     let threads_spawned = Arc::new(Mutex::new(1usize));
 
     explore_node_new_thread(
         distances,
-        &scaled_distances,
         &mut edge_states,
         node_penalties.as_mut_slice(),
         fixed_degrees.as_mut_slice(),
         best_tour.clone(),
-        &mut bb_counter,
         None,
         0,
         threads_spawned,
+        context,
     );
+    debug!("Explored {} branch-and-bound nodes", context.limits.nodes());
 
     best_tour.lock().unwrap().clone().into()
 }
@@ -82,78 +125,80 @@ pub fn held_karp_parallel(distances: &Matrix<Distance>) -> Option<UnTour> {
 /// on an edge from the resulting 1-tree.
 ///
 /// TODO: Summarize arguments in Held-Karp State Struct or Smth
-fn explore_node_new_thread(
-    distances: &Matrix<Distance>,
-    scaled_distances: &Matrix<ScaledDistance>,
-    edge_states: &mut Matrix<EdgeState>,
-    node_penalties: &mut [ScaledDistance],
+fn explore_node_new_thread<D: DistanceValue, O: DistanceOracle<Value = D> + Sync>(
+    distances: &O,
+    edge_states: &mut MatrixSym<EdgeState>,
+    node_penalties: &mut [D::Scaled],
     fixed_degrees: &mut [u32],
-    best_tour: Arc<Mutex<UnTour>>,
-    bb_counter: &mut usize,
-    bb_limit: Option<usize>,
+    best_tour: Arc<Mutex<UnTour<D>>>,
+    parent_bound: Option<D>,
     depth: usize,
     threads_spawned: Arc<Mutex<usize>>,
+    context: &SearchContext<D>,
 ) {
-    // Increment the branch count
-    *bb_counter += 1;
-
-    if let Some(limit) = bb_limit {
-        if *bb_counter >= limit {
-            return;
-        }
+    // Count the node, or skip it if the search has stopped
+    if !context.limits.enter_node(parent_bound) {
+        return;
     }
+    context.progress.node_entered(context.limits.nodes(), depth);
 
-    let (max_iterations, beta) = if depth == 0 {
-        (INITIAL_MAX_ITERATIONS, INITIAL_BETA)
-    } else {
-        (MAX_ITERATIONS, BETA)
-    };
-
-    let one_tree = match held_karp_lower_bound_parallel(
+    let (lower_bound, one_tree) = match held_karp_lower_bound_parallel(
         distances,
-        scaled_distances,
         edge_states,
         node_penalties,
-        // Possibly pass Arc<Mutex<UnTour>> instead of copying the best tour cost each time
+        // Possibly pass Arc<Mutex<UnTour<D>>> instead of copying the best tour cost each time
         best_tour.clone(),
-        max_iterations,
-        beta,
+        depth,
+        context,
     ) {
         Some(LowerBoundOutput::Tour(tour)) => {
             // Found a new tour, that is, an upper bound
-            debug!("Found a new best tour with cost {}", tour.cost.0);
-            *best_tour.lock().unwrap() = tour;
+            let reported = context.progress.is_observed().then(|| tour.clone());
+            {
+                let mut best_tour = best_tour.lock().unwrap();
+                // Another thread might have found a better tour in the meantime
+                if tour.cost >= best_tour.cost {
+                    return;
+                }
+                debug!("Found a new best tour with cost {}", tour.cost);
+                *best_tour = tour;
+            }
+            // Notify without holding the lock, which all threads need for their bounds
+            if let Some(tour) = reported {
+                context.progress.new_incumbent(tour);
+            }
             return;
         }
         Some(LowerBoundOutput::LowerBound(lower_bound, one_tree)) => {
             let current_upper_bound = best_tour.lock().unwrap().cost;
             // Check if the lower bound is better than the current best cost
-            if lower_bound >= current_upper_bound {
+            if context.can_prune(lower_bound, current_upper_bound) {
                 // Prune this node, as we have already found a better tour than the lower bound
                 trace!(
                     "Pruning node with lower bound {} >= upper bound {}",
-                    lower_bound.0, current_upper_bound.0
+                    lower_bound, current_upper_bound
                 );
+                context.progress.pruned();
                 return;
             } else {
-                one_tree
+                (lower_bound, one_tree)
             }
         }
         None => {
             // Infeasible node, prune
+            context.progress.infeasible();
             return;
         }
     };
 
-    let Some(branching_edge) =
-        edge_to_branch_on(scaled_distances, edge_states, node_penalties, &one_tree)
+    let Some(branching_edge) = edge_to_branch_on(distances, edge_states, node_penalties, &one_tree)
     else {
         // No edge to branch on, so we prune
         return;
     };
 
     if (fixed_degrees[branching_edge.from.0] < 2) && (fixed_degrees[branching_edge.to.0] < 2) {
-        if *threads_spawned.lock().unwrap() <= 8 {
+        if *threads_spawned.lock().unwrap() < context.config.max_threads {
             // We can spawn a new thread which explores the branch excluding the edge
             *threads_spawned.lock().unwrap() += 1;
             thread::scope(|s| {
@@ -163,11 +208,10 @@ fn explore_node_new_thread(
                     let mut node_penalties_clone = node_penalties.to_vec();
                     let mut fixed_degrees_clone = fixed_degrees.to_vec();
                     let best_tour_handle = best_tour.clone();
-                    let mut bb_counter_clone = bb_counter.clone();
                     let threads_spawned_handle = threads_spawned.clone();
 
                     let thread_handle = s.spawn(move || {
-                        edge_states_clone.set_data_symmetric(
+                        edge_states_clone.set_data(
                             branching_edge.from,
                             branching_edge.to,
                             EdgeState::Excluded,
@@ -175,15 +219,14 @@ fn explore_node_new_thread(
 
                         explore_node_new_thread(
                             distances,
-                            scaled_distances,
                             &mut edge_states_clone,
                             &mut node_penalties_clone,
                             &mut fixed_degrees_clone,
                             best_tour_handle,
-                            &mut bb_counter_clone,
-                            bb_limit,
+                            Some(lower_bound),
                             depth + 1,
                             threads_spawned_handle,
+                            context,
                         );
                     });
 
@@ -194,59 +237,45 @@ fn explore_node_new_thread(
                 // That is, we might not be able to explore this branch, if we the edge inclusion
                 // would violate the already fixed degrees / edges.
                 // println!("Exploring inclusion at depth {}", depth);
-                edge_states.set_data_symmetric(
-                    branching_edge.from,
-                    branching_edge.to,
-                    EdgeState::Fixed,
-                );
+                edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Fixed);
                 fixed_degrees[branching_edge.from.0] += 1;
                 fixed_degrees[branching_edge.to.0] += 1;
 
                 explore_node_new_thread(
                     distances,
-                    scaled_distances,
                     edge_states,
                     node_penalties,
                     fixed_degrees,
                     best_tour,
-                    bb_counter,
-                    bb_limit,
+                    Some(lower_bound),
                     depth + 1,
                     threads_spawned.clone(),
+                    context,
                 );
 
                 // Backtrack
-                edge_states.set_data_symmetric(
-                    branching_edge.from,
-                    branching_edge.to,
-                    EdgeState::Available,
-                );
+                edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
                 fixed_degrees[branching_edge.from.0] -= 1;
                 fixed_degrees[branching_edge.to.0] -= 1;
             });
-            
+
             // Decrement the thread count
             *threads_spawned.lock().unwrap() -= 1;
         } else {
             // We cannot spawn a new thread, so we explore both branches in the current thread
             {
-                edge_states.set_data_symmetric(
-                    branching_edge.from,
-                    branching_edge.to,
-                    EdgeState::Excluded,
-                );
+                edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Excluded);
 
                 explore_node_new_thread(
                     distances,
-                    scaled_distances,
                     edge_states,
                     node_penalties,
                     fixed_degrees,
                     best_tour.clone(),
-                    bb_counter,
-                    bb_limit,
+                    Some(lower_bound),
                     depth + 1,
                     threads_spawned.clone(),
+                    context,
                 );
             }
 
@@ -256,33 +285,24 @@ fn explore_node_new_thread(
             if (fixed_degrees[branching_edge.from.0] < 2)
                 && (fixed_degrees[branching_edge.to.0] < 2)
             {
-                edge_states.set_data_symmetric(
-                    branching_edge.from,
-                    branching_edge.to,
-                    EdgeState::Fixed,
-                );
+                edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Fixed);
                 fixed_degrees[branching_edge.from.0] += 1;
                 fixed_degrees[branching_edge.to.0] += 1;
 
                 explore_node_new_thread(
                     distances,
-                    scaled_distances,
                     edge_states,
                     node_penalties,
                     fixed_degrees,
                     best_tour,
-                    bb_counter,
-                    bb_limit,
+                    Some(lower_bound),
                     depth + 1,
                     threads_spawned.clone(),
+                    context,
                 );
 
                 // Backtrack
-                edge_states.set_data_symmetric(
-                    branching_edge.from,
-                    branching_edge.to,
-                    EdgeState::Available,
-                );
+                edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
                 fixed_degrees[branching_edge.from.0] -= 1;
                 fixed_degrees[branching_edge.to.0] -= 1;
             }
@@ -290,24 +310,21 @@ fn explore_node_new_thread(
     } else {
         // We can only explore the branch excluding the edge.
         {
-            edge_states.set_data_symmetric(
-                branching_edge.from,
-                branching_edge.to,
-                EdgeState::Excluded,
-            );
+            edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Excluded);
 
             explore_node_new_thread(
                 distances,
-                scaled_distances,
                 edge_states,
                 node_penalties,
                 fixed_degrees,
                 best_tour.clone(),
-                bb_counter,
-                bb_limit,
+                Some(lower_bound),
                 depth + 1,
                 threads_spawned,
+                context,
             );
+
+            edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
         }
     }
 }
@@ -319,70 +336,72 @@ fn explore_node_new_thread(
 /// on an edge from the resulting 1-tree.
 ///
 /// TODO: Summarize arguments in Held-Karp State Struct or Smth
-fn explore_node_parallel(
-    distances: &Matrix<Distance>,
-    scaled_distances: &Matrix<ScaledDistance>,
-    edge_states: &mut Matrix<EdgeState>,
-    node_penalties: &mut [ScaledDistance],
+fn explore_node_parallel<D: DistanceValue, O: DistanceOracle<Value = D> + Sync>(
+    distances: &O,
+    edge_states: &mut MatrixSym<EdgeState>,
+    node_penalties: &mut [D::Scaled],
     fixed_degrees: &mut [u32],
-    best_tour: Arc<Mutex<UnTour>>,
-    bb_counter: &mut usize,
-    bb_limit: Option<usize>,
+    best_tour: Arc<Mutex<UnTour<D>>>,
+    parent_bound: Option<D>,
     depth: usize,
+    context: &SearchContext<D>,
 ) {
-    // Increment the branch count
-    *bb_counter += 1;
-
-    if let Some(limit) = bb_limit {
-        if *bb_counter >= limit {
-            return;
-        }
+    // Count the node, or skip it if the search has stopped
+    if !context.limits.enter_node(parent_bound) {
+        return;
     }
-
-    let (max_iterations, beta) = if depth == 0 {
-        (INITIAL_MAX_ITERATIONS, INITIAL_BETA)
-    } else {
-        (MAX_ITERATIONS, BETA)
-    };
+    context.progress.node_entered(context.limits.nodes(), depth);
 
     let current_upper_bound = best_tour.lock().unwrap().cost;
-    let one_tree = match held_karp_lower_bound_parallel(
+    let (lower_bound, one_tree) = match held_karp_lower_bound_parallel(
         distances,
-        scaled_distances,
         edge_states,
         node_penalties,
-        // Possibly pass Arc<Mutex<UnTour>> instead of copying the best tour cost each time
+        // Possibly pass Arc<Mutex<UnTour<D>>> instead of copying the best tour cost each time
         best_tour.clone(),
-        max_iterations,
-        beta,
+        depth,
+        context,
     ) {
         Some(LowerBoundOutput::Tour(tour)) => {
             // Found a new tour, that is, an upper bound
-            debug!("Found a new best tour with cost {}", tour.cost.0);
-            *best_tour.lock().unwrap() = tour;
+            let reported = context.progress.is_observed().then(|| tour.clone());
+            {
+                let mut best_tour = best_tour.lock().unwrap();
+                // Another thread might have found a better tour in the meantime
+                if tour.cost >= best_tour.cost {
+                    return;
+                }
+                debug!("Found a new best tour with cost {}", tour.cost);
+                *best_tour = tour;
+            }
+            // Notify without holding the lock, which all threads need for their bounds
+            if let Some(tour) = reported {
+                context.progress.new_incumbent(tour);
+            }
             return;
         }
         Some(LowerBoundOutput::LowerBound(lower_bound, one_tree)) => {
             // Check if the lower bound is better than the current best cost
-            if lower_bound >= current_upper_bound {
+            if context.can_prune(lower_bound, current_upper_bound) {
                 // Prune this node, as we have already found a better tour than the lower bound
                 trace!(
                     "Pruning node with lower bound {} >= upper bound {}",
-                    lower_bound.0, current_upper_bound.0
+                    lower_bound, current_upper_bound
                 );
+                context.progress.pruned();
                 return;
             } else {
-                one_tree
+                (lower_bound, one_tree)
             }
         }
         None => {
             // Infeasible node, prune
+            context.progress.infeasible();
             return;
         }
     };
 
-    let Some(branching_edge) =
-        edge_to_branch_on(scaled_distances, edge_states, node_penalties, &one_tree)
+    let Some(branching_edge) = edge_to_branch_on(distances, edge_states, node_penalties, &one_tree)
     else {
         // No edge to branch on, so we prune
         return;
@@ -390,95 +409,90 @@ fn explore_node_parallel(
 
     // Explore the branch excluding the edge
     {
-        edge_states.set_data_symmetric(branching_edge.from, branching_edge.to, EdgeState::Excluded);
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Excluded);
 
         explore_node_parallel(
             distances,
-            scaled_distances,
             edge_states,
             node_penalties,
             fixed_degrees,
             best_tour.clone(),
-            bb_counter,
-            bb_limit,
+            Some(lower_bound),
             depth + 1,
+            context,
         );
 
-        edge_states.set_data_symmetric(
-            branching_edge.from,
-            branching_edge.to,
-            EdgeState::Available,
-        );
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
     }
 
     // Try exploring the branch including the edge.
     // That is, we might not be able to explore this branch, if we the edge inclusion would violate
     // the already fixed degrees / edges.
     if (fixed_degrees[branching_edge.from.0] < 2) && (fixed_degrees[branching_edge.to.0] < 2) {
-        edge_states.set_data_symmetric(branching_edge.from, branching_edge.to, EdgeState::Fixed);
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Fixed);
         fixed_degrees[branching_edge.from.0] += 1;
         fixed_degrees[branching_edge.to.0] += 1;
 
         explore_node_parallel(
             distances,
-            scaled_distances,
             edge_states,
             node_penalties,
             fixed_degrees,
             best_tour,
-            bb_counter,
-            bb_limit,
+            Some(lower_bound),
             depth + 1,
+            context,
         );
 
         // Backtrack
-        edge_states.set_data_symmetric(
-            branching_edge.from,
-            branching_edge.to,
-            EdgeState::Available,
-        );
+        edge_states.set_data(branching_edge.from, branching_edge.to, EdgeState::Available);
         fixed_degrees[branching_edge.from.0] -= 1;
         fixed_degrees[branching_edge.to.0] -= 1;
     }
 }
 
-enum LowerBoundOutput {
-    LowerBound(Distance, Vec<UnEdge>),
-    Tour(UnTour),
+enum LowerBoundOutput<D> {
+    LowerBound(D, Vec<UnEdge>),
+    Tour(UnTour<D>),
 }
 
 /// Compute Held-Karp lower bound using 1-trees and Lagrangian relaxation
-fn held_karp_lower_bound_parallel(
-    distances: &Matrix<Distance>,
-    scaled_distances: &Matrix<ScaledDistance>,
-    edge_states: &Matrix<EdgeState>,
-    node_penalties: &mut [ScaledDistance],
-    best_tour: Arc<Mutex<UnTour>>,
-    max_iterations: usize,
-    beta: f64,
-) -> Option<LowerBoundOutput> {
+fn held_karp_lower_bound_parallel<D: DistanceValue, O: DistanceOracle<Value = D> + Sync>(
+    distances: &O,
+    edge_states: &MatrixSym<EdgeState>,
+    node_penalties: &mut [D::Scaled],
+    best_tour: Arc<Mutex<UnTour<D>>>,
+    depth: usize,
+    context: &SearchContext<D>,
+) -> Option<LowerBoundOutput<D>> {
+    let config = context.config;
+    let (max_iterations, beta) = config.iteration_parameters(depth);
+    let min_step = min_step::<D>(config);
+
     // Tracks the current best lower bound found
-    let mut scaled_best_lower_bound = ScaledDistance::MIN;
+    let mut scaled_best_lower_bound = D::Scaled::MIN;
 
     let mut iter_count = 0;
 
-    let mut alpha = INITIAL_ALPHA;
+    let mut alpha = config.initial_alpha;
 
-    let node_penalty_sum: ScaledDistance = node_penalties.iter().sum();
+    let node_penalty_sum: D::Scaled = node_penalties.iter().sum();
 
     let one_tree = loop {
-        let one_tree = min_one_tree(scaled_distances, edge_states, node_penalties)?;
+        let one_tree = min_one_tree(distances, edge_states, node_penalties)?;
 
-        let scaled_upper_bound = ScaledDistance::from_distance(best_tour.lock().unwrap().cost);
+        let upper_bound = best_tour.lock().unwrap().cost;
+        let scaled_upper_bound = upper_bound.to_scaled();
+        let scaled_target = context.ascent_target(upper_bound);
 
         // Compute the cost of the 1-tree with penalties. This is simultaneously the value of
         // the lagrangian relaxation and thus a lower bound (possibly an upper bound too, if it is a
         // tour).
         let one_tree_cost = {
-            let mut base_cost = 2 * node_penalty_sum;
+            let mut base_cost = node_penalty_sum.times(2);
 
             for edge in &one_tree {
-                base_cost += scaled_distances.get_data(edge.from, edge.to);
+                base_cost += distances.get(edge.from, edge.to).to_scaled();
                 base_cost -= node_penalties[edge.from.0];
                 base_cost -= node_penalties[edge.to.0];
             }
@@ -488,12 +502,18 @@ fn held_karp_lower_bound_parallel(
 
         if one_tree_cost > scaled_best_lower_bound {
             scaled_best_lower_bound = one_tree_cost;
+            if depth == 0 {
+                // Without fixed or excluded edges, the bound of the root holds for all tours
+                context
+                    .progress
+                    .lower_bound(D::from_scaled_rounded_up(one_tree_cost));
+            }
         }
         if one_tree_cost >= scaled_upper_bound {
             // Lower bound exceeds current upper bound, prune
             trace!(
-                "Pruning in held_karp_lower_bound due to lower bound {} >= upper bound {}",
-                one_tree_cost.0, scaled_upper_bound.0
+                "Pruning in held_karp_lower_bound due to lower bound {:?} >= upper bound {:?}",
+                one_tree_cost, scaled_upper_bound
             );
             break one_tree;
         }
@@ -516,9 +536,9 @@ fn held_karp_lower_bound_parallel(
 
         if square_sum == 0 {
             // Found a tour
-            let cost: Distance = one_tree
+            let cost: D = one_tree
                 .iter()
-                .map(|edge| distances.get_data(edge.from, edge.to))
+                .map(|edge| distances.get(edge.from, edge.to))
                 .sum();
 
             return Some(LowerBoundOutput::Tour(UnTour {
@@ -527,6 +547,11 @@ fn held_karp_lower_bound_parallel(
             }));
         }
 
+        if one_tree_cost >= scaled_target {
+            // The bound reached the given upper bound, so the ascent has nothing to step towards
+            break one_tree;
+        }
+
         // We have not found a tour yet, so we want to update the penalties
         iter_count += 1;
 
@@ -535,28 +560,33 @@ fn held_karp_lower_bound_parallel(
             break one_tree;
         }
 
+        if context.limits.is_stopped() {
+            // The search stops, but the best bound so far is still valid
+            break one_tree;
+        }
+
         // TODO: Research on subgradient method for non-smooth optimization to find out more about
         // this
-        let step_size = (alpha
-            * ((scaled_upper_bound.0 - one_tree_cost.0) as f64 / (square_sum as f64)))
-            as i32;
+        let step_size = D::Scaled::from_raw_f64(
+            alpha * ((scaled_target - one_tree_cost).to_raw_f64() / (square_sum as f64)),
+        );
 
-        if step_size <= 3 {
-            // Step size is very small (<= 3 in scaled), we probably won't be making much progress
+        if step_size <= min_step {
+            // Step size is very small, we probably won't be making much progress
             break one_tree;
         }
 
         alpha *= beta;
 
         // Update penalties based on degree deviations and step size
-        // TODO: Handle overflows
+        // Overflows panic in debug builds and saturate in release builds, see `ScaledValue`
         for (node_penalty, &d) in node_penalties.iter_mut().zip(deg.iter()) {
-            let adjustment = ScaledDistance(step_size * d);
+            let adjustment = step_size.times(d);
             *node_penalty += adjustment;
         }
     };
 
-    let best_lower_bound = scaled_best_lower_bound.to_distance_rounded_up();
+    let best_lower_bound = D::from_scaled_rounded_up(scaled_best_lower_bound);
 
     Some(LowerBoundOutput::LowerBound(best_lower_bound, one_tree))
 }
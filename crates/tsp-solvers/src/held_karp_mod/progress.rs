@@ -0,0 +1,103 @@
+use std::sync::{
+    Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use tsp_core::instance::{UnTour, distance::DistanceValue};
+
+use crate::observer::{NodeProgress, SolverEvent, SolverObserver};
+
+/// Counts the pruned nodes of a search and reports its progress to an observer.
+///
+/// One instance is shared by all threads of a search. The counters are atomics, so reporting does
+/// not synchronize the threads; only improvements of the global lower bound, which happen during
+/// the ascent at the root, and new incumbents take a lock.
+pub(crate) struct SearchProgress<'a, D> {
+    observer: &'a dyn SolverObserver<D>,
+    /// Whether the observer handles events, see [`SolverObserver::is_enabled`].
+    observed: bool,
+    pruned: AtomicUsize,
+    infeasible: AtomicUsize,
+    /// The best proven lower bound reported so far.
+    lower_bound: Mutex<Option<D>>,
+    /// The cost of the best tour reported so far.
+    incumbent_cost: Mutex<Option<D>>,
+}
+
+impl<'a, D: DistanceValue> SearchProgress<'a, D> {
+    pub(crate) fn new(observer: &'a dyn SolverObserver<D>) -> Self {
+        Self {
+            observer,
+            observed: observer.is_enabled(),
+            pruned: AtomicUsize::new(0),
+            infeasible: AtomicUsize::new(0),
+            lower_bound: Mutex::new(None),
+            incumbent_cost: Mutex::new(None),
+        }
+    }
+
+    /// Returns whether the observer handles events. Tours for [`Self::new_incumbent`] only need
+    /// to be created if it does.
+    pub(crate) fn is_observed(&self) -> bool {
+        self.observed
+    }
+
+    /// Reports a tour if it is cheaper than the ones reported before.
+    ///
+    /// Threads report their tours after releasing the best tour of the search, so the tours can
+    /// arrive out of order; the ones that are no improvement anymore are dropped.
+    pub(crate) fn new_incumbent(&self, tour: UnTour<D>) {
+        let mut incumbent_cost = self.incumbent_cost.lock().unwrap();
+        if incumbent_cost.is_none_or(|cost| tour.cost < cost) {
+            *incumbent_cost = Some(tour.cost);
+            self.observer.on_event(SolverEvent::NewIncumbent(tour));
+        }
+    }
+
+    /// Reports a lower bound valid for all tours, if it is better than the ones reported before.
+    pub(crate) fn lower_bound(&self, bound: D) {
+        let mut lower_bound = self.lower_bound.lock().unwrap();
+        if lower_bound.is_none_or(|lower_bound| bound > lower_bound) {
+            *lower_bound = Some(bound);
+            self.observer.on_event(SolverEvent::LowerBound(bound));
+        }
+    }
+
+    /// Counts a node pruned by its lower bound.
+    pub(crate) fn pruned(&self) {
+        self.pruned.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a node without any tour.
+    pub(crate) fn infeasible(&self) {
+        self.infeasible.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reports that the search entered a node at the given depth, `nodes` is the total count.
+    pub(crate) fn node_entered(&self, nodes: usize, depth: usize) {
+        if !self.observed {
+            return;
+        }
+        self.observer.on_event(SolverEvent::Node(NodeProgress {
+            nodes,
+            depth,
+            pruned: self.pruned_count(),
+            infeasible: self.infeasible_count(),
+        }));
+    }
+
+    /// Returns the number of nodes pruned by their lower bound.
+    pub(crate) fn pruned_count(&self) -> usize {
+        self.pruned.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of nodes without any tour.
+    pub(crate) fn infeasible_count(&self) -> usize {
+        self.infeasible.load(Ordering::Relaxed)
+    }
+
+    /// Returns the best lower bound reported so far.
+    pub(crate) fn best_lower_bound(&self) -> Option<D> {
+        *self.lower_bound.lock().unwrap()
+    }
+}
@@ -1,41 +1,38 @@
 use tsp_core::instance::{
-    distance::ScaledDistance,
+    distance::{DistanceValue, ScaledValue},
     edge::UnEdge,
-    matrix::{Matrix, MatrixViewZeroRemoved},
+    matrix::MatrixSym,
     node::Node,
+    oracle::DistanceOracle,
 };
 
 use crate::held_karp_mod::EdgeState;
 
 /// Compute a minimum 1-tree with given node penalties and edge states.
-pub fn min_one_tree(
-    distances_scaled: &Matrix<ScaledDistance>,
-    edge_states: &Matrix<EdgeState>,
-    penalties: &[ScaledDistance],
+pub fn min_one_tree<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    edge_states: &MatrixSym<EdgeState>,
+    penalties: &[D::Scaled],
 ) -> Option<Vec<UnEdge>> {
-    let (distances_scaled_zero, distances_scaled_rest) = distances_scaled.split_first_row();
-    let (edge_states_zero, edge_states_rest) = edge_states.split_first_row();
-
     // First, compute the minimum spanning tree on all nodes except the first one (node 0)
-    let tree = min_spanning_tree(distances_scaled_rest, edge_states_rest, penalties)?;
+    let tree = min_spanning_tree(distances, edge_states, penalties)?;
 
     // Next, find the two cheapest edges connecting the first node (node 0) to the rest of the tree
     let node_zero = Node(0);
     // We will uphold the following invariant dist_cheapest_edge_a <= dist_cheapest_edge_b
-    let mut dist_cheapest_edge_a = ScaledDistance::MAX;
-    let mut dist_cheapest_edge_b = ScaledDistance::MAX;
+    let mut dist_cheapest_edge_a = D::Scaled::MAX;
+    let mut dist_cheapest_edge_b = D::Scaled::MAX;
     let mut cheapest_neighbor_a = None;
     let mut cheapest_neighbor_b = None;
 
-    let mut zero_neighbors_iter = distances_scaled_zero.iter().enumerate();
-    // Start from index 1, as index 0 is the distance from node 0
-    zero_neighbors_iter.next();
-
-    for (node_index, &distance) in zero_neighbors_iter {
-        let node = Node(node_index);
-        match edge_states_zero[node_index] {
+    // Start from node 1, as the distance from node 0 to itself is not an edge
+    for node in (1..distances.dimension()).map(Node) {
+        match edge_states.get_data_from_bigger(node, node_zero) {
             EdgeState::Excluded => continue,
             EdgeState::Available => {
+                // The penalty of node 0 is the same for all of its edges, so only the penalty of
+                // the other node decides which edges are the cheapest
+                let distance = distances.get(node_zero, node).to_scaled() - penalties[node.0];
                 if distance < dist_cheapest_edge_a {
                     // Assign new value to cheapest edge a, and move previous a to b
                     // (because of the invariant)
@@ -50,9 +47,9 @@ pub fn min_one_tree(
                 }
             }
             EdgeState::Fixed => {
-                if dist_cheapest_edge_b == ScaledDistance::MIN {
+                if dist_cheapest_edge_b == D::Scaled::MIN {
                     // By the invariant, this implies that dist_cheapest_edge_a is also
-                    // Distance(i32::MIN), meaning we have already included two
+                    // D::Scaled::MIN, meaning we have already included two
                     // fixed edges and just found another one, that is, we are infeasible.
                     return None;
                 }
@@ -60,7 +57,7 @@ pub fn min_one_tree(
                 // Proceed same as EdgeState::Available && distance < dist_cheapest_edge_a
                 dist_cheapest_edge_b = dist_cheapest_edge_a;
                 cheapest_neighbor_b = cheapest_neighbor_a;
-                dist_cheapest_edge_a = ScaledDistance::MIN;
+                dist_cheapest_edge_a = D::Scaled::MIN;
                 cheapest_neighbor_a = Some(node);
             }
         }
@@ -80,27 +77,27 @@ pub fn min_one_tree(
     }
 }
 
-/// Compute a minimum spanning tree with given edge states and node penalties. Implements a
-/// variation of Prim's algorithm to abide the edge states.
+/// Compute a minimum spanning tree of all nodes except node 0 with given edge states and node
+/// penalties. Implements a variation of Prim's algorithm to abide the edge states.
 ///
 /// Returns a vector of edges representing the minimum spanning tree.
-fn min_spanning_tree(
-    distances_scaled: MatrixViewZeroRemoved<ScaledDistance>,
-    edge_states: MatrixViewZeroRemoved<EdgeState>,
-    penalties: &[ScaledDistance],
+fn min_spanning_tree<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    edge_states: &MatrixSym<EdgeState>,
+    penalties: &[D::Scaled],
 ) -> Option<Vec<UnEdge>> {
-    let number_of_nodes_in_tree = distances_scaled.dimension_adjusted();
+    let dimension = distances.dimension();
+    let number_of_nodes_in_tree = dimension - 1;
     // Track which nodes are yet to be added to the tree
     let mut remaining_nodes = Vec::with_capacity(number_of_nodes_in_tree);
-    for node_index in 2..=number_of_nodes_in_tree {
+    for node_index in 2..dimension {
         remaining_nodes.push(Node(node_index));
     }
 
     // For each node, track the best predecessor node and cost to reach it (Initialize with
     // unreachable values)
-    let mut best_pred_to_node =
-        vec![Node(number_of_nodes_in_tree + 1); number_of_nodes_in_tree + 1];
-    let mut best_cost_to_node = vec![ScaledDistance::MAX; number_of_nodes_in_tree + 1];
+    let mut best_pred_to_node = vec![Node(dimension); dimension];
+    let mut best_cost_to_node = vec![D::Scaled::MAX; dimension];
 
     // Start from node 1
     let mut curr = Node(1);
@@ -110,18 +107,17 @@ fn min_spanning_tree(
 
     // Tree contains n - 1 edges
     for _ in 0..(number_of_nodes_in_tree - 1) {
-        let mut cheapest_edge = ScaledDistance::MAX;
+        let mut cheapest_edge = D::Scaled::MAX;
         let mut cheapest_node = None;
 
         let current_penalty = penalties[curr.0];
-        let distances_scaled_curr = distances_scaled.get_adjacency_list(curr);
-        let edge_states_curr = edge_states.get_adjacency_list(curr);
 
         for (index, next) in remaining_nodes.iter().enumerate() {
-            match edge_states_curr[next.0] {
-                EdgeState::Excluded => continue,
+            match edge_states.get_data(curr, *next) {
+                // The node might still be reached from another node of the tree
+                EdgeState::Excluded => {}
                 EdgeState::Available => {
-                    let distance = distances_scaled_curr[next.0];
+                    let distance = distances.get(curr, *next).to_scaled();
                     let adjusted_distance = distance - current_penalty - penalties[next.0];
                     if adjusted_distance < best_cost_to_node[next.0] {
                         best_cost_to_node[next.0] = adjusted_distance;
@@ -130,7 +126,7 @@ fn min_spanning_tree(
                 }
                 EdgeState::Fixed => {
                     // The edge is fixed, so we must include it in the tree
-                    if best_cost_to_node[next.0] == ScaledDistance::MIN {
+                    if best_cost_to_node[next.0] == D::Scaled::MIN {
                         // This means we have already included the node next via a fixed edge, so
                         // including it again would create a cycle. Therefore, the MST is not
                         // possible with the current (fixed) edge states.
@@ -138,7 +134,7 @@ fn min_spanning_tree(
                     }
 
                     // Force this edge by setting its cost to the minimum possible value
-                    best_cost_to_node[next.0] = ScaledDistance::MIN;
+                    best_cost_to_node[next.0] = D::Scaled::MIN;
                     best_pred_to_node[next.0] = curr;
                 }
             }
@@ -172,7 +168,10 @@ fn min_spanning_tree(
 #[cfg(test)]
 mod tests {
 
-    use tsp_core::instance::matrix::Matrix;
+    use tsp_core::instance::{
+        distance::{Distance, ScaledDistance},
+        matrix::Matrix,
+    };
 
     use super::*;
 
@@ -181,20 +180,14 @@ mod tests {
         let dimension = 11;
         let distance_matrix = Matrix::new_from_distance_function(dimension, |from, to| {
             if from.0 + 1 == to.0 || from.0 == to.0 + 1 {
-                ScaledDistance(0)
+                Distance(0)
             } else {
-                ScaledDistance(1)
+                Distance(1)
             }
         });
-        let penalties = vec![ScaledDistance(0); dimension];
-        let edge_states = Matrix::new_from_dimension_with_value(
-            distance_matrix.dimension(),
-            EdgeState::Available,
-        );
-        let (_, distance_matrix_rest) = distance_matrix.split_first_row();
-        let (_, edge_states_rest) = edge_states.split_first_row();
-
-        let mst = min_spanning_tree(distance_matrix_rest, edge_states_rest, &penalties).unwrap();
+        let penalties = vec![ScaledDistance::from_raw(0); dimension];
+        let edge_states = MatrixSym::new_from_dimension_with_value(dimension, EdgeState::Available);
+        let mst = min_spanning_tree(&distance_matrix, &edge_states, &penalties).unwrap();
         assert_eq!(mst.len(), dimension - 2);
         let expected = (1..(dimension))
             .map(|i| UnEdge {
@@ -213,64 +206,54 @@ mod tests {
 
     #[test]
     fn test_min_spanning_tree_excluded_infeasible() {
-        let distance_matrix = Matrix::new_from_dimension_with_value(10, ScaledDistance(0));
-        let penalties = vec![ScaledDistance(0); 10];
-        let edge_states =
-            Matrix::new_from_dimension_with_value(distance_matrix.dimension(), EdgeState::Excluded);
-        let (_, distance_matrix_rest) = distance_matrix.split_first_row();
-        let (_, edge_states_rest) = edge_states.split_first_row();
-
-        let mst = min_spanning_tree(distance_matrix_rest, edge_states_rest, &penalties);
+        let distance_matrix = Matrix::new_from_dimension_with_value(10, Distance(0));
+        let penalties = vec![ScaledDistance::from_raw(0); 10];
+        let edge_states = MatrixSym::new_from_dimension_with_value(10, EdgeState::Excluded);
+        let mst = min_spanning_tree(&distance_matrix, &edge_states, &penalties);
         assert_eq!(mst, None);
     }
 
     #[test]
     fn test_min_spanning_tree_infeasible_node_isolated() {
         let dimension = 6;
-        let distance_matrix = Matrix::new_from_dimension_with_value(dimension, ScaledDistance(0));
-        let penalties = vec![ScaledDistance(0); dimension];
+        let distance_matrix = Matrix::new_from_dimension_with_value(dimension, Distance(0));
+        let penalties = vec![ScaledDistance::from_raw(0); dimension];
         let mut edge_states =
-            Matrix::new_from_dimension_with_value(dimension, EdgeState::Available);
+            MatrixSym::new_from_dimension_with_value(dimension, EdgeState::Available);
         for from in 0..dimension {
             for to in 0..=from {
                 if (from == 2) || (to == 2) {
-                    edge_states.set_data_symmetric(Node(from), Node(to), EdgeState::Excluded);
+                    edge_states.set_data(Node(from), Node(to), EdgeState::Excluded);
                 } else if to + 1 == from {
-                    edge_states.set_data_symmetric(Node(from), Node(to), EdgeState::Fixed);
+                    edge_states.set_data(Node(from), Node(to), EdgeState::Fixed);
                 } else {
-                    edge_states.set_data_symmetric(Node(from), Node(to), EdgeState::Available);
+                    edge_states.set_data(Node(from), Node(to), EdgeState::Available);
                 }
             }
         }
 
-        let (_, distance_matrix_rest) = distance_matrix.split_first_row();
-        let (_, edge_states_rest) = edge_states.split_first_row();
-
-        let mst = min_spanning_tree(distance_matrix_rest, edge_states_rest, &penalties);
+        let mst = min_spanning_tree(&distance_matrix, &edge_states, &penalties);
         assert_eq!(mst, None);
     }
 
     #[test]
     fn test_min_spanning_tree_fixed() {
         let dimension = 6;
-        let distance_matrix = Matrix::new_from_dimension_with_value(dimension, ScaledDistance(0));
-        let penalties = vec![ScaledDistance(0); dimension];
+        let distance_matrix = Matrix::new_from_dimension_with_value(dimension, Distance(0));
+        let penalties = vec![ScaledDistance::from_raw(0); dimension];
         let mut edge_states =
-            Matrix::new_from_dimension_with_value(dimension, EdgeState::Available);
+            MatrixSym::new_from_dimension_with_value(dimension, EdgeState::Available);
         for from in 0..dimension {
             for to in 0..=from {
                 if to + 1 == from {
-                    edge_states.set_data_symmetric(Node(from), Node(to), EdgeState::Fixed);
+                    edge_states.set_data(Node(from), Node(to), EdgeState::Fixed);
                 } else {
-                    edge_states.set_data_symmetric(Node(from), Node(to), EdgeState::Available);
+                    edge_states.set_data(Node(from), Node(to), EdgeState::Available);
                 }
             }
         }
 
-        let (_, distance_matrix_rest) = distance_matrix.split_first_row();
-        let (_, edge_states_rest) = edge_states.split_first_row();
-
-        let mst = min_spanning_tree(distance_matrix_rest, edge_states_rest, &penalties).unwrap();
+        let mst = min_spanning_tree(&distance_matrix, &edge_states, &penalties).unwrap();
         let expected = vec![
             UnEdge::new(Node(2), Node(3)),
             UnEdge::new(Node(3), Node(4)),
@@ -286,4 +269,45 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn test_min_spanning_tree_excluded_edge_to_last_node() {
+        // Node 3 is only reachable from node 1, which is added to the tree before node 2
+        let dimension = 4;
+        let distance_matrix = Matrix::new_from_distance_function(dimension, |from, to| {
+            match (from.0.min(to.0), from.0.max(to.0)) {
+                (1, 3) => Distance(5),
+                _ => Distance(1),
+            }
+        });
+        let penalties = vec![ScaledDistance::from_raw(0); dimension];
+        let mut edge_states =
+            MatrixSym::new_from_dimension_with_value(dimension, EdgeState::Available);
+        edge_states.set_data(Node(2), Node(3), EdgeState::Excluded);
+
+        let mst = min_spanning_tree(&distance_matrix, &edge_states, &penalties).unwrap();
+        assert_eq!(mst.len(), 2);
+        assert!(mst.contains(&UnEdge::new(Node(1), Node(2))));
+        assert!(mst.contains(&UnEdge::new(Node(1), Node(3))));
+    }
+
+    #[test]
+    fn test_min_one_tree_penalized_edges_of_node_zero() {
+        // Node 0 is closest to nodes 1 and 2, but the penalty of node 3 makes its edge cheaper
+        let dimension = 4;
+        let distance_matrix = Matrix::new_from_distance_function(dimension, |from, to| {
+            match (from.0.min(to.0), from.0.max(to.0)) {
+                (0, 1) | (0, 2) => Distance(10),
+                (0, 3) => Distance(15),
+                _ => Distance(1),
+            }
+        });
+        let mut penalties = vec![ScaledDistance::from_raw(0); dimension];
+        penalties[3] = Distance(10).to_scaled();
+        let edge_states = MatrixSym::new_from_dimension_with_value(dimension, EdgeState::Available);
+
+        let one_tree = min_one_tree(&distance_matrix, &edge_states, &penalties).unwrap();
+        assert_eq!(one_tree.len(), dimension);
+        assert!(one_tree.contains(&UnEdge::new(Node(0), Node(3))));
+    }
 }
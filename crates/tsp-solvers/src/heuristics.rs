@@ -0,0 +1,136 @@
+//! Cheap heuristics for good, but not necessarily optimal, tours.
+//!
+//! The exact solvers start from [`nearest_neighbor_two_opt`] unless they are given an initial tour
+//! or upper bound (see [`SolverOptions`](crate::solver::SolverOptions)), since the cost of the
+//! first tour decides how much of the search tree can be pruned early on.
+
+use tsp_core::instance::{distance::DistanceValue, node::Node, oracle::DistanceOracle, tour::Tour};
+
+/// Builds a tour by starting at `start` and always moving on to the nearest unvisited node.
+///
+/// Takes `O(n^2)` time. Panics if `start` is not a node of the instance.
+pub fn nearest_neighbor<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    start: Node,
+) -> Tour<D> {
+    let dimension = distances.dimension();
+    let mut visited = vec![false; dimension];
+    let mut nodes = Vec::with_capacity(dimension);
+
+    let mut current = start;
+    visited[current.0] = true;
+    nodes.push(current);
+    while nodes.len() < dimension {
+        let next = (0..dimension)
+            .filter(|&node| !visited[node])
+            .map(Node)
+            .min_by_key(|&node| distances.get(current, node))
+            .expect("There is an unvisited node left");
+        visited[next.0] = true;
+        nodes.push(next);
+        current = next;
+    }
+
+    Tour::from_nodes(nodes, |from, to| distances.get(from, to))
+        .expect("Every node is visited exactly once")
+}
+
+/// Improves a tour with 2-opt moves until no move makes it shorter.
+///
+/// A 2-opt move removes two edges of the tour and reconnects the two paths the other way around.
+/// Every pass over all pairs of edges takes `O(n^2)` time. The distances are assumed to be
+/// symmetric.
+pub fn two_opt<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+    tour: Tour<D>,
+) -> Tour<D> {
+    let mut nodes = tour.nodes().to_vec();
+    let dimension = nodes.len();
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        // Replace the edges (nodes[i], nodes[i + 1]) and (nodes[j], nodes[j + 1]) by the edges
+        // (nodes[i], nodes[j]) and (nodes[i + 1], nodes[j + 1]), which reverses the path between
+        // them
+        for i in 0..dimension.saturating_sub(2) {
+            for j in i + 2..dimension {
+                if i == 0 && j == dimension - 1 {
+                    // The edges are adjacent through the edge closing the cycle
+                    continue;
+                }
+                let (a, b) = (nodes[i], nodes[i + 1]);
+                let (c, d) = (nodes[j], nodes[(j + 1) % dimension]);
+                if distances.get(a, c) + distances.get(b, d)
+                    < distances.get(a, b) + distances.get(c, d)
+                {
+                    nodes[i + 1..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    Tour::from_nodes(nodes, |from, to| distances.get(from, to))
+        .expect("Reversing paths keeps the tour a permutation")
+}
+
+/// Builds a tour with [`nearest_neighbor`] starting at node 0 and improves it with [`two_opt`].
+///
+/// Panics if the instance has no nodes.
+pub fn nearest_neighbor_two_opt<D: DistanceValue, O: DistanceOracle<Value = D>>(
+    distances: &O,
+) -> Tour<D> {
+    two_opt(distances, nearest_neighbor(distances, Node(0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use tsp_core::instance::{distance::Distance, matrix::Matrix};
+
+    use super::*;
+
+    /// Regular polygon with `dimension` corners, visited in a scrambled order by the node indices.
+    fn scrambled_polygon(dimension: usize) -> Matrix<Distance> {
+        let corner = |node: Node| {
+            let position = (node.0 * 5) % dimension;
+            let angle = 2.0 * std::f64::consts::PI * position as f64 / dimension as f64;
+            (1000.0 * angle.cos(), 1000.0 * angle.sin())
+        };
+        Matrix::new_from_distance_function(dimension, |from, to| {
+            let ((x1, y1), (x2, y2)) = (corner(from), corner(to));
+            Distance(((x1 - x2).hypot(y1 - y2)).round() as i32)
+        })
+    }
+
+    #[test]
+    fn test_two_opt_finds_polygon() {
+        let distances = scrambled_polygon(12);
+        let identity = Tour::from_nodes((0..12).map(Node).collect(), |from, to| {
+            distances.get_data(from, to)
+        })
+        .unwrap();
+
+        // On points in convex position, every tour without crossing edges follows the boundary
+        let polygon = two_opt(&distances, identity.clone());
+        assert!(polygon.cost() < identity.cost());
+        assert!(
+            polygon
+                .edges()
+                .all(|edge| distances.get_data(edge.from, edge.to)
+                    == distances.get_data(Node(0), Node(5)))
+        );
+
+        let tour = nearest_neighbor_two_opt(&distances);
+        assert_eq!(tour.cost(), polygon.cost());
+        assert_eq!(two_opt(&distances, tour.clone()), tour);
+    }
+
+    #[test]
+    fn test_nearest_neighbor_visits_all_nodes() {
+        let distances = scrambled_polygon(7);
+        let tour = nearest_neighbor(&distances, Node(3));
+        assert_eq!(tour.dimension(), 7);
+        assert_eq!(tour.check_dimension(7), Ok(()));
+    }
+}
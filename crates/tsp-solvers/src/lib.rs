@@ -5,4 +5,7 @@ Explanations and references for the algorithms can be found in their respective
 #![warn(missing_debug_implementations, missing_docs)]
 
 pub mod held_karp_mod;
+pub mod heuristics;
+pub mod observer;
+pub mod solver;
 pub use held_karp_mod::held_karp;
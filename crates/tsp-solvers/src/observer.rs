@@ -0,0 +1,159 @@
+//! Following the progress of a running solver.
+//!
+//! Solvers report [`SolverEvent`]s to a [`SolverObserver`] passed to
+//! [`Solver::solve_with_observer`](crate::solver::Solver::solve_with_observer). Parallel solvers
+//! call the observer from all their threads at the same time, so observers should return quickly
+//! and must not block on each other. A [`ChannelObserver`] forwards the events to another thread,
+//! e.g. one that draws a progress bar. To keep its channel from growing with the millions of nodes
+//! of long searches, it only forwards every thousandth [`SolverEvent::Node`] by default:
+//!
+//! ```no_run
+//! use std::thread;
+//!
+//! use tsp_core::instance::{TSPSymInstance, distance::Distance, matrix::MatrixSym};
+//! use tsp_solvers::{
+//!     held_karp_mod::HeldKarpParallel,
+//!     observer::{ChannelObserver, SolverEvent},
+//!     solver::{Solver, SolverOptions},
+//! };
+//!
+//! let instance: TSPSymInstance<MatrixSym<Distance>> =
+//!     tsp_parser::parse_tsp_instance("instances/tsplib_symmetric/eil76.tsp").unwrap();
+//! let (observer, events) = ChannelObserver::new();
+//! let ui = thread::spawn(move || {
+//!     for event in events {
+//!         if let SolverEvent::NewIncumbent(tour) = event {
+//!             println!("New best tour of cost {}", tour.cost);
+//!         }
+//!     }
+//! });
+//! let solution = HeldKarpParallel::default()
+//!     .solve_with_observer(&instance, &SolverOptions::default(), &observer)
+//!     .unwrap();
+//! // Dropping the observer closes the channel and ends the loop of the UI thread
+//! drop(observer);
+//! ui.join().unwrap();
+//! println!("Best tour of cost {:?}", solution.cost());
+//! ```
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+    mpsc::{self, Receiver, Sender},
+};
+
+use tsp_core::instance::{UnTour, distance::DistanceValue};
+
+/// Something that happened during a solve.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverEvent<D: DistanceValue> {
+    /// A tour cheaper than all tours found before.
+    NewIncumbent(UnTour<D>),
+    /// The proven lower bound on the cost of all tours improved.
+    LowerBound(D),
+    /// The search entered a branch-and-bound node.
+    Node(NodeProgress),
+}
+
+/// The progress of a branch-and-bound search when it enters a node.
+///
+/// The counts are totals over all threads and are read without synchronizing the threads, so in
+/// parallel searches the counts of consecutive events are not necessarily increasing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeProgress {
+    /// The number of entered nodes, including this one.
+    pub nodes: usize,
+    /// The depth of the node, 0 for the root.
+    pub depth: usize,
+    /// The number of nodes pruned because their lower bound was not below the best tour.
+    pub pruned: usize,
+    /// The number of nodes pruned because their fixed and excluded edges allow no tour.
+    pub infeasible: usize,
+}
+
+/// Receives the events of a running solver.
+///
+/// Implemented for all closures taking a [`SolverEvent`].
+pub trait SolverObserver<D: DistanceValue>: Sync {
+    /// Handles an event. Called concurrently by the threads of parallel solvers.
+    fn on_event(&self, event: SolverEvent<D>);
+
+    /// Whether the observer handles any events. Solvers skip creating the events, e.g. cloning
+    /// new tours, for observers returning `false`.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
+impl<D: DistanceValue, F: Fn(SolverEvent<D>) + Sync> SolverObserver<D> for F {
+    fn on_event(&self, event: SolverEvent<D>) {
+        self(event)
+    }
+}
+
+/// An observer ignoring all events.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoObserver;
+
+impl<D: DistanceValue> SolverObserver<D> for NoObserver {
+    fn on_event(&self, _event: SolverEvent<D>) {}
+
+    fn is_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// The default number of [`SolverEvent::Node`] events per event sent by a [`ChannelObserver`].
+pub const DEFAULT_NODE_INTERVAL: usize = 1_000;
+
+/// Sends the events to a channel.
+///
+/// New incumbents and lower bounds are always sent, node events only once per
+/// [`with_node_interval`](Self::with_node_interval) nodes, starting with the root. Sending never
+/// blocks, and events are dropped silently once the receiver is gone.
+#[derive(Debug, Clone)]
+pub struct ChannelObserver<D: DistanceValue> {
+    sender: Sender<SolverEvent<D>>,
+    node_interval: usize,
+    /// The number of node events received, shared by all clones.
+    node_events: Arc<AtomicUsize>,
+}
+
+impl<D: DistanceValue> ChannelObserver<D> {
+    /// Creates an observer and the receiver of its events. The channel is closed once the
+    /// observer (and all its clones) are dropped.
+    pub fn new() -> (Self, Receiver<SolverEvent<D>>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self::from_sender(sender), receiver)
+    }
+
+    /// Creates an observer sending the events to an existing channel.
+    pub fn from_sender(sender: Sender<SolverEvent<D>>) -> Self {
+        Self {
+            sender,
+            node_interval: DEFAULT_NODE_INTERVAL,
+            node_events: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Sends only one of every `node_interval` node events, 1 sends all of them. An interval of 0
+    /// is treated as 1.
+    pub fn with_node_interval(mut self, node_interval: usize) -> Self {
+        self.node_interval = node_interval.max(1);
+        self
+    }
+}
+
+impl<D: DistanceValue> SolverObserver<D> for ChannelObserver<D> {
+    fn on_event(&self, event: SolverEvent<D>) {
+        if matches!(event, SolverEvent::Node(_))
+            && !self
+                .node_events
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(self.node_interval)
+        {
+            return;
+        }
+        let _ = self.sender.send(event);
+    }
+}
@@ -0,0 +1,220 @@
+//! A common interface for all solvers.
+//!
+//! Every solver implements [`Solver`], which takes the distances of an instance and
+//! [`SolverOptions`] and returns a [`Solution`]. This allows choosing a solver at runtime, e.g. by
+//! name with [`solver_by_name`]:
+//!
+//! ```no_run
+//! use tsp_core::instance::{TSPSymInstance, distance::Distance, matrix::MatrixSym};
+//! use tsp_solvers::solver::{SolverOptions, solver_by_name};
+//!
+//! let instance: TSPSymInstance<MatrixSym<Distance>> =
+//!     tsp_parser::parse_tsp_instance("instances/tsplib_symmetric/berlin52.tsp").unwrap();
+//! let solver = solver_by_name("held-karp-parallel").unwrap();
+//! let solution = solver.solve(&instance, &SolverOptions::default()).unwrap();
+//! println!("{}: {:?}", solver.name(), solution.cost());
+//! ```
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+use tsp_core::instance::{
+    UnTour,
+    comparison::relative_gap,
+    distance::{Distance, DistanceValue},
+    oracle::DistanceOracle,
+    tour::{Tour, TourError},
+};
+
+use crate::{
+    held_karp_mod::{HeldKarp, HeldKarpParallel},
+    observer::{NoObserver, SolverObserver},
+};
+
+/// The names of all solvers known to [`solver_by_name`].
+pub const SOLVER_NAMES: &[&str] = &[HeldKarp::NAME, HeldKarpParallel::NAME];
+
+/// A solver for symmetric TSP instances with distances given by an oracle of type `O`.
+pub trait Solver<O: DistanceOracle<Value: DistanceValue>> {
+    /// Returns the name of the solver, as accepted by [`solver_by_name`].
+    fn name(&self) -> &'static str;
+
+    /// Solves the instance with the given distances, or returns an error if the options do not fit
+    /// the instance, see [`SolverOptions::validate`].
+    fn solve(
+        &self,
+        distances: &O,
+        options: &SolverOptions<O::Value>,
+    ) -> Result<Solution<O::Value>, SolverOptionsError> {
+        self.solve_with_observer(distances, options, &NoObserver)
+    }
+
+    /// Solves the instance with the given distances and reports the progress to the observer, or
+    /// returns an error if the options do not fit the instance.
+    fn solve_with_observer(
+        &self,
+        distances: &O,
+        options: &SolverOptions<O::Value>,
+        observer: &dyn SolverObserver<O::Value>,
+    ) -> Result<Solution<O::Value>, SolverOptionsError>;
+}
+
+/// Returns the solver with the given name, see [`SOLVER_NAMES`].
+pub fn solver_by_name<O: DistanceOracle<Value: DistanceValue> + Sync>(
+    name: &str,
+) -> Option<Box<dyn Solver<O>>> {
+    match name {
+        HeldKarp::NAME => Some(Box::new(HeldKarp::default())),
+        HeldKarpParallel::NAME => Some(Box::new(HeldKarpParallel::default())),
+        _ => None,
+    }
+}
+
+/// Options that apply to all solvers.
+///
+/// The limits are checked regularly during the search. When one is hit, the solver stops and
+/// returns the best tour found so far and the best proven lower bound, with the limit as the
+/// [`TerminationReason`].
+///
+/// Without an [`initial_tour`](Self::initial_tour) or [`upper_bound`](Self::upper_bound), the
+/// solvers start from a tour built by a cheap heuristic, see
+/// [`nearest_neighbor_two_opt`](crate::heuristics::nearest_neighbor_two_opt).
+#[derive(Debug, Clone, Default)]
+pub struct SolverOptions<D = Distance> {
+    /// The point in time at which the solver stops.
+    pub deadline: Option<Instant>,
+    /// The maximum number of branch-and-bound nodes to explore.
+    pub node_limit: Option<usize>,
+    /// A token to stop the solver from another thread.
+    pub cancellation: Option<CancellationToken>,
+    /// A tour to start the search from, e.g. the result of an earlier run or another heuristic.
+    /// It is returned if the search finds no cheaper tour. Its cost is recomputed from the
+    /// distances.
+    pub initial_tour: Option<Tour<D>>,
+    /// A known upper bound on the cost of an optimal tour. Nodes whose lower bound exceeds it are
+    /// pruned even before a tour this cheap is found.
+    ///
+    /// If no tour costs at most the bound, the search stops with
+    /// [`TerminationReason::UpperBoundTooLow`] instead of proving optimality.
+    pub upper_bound: Option<D>,
+}
+
+impl<D> SolverOptions<D> {
+    /// Sets the deadline to the given duration from now.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.deadline = Some(Instant::now() + time_limit);
+        self
+    }
+}
+
+impl<D: DistanceValue> SolverOptions<D> {
+    /// Returns an error if the options do not fit an instance with the given number of nodes.
+    pub fn validate(&self, dimension: usize) -> Result<(), SolverOptionsError> {
+        if let Some(tour) = &self.initial_tour {
+            tour.check_dimension(dimension)
+                .map_err(SolverOptionsError::InvalidInitialTour)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reasons why [`SolverOptions`] do not fit an instance.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SolverOptionsError {
+    /// The initial tour does not visit the nodes of the instance.
+    #[error("Invalid initial tour: {0}")]
+    InvalidInitialTour(TourError),
+}
+
+/// A flag to stop running solvers, e.g. from a signal handler or another thread.
+///
+/// Clones share the flag, so one token can be passed to several solvers and cancel all of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops all solvers using this token (or a clone of it).
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The result of [`Solver::solve`].
+#[derive(Debug, Clone)]
+pub struct Solution<D: DistanceValue> {
+    /// The best tour found, if any.
+    pub tour: Option<UnTour<D>>,
+    /// The best proven lower bound on the cost of a tour, if any.
+    pub lower_bound: Option<D>,
+    /// Why the solver stopped.
+    pub termination: TerminationReason,
+    /// Statistics about the run.
+    pub stats: SolverStats,
+}
+
+impl<D: DistanceValue> Solution<D> {
+    /// Returns the cost of the best tour found, if any.
+    pub fn cost(&self) -> Option<D> {
+        self.tour.as_ref().map(|tour| tour.cost)
+    }
+
+    /// Returns whether the tour is proven to be optimal.
+    pub fn is_optimal(&self) -> bool {
+        self.termination == TerminationReason::Optimal
+    }
+
+    /// Returns how much more expensive the tour is than the lower bound in percent, if both are
+    /// known. See [`relative_gap`].
+    pub fn gap(&self) -> Option<f64> {
+        let cost = self.cost()?;
+        let lower_bound = self.lower_bound?;
+        Some(relative_gap(cost.to_f64(), lower_bound.to_f64()))
+    }
+}
+
+/// The reason a solver stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TerminationReason {
+    /// The search finished and the tour is optimal.
+    Optimal,
+    /// The search finished without finding a tour.
+    Infeasible,
+    /// The search finished and no tour costs at most [`SolverOptions::upper_bound`]. The tour is
+    /// the best one found, but not necessarily optimal.
+    UpperBoundTooLow,
+    /// The deadline was reached, see [`SolverOptions::deadline`].
+    TimeLimit,
+    /// The node limit was reached, see [`SolverOptions::node_limit`].
+    NodeLimit,
+    /// The search was cancelled, see [`SolverOptions::cancellation`].
+    Cancelled,
+}
+
+/// Statistics about a run of a solver.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolverStats {
+    /// The number of branch-and-bound nodes explored.
+    pub nodes: usize,
+    /// The number of nodes pruned because their lower bound was not below the best tour.
+    pub pruned: usize,
+    /// The number of nodes pruned because their fixed and excluded edges allow no tour.
+    pub infeasible: usize,
+    /// The wall-clock time the solver took.
+    pub elapsed: Duration,
+}
@@ -0,0 +1,72 @@
+use tsp_core::instance::{
+    TSPSymInstance, distance::Distance, edge::UnEdge, matrix::MatrixSym, node::Node,
+};
+use tsp_solvers::held_karp_mod::{HeldKarpConfig, held_karp_bound};
+
+fn degrees(dimension: usize, edges: &[UnEdge]) -> Vec<usize> {
+    let mut degrees = vec![0; dimension];
+    for edge in edges {
+        degrees[edge.from.0] += 1;
+        degrees[edge.to.0] += 1;
+    }
+    degrees
+}
+
+#[test]
+fn test_held_karp_bound_berlin52() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/berlin52.tsp").unwrap();
+    let optimum = Distance(7542);
+
+    let result = held_karp_bound(&tsp_instance, Some(optimum), &HeldKarpConfig::default()).unwrap();
+    assert!(result.lower_bound <= optimum);
+    assert!(result.lower_bound.0 as f64 >= 0.99 * optimum.0 as f64);
+    assert_eq!(result.upper_bound, optimum);
+    assert_eq!(result.node_penalties.len(), 52);
+    assert_eq!(result.one_tree.len(), 52);
+    assert_eq!(degrees(52, &result.one_tree).iter().sum::<usize>(), 104);
+    assert!(!result.trace.is_empty() && result.trace.len() <= 1000);
+    assert_eq!(
+        result.trace.iter().map(|iteration| iteration.bound).max(),
+        Some(result.lower_bound)
+    );
+    if let Some(tour) = &result.tour {
+        assert_eq!(tour.cost, optimum);
+        assert!(degrees(52, &tour.edges).iter().all(|&degree| degree == 2));
+    }
+
+    // Fewer iterations give a weaker (or equal) bound
+    let limited = held_karp_bound(
+        &tsp_instance,
+        None,
+        &HeldKarpConfig {
+            initial_max_iterations: 5,
+            ..HeldKarpConfig::default()
+        },
+    )
+    .unwrap();
+    assert!(limited.trace.len() <= 5);
+    assert!(limited.lower_bound <= result.lower_bound);
+    assert_eq!(
+        limited.upper_bound,
+        (0..52)
+            .map(|node| tsp_instance
+                .distance_matrix()
+                .get_data(Node(node), Node((node + 1) % 52)))
+            .sum()
+    );
+}
+
+#[test]
+fn test_held_karp_bound_finds_tour_on_12() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+
+    let result = held_karp_bound(&tsp_instance, None, &HeldKarpConfig::default()).unwrap();
+    // The first 1-tree with the initial penalties is already an optimal tour
+    let tour = result.tour.unwrap();
+    assert_eq!(tour.cost, Distance(1200));
+    assert_eq!(result.lower_bound, Distance(1200));
+    assert_eq!(result.trace.len(), 1);
+    assert_eq!(result.trace[0].degree_deviation, 0);
+}
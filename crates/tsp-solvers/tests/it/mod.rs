@@ -1,68 +1,72 @@
 use tsp_core::instance::{
-    TSPSymInstance, UnTour, distance::Distance, edge::UnEdge, matrix::Matrix, node::Node,
+    TSPSymInstance,
+    distance::{Distance, Distance64, FloatDistance},
+    matrix::{AnyCompactMatrix, AnyCompactMatrixSym, Matrix, MatrixSym},
+    node::Node,
+    tour::Tour,
 };
-use tsp_solvers::held_karp;
+use tsp_solvers::{held_karp, held_karp_mod::held_karp_parallel};
 
+mod held_karp_bound;
 mod held_karp_correct_length;
+mod observer;
+mod solver;
 
 #[test]
 fn test_held_karp_on_12() {
     let tsp_instance: TSPSymInstance<Matrix<Distance>> =
         tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
     let best_tour = held_karp(&tsp_instance.distance_matrix()).unwrap();
-    let edges = vec![
-        UnEdge {
-            from: Node(1),
-            to: Node(6),
-        },
-        UnEdge {
-            from: Node(6),
-            to: Node(2),
-        },
-        UnEdge {
-            from: Node(1),
-            to: Node(10),
-        },
-        UnEdge {
-            from: Node(10),
-            to: Node(3),
-        },
-        UnEdge {
-            from: Node(2),
-            to: Node(4),
-        },
-        UnEdge {
-            from: Node(4),
-            to: Node(9),
-        },
-        UnEdge {
-            from: Node(9),
-            to: Node(11),
-        },
-        UnEdge {
-            from: Node(11),
-            to: Node(5),
-        },
-        UnEdge {
-            from: Node(3),
-            to: Node(7),
-        },
-        UnEdge {
-            from: Node(7),
-            to: Node(8),
-        },
-        UnEdge {
-            from: Node(0),
-            to: Node(5),
-        },
-        UnEdge {
-            from: Node(0),
-            to: Node(8),
-        },
-    ];
-    let expected_tour = UnTour {
-        edges,
-        cost: Distance(1200),
+    let expected_tour = Tour::new(
+        [0, 5, 11, 9, 4, 2, 6, 1, 10, 3, 7, 8].map(Node).to_vec(),
+        Distance(1200),
+    )
+    .unwrap();
+    assert_eq!(Tour::try_from(best_tour).unwrap(), expected_tour);
+}
+
+#[test]
+fn test_held_karp_on_12_with_wide_distances() {
+    let tsp_instance: TSPSymInstance<Matrix<Distance64>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    let best_tour = held_karp(tsp_instance.distance_matrix()).unwrap();
+    assert_eq!(best_tour.cost, Distance64(1200));
+
+    let tsp_instance: TSPSymInstance<Matrix<FloatDistance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    let best_tour = held_karp(tsp_instance.distance_matrix()).unwrap();
+    assert_eq!(best_tour.cost, FloatDistance(1200.0));
+}
+
+#[test]
+fn test_held_karp_on_12_with_symmetric_matrix() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    let best_tour = held_karp(tsp_instance.distance_matrix()).unwrap();
+    assert_eq!(best_tour.cost, Distance(1200));
+
+    let best_tour = held_karp_parallel(&tsp_instance).unwrap();
+    assert_eq!(best_tour.cost, Distance(1200));
+}
+
+#[test]
+fn test_held_karp_on_12_with_compact_matrix() {
+    let tsp_instance: TSPSymInstance<AnyCompactMatrixSym> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    // The solver reads the u16 entries directly, without widening the matrix first
+    let AnyCompactMatrixSym::U16(distances) = tsp_instance.distances() else {
+        panic!("The distances of 12.tsp should fit into u16");
     };
-    assert_eq!(best_tour, expected_tour);
+    let best_tour = held_karp(distances).unwrap();
+    assert_eq!(best_tour.cost, Distance(1200));
+
+    let tsp_instance: TSPSymInstance<AnyCompactMatrix> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    let best_tour = match tsp_instance.distances() {
+        AnyCompactMatrix::U8(distances) => held_karp_parallel(distances),
+        AnyCompactMatrix::U16(distances) => held_karp_parallel(distances),
+        AnyCompactMatrix::I32(distances) => held_karp_parallel(distances),
+    }
+    .unwrap();
+    assert_eq!(best_tour.cost, Distance(1200));
 }
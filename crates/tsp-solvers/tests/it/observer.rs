@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tsp_core::instance::{TSPSymInstance, distance::Distance, matrix::MatrixSym};
+use tsp_solvers::{
+    held_karp_mod::{HeldKarp, HeldKarpParallel},
+    observer::{ChannelObserver, SolverEvent},
+    solver::{Solver, SolverOptions, TerminationReason},
+};
+
+#[test]
+fn test_channel_observer_on_eil51() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/eil51.tsp").unwrap();
+    let (observer, events) = ChannelObserver::new();
+    let observer = observer.with_node_interval(1);
+    let solution = HeldKarp::default()
+        .solve_with_observer(&tsp_instance, &SolverOptions::default(), &observer)
+        .unwrap();
+    drop(observer);
+    assert_eq!(solution.termination, TerminationReason::Optimal);
+    assert_eq!(solution.cost(), Some(Distance(426)));
+
+    let mut incumbents = Vec::new();
+    let mut lower_bounds = Vec::new();
+    let mut nodes = Vec::new();
+    for event in events {
+        match event {
+            SolverEvent::NewIncumbent(tour) => incumbents.push(tour.cost),
+            SolverEvent::LowerBound(bound) => lower_bounds.push(bound),
+            SolverEvent::Node(progress) => nodes.push(progress),
+        }
+    }
+
+    assert!(incumbents.is_sorted_by(|a, b| a > b), "{incumbents:?}");
+    assert_eq!(incumbents.last(), Some(&Distance(426)));
+    assert!(lower_bounds.is_sorted_by(|a, b| a < b), "{lower_bounds:?}");
+    assert_eq!(lower_bounds.last(), Some(&Distance(426)));
+    // The root bound is reported before the search finishes
+    assert!(lower_bounds.len() > 1);
+
+    assert_eq!(nodes.len(), solution.stats.nodes);
+    assert_eq!(nodes[0].nodes, 1);
+    assert_eq!(nodes[0].depth, 0);
+    assert!(
+        nodes
+            .iter()
+            .enumerate()
+            .all(|(index, progress)| progress.nodes == index + 1)
+    );
+    let last = nodes.last().unwrap();
+    assert!(last.pruned <= solution.stats.pruned);
+    assert!(last.infeasible <= solution.stats.infeasible);
+}
+
+#[test]
+fn test_channel_observer_throttles_node_events() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/eil51.tsp").unwrap();
+    let (observer, events) = ChannelObserver::new();
+    let observer = observer.with_node_interval(10);
+    let solution = HeldKarp::default()
+        .solve_with_observer(&tsp_instance, &SolverOptions::default(), &observer)
+        .unwrap();
+    drop(observer);
+
+    let nodes: Vec<_> = events
+        .into_iter()
+        .filter_map(|event| match event {
+            SolverEvent::Node(progress) => Some(progress.nodes),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(nodes.len(), solution.stats.nodes.div_ceil(10));
+    assert!(
+        nodes
+            .iter()
+            .enumerate()
+            .all(|(index, &nodes)| nodes == 10 * index + 1)
+    );
+}
+
+#[test]
+fn test_closure_observer_in_parallel_solver() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/eil51.tsp").unwrap();
+    let node_events = AtomicUsize::new(0);
+    let best_cost = AtomicUsize::new(usize::MAX);
+    let observer = |event: SolverEvent<Distance>| match event {
+        SolverEvent::Node(_) => {
+            node_events.fetch_add(1, Ordering::Relaxed);
+        }
+        SolverEvent::NewIncumbent(tour) => {
+            let previous = best_cost.swap(tour.cost.0 as usize, Ordering::Relaxed);
+            assert!((tour.cost.0 as usize) < previous);
+        }
+        SolverEvent::LowerBound(_) => {}
+    };
+
+    let solution = HeldKarpParallel::default()
+        .solve_with_observer(&tsp_instance, &SolverOptions::default(), &observer)
+        .unwrap();
+    assert_eq!(node_events.into_inner(), solution.stats.nodes);
+    assert_eq!(best_cost.into_inner(), solution.cost().unwrap().0 as usize);
+    assert_eq!(solution.cost(), Some(Distance(426)));
+}
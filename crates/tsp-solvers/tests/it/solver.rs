@@ -0,0 +1,280 @@
+use std::time::Duration;
+
+use tsp_core::instance::{
+    TSPSymInstance,
+    distance::{Distance, FloatDistance},
+    matrix::MatrixSym,
+    node::Node,
+    oracle::DistanceOracle,
+    tour::{Tour, TourError},
+};
+use tsp_solvers::{
+    held_karp_mod::{HeldKarp, HeldKarpConfig, HeldKarpConfigError, HeldKarpParallel},
+    heuristics::nearest_neighbor_two_opt,
+    solver::{
+        CancellationToken, SOLVER_NAMES, Solver, SolverOptions, SolverOptionsError,
+        TerminationReason, solver_by_name,
+    },
+};
+
+#[test]
+fn test_solvers_by_name_on_12() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+
+    for &name in SOLVER_NAMES {
+        let solver = solver_by_name(name).unwrap();
+        assert_eq!(solver.name(), name);
+
+        let solution = solver
+            .solve(&tsp_instance, &SolverOptions::default())
+            .unwrap();
+        assert_eq!(solution.cost(), Some(Distance(1200)), "{name}");
+        assert_eq!(solution.lower_bound, Some(Distance(1200)), "{name}");
+        assert_eq!(solution.termination, TerminationReason::Optimal);
+        assert!(solution.is_optimal());
+        assert_eq!(solution.gap(), Some(0.0));
+        assert!(solution.stats.nodes >= 1, "{name}");
+    }
+
+    assert!(solver_by_name::<TSPSymInstance<MatrixSym<Distance>>>("concorde").is_none());
+}
+
+#[test]
+fn test_solver_on_float_distances() {
+    let tsp_instance: TSPSymInstance<MatrixSym<FloatDistance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    let solver = solver_by_name("held-karp").unwrap();
+    let solution = solver
+        .solve(tsp_instance.distances(), &SolverOptions::default())
+        .unwrap();
+    assert_eq!(solution.cost(), Some(FloatDistance(1200.0)));
+}
+
+#[test]
+fn test_held_karp_config_presets_on_12() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+
+    for config in [
+        HeldKarpConfig::fast(),
+        HeldKarpConfig::default(),
+        HeldKarpConfig::thorough(),
+        HeldKarpConfig {
+            max_threads: 1,
+            ..HeldKarpConfig::default()
+        },
+    ] {
+        let options = SolverOptions::default();
+        let solution = HeldKarp::new(config.clone())
+            .unwrap()
+            .solve(&tsp_instance, &options)
+            .unwrap();
+        assert_eq!(solution.cost(), Some(Distance(1200)), "{config:?}");
+
+        let solution = HeldKarpParallel::new(config.clone())
+            .unwrap()
+            .solve(&tsp_instance, &options)
+            .unwrap();
+        assert_eq!(solution.cost(), Some(Distance(1200)), "{config:?}");
+    }
+
+    let config = HeldKarpConfig {
+        initial_beta: 0.0,
+        ..HeldKarpConfig::default()
+    };
+    assert_eq!(
+        HeldKarp::new(config).unwrap_err(),
+        HeldKarpConfigError::InvalidBeta {
+            field: "initial_beta",
+            value: 0.0
+        }
+    );
+}
+
+#[test]
+fn test_solver_limits_on_eil51() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/eil51.tsp").unwrap();
+
+    for &name in SOLVER_NAMES {
+        let solver = solver_by_name(name).unwrap();
+
+        let options = SolverOptions {
+            node_limit: Some(1),
+            ..SolverOptions::default()
+        };
+        let solution = solver.solve(&tsp_instance, &options).unwrap();
+        assert_eq!(solution.termination, TerminationReason::NodeLimit, "{name}");
+        assert_eq!(solution.stats.nodes, 1, "{name}");
+        assert!(!solution.is_optimal());
+        // Only the root was explored, so the proven bound is the Held-Karp bound of the root
+        let lower_bound = solution.lower_bound.unwrap();
+        assert!(lower_bound <= Distance(426), "{name}: {lower_bound}");
+        assert!(
+            lower_bound.0 as f64 >= 0.98 * 426.0,
+            "{name}: {lower_bound}"
+        );
+        assert!(solution.cost().unwrap() >= lower_bound);
+        assert!(solution.gap().unwrap() >= 0.0);
+
+        let cancellation = CancellationToken::new();
+        cancellation.clone().cancel();
+        let options = SolverOptions {
+            cancellation: Some(cancellation),
+            ..SolverOptions::default()
+        };
+        let solution = solver.solve(&tsp_instance, &options).unwrap();
+        assert_eq!(solution.termination, TerminationReason::Cancelled, "{name}");
+        assert_eq!(solution.stats.nodes, 0);
+        assert_eq!(solution.lower_bound, None);
+        assert!(solution.tour.is_some());
+
+        let options = SolverOptions::default().with_time_limit(Duration::ZERO);
+        let solution = solver.solve(&tsp_instance, &options).unwrap();
+        assert_eq!(solution.termination, TerminationReason::TimeLimit, "{name}");
+    }
+}
+
+#[test]
+fn test_solver_generous_limits_on_12() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsp_rust/12.tsp").unwrap();
+    let options = SolverOptions {
+        node_limit: Some(1_000_000),
+        cancellation: Some(CancellationToken::new()),
+        ..SolverOptions::default()
+    }
+    .with_time_limit(Duration::from_secs(600));
+
+    for &name in SOLVER_NAMES {
+        let solution = solver_by_name(name)
+            .unwrap()
+            .solve(&tsp_instance, &options)
+            .unwrap();
+        assert_eq!(solution.termination, TerminationReason::Optimal, "{name}");
+        assert_eq!(solution.cost(), Some(Distance(1200)));
+    }
+}
+
+#[test]
+fn test_parallel_solver_with_one_thread_on_ulysses16() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/ulysses16.tsp").unwrap();
+    let config = HeldKarpConfig {
+        max_threads: 1,
+        ..HeldKarpConfig::default()
+    };
+
+    // Without other threads, the parallel search explores the same tree as the sequential one
+    let options = SolverOptions::default();
+    let sequential = HeldKarp::new(config.clone())
+        .unwrap()
+        .solve(&tsp_instance, &options)
+        .unwrap();
+    let parallel = HeldKarpParallel::new(config)
+        .unwrap()
+        .solve(&tsp_instance, &options)
+        .unwrap();
+    assert!(sequential.is_optimal());
+    assert!(parallel.is_optimal());
+    assert_eq!(parallel.cost(), sequential.cost());
+    assert_eq!(parallel.stats.nodes, sequential.stats.nodes);
+}
+
+#[test]
+fn test_solver_initial_tour_and_upper_bound_on_fri26() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/fri26.tsp").unwrap();
+    let dimension = tsp_instance.dimension();
+    // The cost is recomputed by the solvers
+    let identity_tour = Tour::new((0..dimension).map(Node).collect(), Distance(0)).unwrap();
+
+    for &name in SOLVER_NAMES {
+        let solver = solver_by_name(name).unwrap();
+        let heuristic_start = solver
+            .solve(&tsp_instance, &SolverOptions::default())
+            .unwrap();
+        assert_eq!(heuristic_start.cost(), Some(Distance(937)), "{name}");
+
+        let options = SolverOptions {
+            initial_tour: Some(identity_tour.clone()),
+            ..SolverOptions::default()
+        };
+        let identity_start = solver.solve(&tsp_instance, &options).unwrap();
+        assert_eq!(identity_start.cost(), Some(Distance(937)), "{name}");
+        assert!(identity_start.is_optimal());
+        if name == HeldKarp::NAME {
+            // Starting from a good tour prunes more of the search tree
+            assert!(heuristic_start.stats.nodes < identity_start.stats.nodes);
+        }
+
+        let options = SolverOptions {
+            upper_bound: Some(Distance(937)),
+            ..SolverOptions::default()
+        };
+        let solution = solver.solve(&tsp_instance, &options).unwrap();
+        assert_eq!(solution.cost(), Some(Distance(937)), "{name}");
+        assert!(solution.is_optimal());
+    }
+}
+
+#[test]
+fn test_solver_upper_bound_below_optimum_on_fri26() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/fri26.tsp").unwrap();
+
+    // The optimum is 937, so no tour satisfies the bound
+    let options = SolverOptions {
+        upper_bound: Some(Distance(936)),
+        ..SolverOptions::default()
+    };
+    for &name in SOLVER_NAMES {
+        let solver = solver_by_name(name).unwrap();
+        let solution = solver.solve(&tsp_instance, &options).unwrap();
+        assert_eq!(
+            solution.termination,
+            TerminationReason::UpperBoundTooLow,
+            "{name}"
+        );
+        assert!(!solution.is_optimal());
+        assert!(solution.cost().unwrap() > Distance(936), "{name}");
+        let lower_bound = solution.lower_bound.unwrap();
+        assert!(lower_bound >= Distance(936), "{name}");
+        assert!(lower_bound <= Distance(937), "{name}");
+    }
+}
+
+#[test]
+fn test_solver_invalid_initial_tour_on_fri26() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/fri26.tsp").unwrap();
+    let options = SolverOptions {
+        initial_tour: Some(Tour::new((0..10).map(Node).collect(), Distance(0)).unwrap()),
+        ..SolverOptions::default()
+    };
+    let expected = SolverOptionsError::InvalidInitialTour(TourError::DimensionMismatch {
+        expected: 26,
+        found: 10,
+    });
+    assert_eq!(
+        options.validate(tsp_instance.dimension()),
+        Err(expected.clone())
+    );
+
+    for &name in SOLVER_NAMES {
+        let solver = solver_by_name(name).unwrap();
+        let result = solver.solve(&tsp_instance, &options);
+        assert_eq!(result.err(), Some(expected.clone()), "{name}");
+    }
+}
+
+#[test]
+fn test_nearest_neighbor_two_opt_on_berlin52() {
+    let tsp_instance: TSPSymInstance<MatrixSym<Distance>> =
+        tsp_parser::parse_tsp_instance("../../instances/tsplib_symmetric/berlin52.tsp").unwrap();
+    let tour = nearest_neighbor_two_opt(&tsp_instance);
+    assert_eq!(tour.check_dimension(tsp_instance.dimension()), Ok(()));
+    assert!(tour.cost() >= Distance(7542));
+    assert!(tour.cost().0 as f64 <= 1.1 * 7542.0, "{}", tour.cost());
+}